@@ -0,0 +1,107 @@
+//! Derive macro for packed bit layouts
+//!
+//! This crate is re-exported by bitlab with the feature **derive**. See bitlab::BitLayout.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitInt, Type};
+
+/// Generates from_bytes(), to_bytes() and BIT_LEN for a struct, whose fields are packed
+/// one after the other in the order of their declaration. Each field needs a
+/// `#[bits(n)]` attribute with its width, except for bool fields, which take one bit.
+/// The fields may have any integer type up to 128 bits or bool.
+#[proc_macro_derive(BitLayout, attributes(bits))]
+pub fn derive_bit_layout(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match expand(&input) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into(),
+	}
+}
+
+// Returns true if the type is bool
+fn is_bool(ty: &Type) -> bool {
+	match ty {
+		Type::Path(p) => p.qself.is_none() && p.path.is_ident("bool"),
+		_ => false,
+	}
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+	let fields = match &input.data {
+		Data::Struct(s) => match &s.fields {
+			Fields::Named(named) => &named.named,
+			_ => return Err(Error::new(Span::call_site(), "BitLayout needs a struct with named fields")),
+		},
+		_ => return Err(Error::new(Span::call_site(), "BitLayout can only be derived for structs")),
+	};
+
+	let mut offset: u32 = 0;
+	let mut reads = Vec::new();
+	let mut writes = Vec::new();
+	for field in fields {
+		let name = field.ident.as_ref().expect("named fields have an identifier");
+		let ty = &field.ty;
+
+		let mut width = None;
+		for attr in field.attrs.iter().filter(|a| a.path().is_ident("bits")) {
+			let lit: LitInt = attr.parse_args()?;
+			let n: u32 = lit.base10_parse()?;
+			if n == 0 || n > 128 {
+				return Err(Error::new_spanned(lit, "The width must be between 1 and 128 bits"));
+			}
+			width = Some(n);
+		}
+		let width = match width {
+			Some(n) => n,
+			None if is_bool(ty) => 1,
+			None => return Err(Error::new_spanned(field, "The field needs a #[bits(n)] attribute")),
+		};
+
+		if is_bool(ty) {
+			reads.push(quote! {
+				#name: <[u8] as ::bitlab::ExtractBitsFromVecU8>::get_u128(bytes, 0, #offset, #width)? != 0
+			});
+			writes.push(quote! {
+				::bitlab::InsertBitsIntoVecU8::set(&mut bytes[..], 0, #offset, #width, self.#name as u8)?;
+			});
+		} else {
+			reads.push(quote! {
+				#name: <[u8] as ::bitlab::ExtractBitsFromVecU8>::get_at::<#ty>(bytes, 0, #offset, #width)?
+			});
+			writes.push(quote! {
+				::bitlab::InsertBitsIntoVecU8::set(&mut bytes[..], 0, #offset, #width, self.#name)?;
+			});
+		}
+		offset = offset.checked_add(width).ok_or_else(|| Error::new_spanned(field, "The layout is too long"))?;
+	}
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let n_bytes = offset.div_ceil(8) as usize;
+
+	Ok(quote! {
+		impl #impl_generics #name #ty_generics #where_clause {
+			/// The number of bits of the packed layout
+			pub const BIT_LEN: u32 = #offset;
+
+			/// Extracts the fields from the packed layout at the start of bytes
+			pub fn from_bytes(bytes: &[u8]) -> ::std::result::Result<Self, ::std::string::String> {
+				::std::result::Result::Ok(#name {
+					#( #reads, )*
+				})
+			}
+
+			/// Packs the fields into bytes. The unused bits of the last byte are zero.
+			/// Returns an error if a value does not fit into its field.
+			pub fn to_bytes(&self) -> ::std::result::Result<::std::vec::Vec<u8>, ::std::string::String> {
+				let mut bytes = ::std::vec![0u8; #n_bytes];
+				#( #writes )*
+				::std::result::Result::Ok(bytes)
+			}
+		}
+	})
+}
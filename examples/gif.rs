@@ -1,19 +1,19 @@
 extern crate bitlab;
 
 use bitlab::*;
-use std::io;
 use std::fs::File;
-use std::io::prelude::*;
 
 fn main() {
 	// Thanks to http://www.matthewflickinger.com/lab/whatsinagif/bits_and_bytes.asp
 	let file_name = "examples/sample_1.gif";
-	match read_file(file_name) {
-		Ok(vec) => {
-			match vec.get_u8(10, 1, 3) {
+	match File::open(file_name) {
+		Ok(file) => {
+			// Read the bits directly from the file without loading it into a Vec
+			let mut source = BitSource::new(file);
+			let color_resolution = source.skip_bits(10 * 8 + 1).and_then(|_| source.read_bits(3));
+			match color_resolution {
 				Ok(color_resolution) => {
-					println!("The packed byte is at byte offset 10 = 0x{:X} = {:b}", vec[10], vec[10]);
-					println!("The color resolution starts at \
+					println!("The color resolution starts at byte offset 10, \
 						bit offset 1 and is 3 bits long: (binary)");
 					println!("binary  : {:03b}", color_resolution);
 					println!("decimal : {}", color_resolution);
@@ -24,13 +24,3 @@ fn main() {
 		Err(e) => panic!("Unable to open {} {:?}", file_name, e)
 	}
 }
-
-fn read_file(path: &str) -> Result<Vec<u8>, io::Error> {
-	let mut f = File::open(path)?;	// If that fails return early and pass the error message
-	let metadata = f.metadata()?;	// The ? is the same as the try!()
-	let size = metadata.len() as usize;
-	let mut v: Vec<u8> = Vec::new();
-	v.resize(size, 0);
-	f.read_exact(&mut v)?;
-	Ok(v)
-}
@@ -0,0 +1,39 @@
+// Compares the insertion of 32 bit fields into a vector with a bit by bit insertion,
+// which is how InsertBitsIntoVecU8::set used to work.
+//
+// cargo run --release --example set_performance
+
+use bitlab::*;
+use std::time::{Duration, Instant};
+
+// Inserts the length least significant bits of value one bit at a time
+fn set_bit_by_bit(v: &mut Vec<u8>, byte_offset: u32, bit_offset: u32, length: u32, value: u32) -> Result<(), String> {
+	for i in 0..length {
+		let bit = value.get_bit(32 - length + i)?;
+		v.assign_bit(byte_offset, bit_offset + i, bit)?;
+	}
+	Ok(())
+}
+
+fn measure<F: FnMut(u32)>(n: u32, mut f: F) -> Duration {
+	let now = Instant::now();
+	for i in 0..n {
+		f(i);
+	}
+	now.elapsed()
+}
+
+fn main() {
+	let n = 1_000_000;
+	let mut v: Vec<u8> = vec!{ 0; 64 };
+
+	let masked = measure(n, |i| v.set(i % 32, 3, 32, i).unwrap());
+	let reference = v.clone();
+
+	let bit_by_bit = measure(n, |i| set_bit_by_bit(&mut v, i % 32, 3, 32, i).unwrap());
+	assert_eq!(v, reference);
+
+	println!("set:          {:?} for {} runs", masked, n);
+	println!("bit by bit:   {:?} for {} runs", bit_by_bit, n);
+	println!("speedup:      {:.1}x", bit_by_bit.as_secs_f64() / masked.as_secs_f64());
+}
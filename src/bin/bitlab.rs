@@ -0,0 +1,237 @@
+//! Command line interface (requires the feature **cli**)
+//!
+//! ```cli
+//! bitlab get file.bin --byte 10 --bit 1 --len 3 --as u8 --format bin
+//! bitlab set file.bin --byte 10 --bit 1 --len 3 --value 0b101
+//! bitlab dump file.bin --byte 16 --len 32
+//! ```
+
+use bitlab::*;
+use std::convert::TryFrom;
+use std::process;
+
+static USAGE: &str = "Usage:
+  bitlab get <file> --byte <n> --bit <n> --len <n> [--as <type>] [--format <format>]
+      Extracts len bits starting at the given byte and bit offset and prints them
+      as u8, i8, u16, i16, u32, i32, u64 or i64 (default u64)
+      in the format dec, hex, bin or all (default dec)
+  bitlab set <file> --byte <n> --bit <n> --len <n> --value <value>
+      Inserts the value (decimal, 0x hex or 0b binary, optionally negative) into
+      len bits starting at the given byte and bit offset and writes the file
+  bitlab dump <file> [--byte <n>] [--len <n>]
+      Prints a hexdump of len bytes (default all) starting at the given byte offset";
+
+type Result<T> = std::result::Result<T, String>;
+
+// The options following the file name
+struct Options {
+	byte: u32,
+	bit: u32,
+	len: Option<u32>,
+	ty: String,
+	format: String,
+	value: Option<String>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options> {
+	let mut options = Options { byte: 0, bit: 0, len: None, ty: String::from("u64"), format: String::from("dec"), value: None };
+
+	let mut iter = args.iter();
+	while let Some(name) = iter.next() {
+		let value = iter.next().ok_or(format!("Missing value for {}", name))?;
+		let number = || value.parse::<u32>().map_err(|_| format!("Invalid value for {}: {}", name, value));
+		match name.as_str() {
+			"--byte" => options.byte = number()?,
+			"--bit"  => options.bit = number()?,
+			"--len"  => options.len = Some(number()?),
+			"--as"   => options.ty = value.clone(),
+			"--format" => options.format = value.clone(),
+			"--value" => options.value = Some(value.clone()),
+			_ => return Err(format!("Unknown option: {}", name)),
+		}
+	}
+
+	Ok(options)
+}
+
+fn get(data: &[u8], options: &Options) -> Result<String> {
+	let len = options.len.ok_or("Missing option --len")?;
+	let (byte, bit) = (options.byte, options.bit);
+	let decimal = match options.ty.as_str() {
+		"u8"  => data.get_u8 (byte, bit, len)?.to_string(),
+		"i8"  => data.get_i8 (byte, bit, len)?.to_string(),
+		"u16" => data.get_u16(byte, bit, len)?.to_string(),
+		"i16" => data.get_i16(byte, bit, len)?.to_string(),
+		"u32" => data.get_u32(byte, bit, len)?.to_string(),
+		"i32" => data.get_i32(byte, bit, len)?.to_string(),
+		"u64" => data.get_u64(byte, bit, len)?.to_string(),
+		"i64" => data.get_i64(byte, bit, len)?.to_string(),
+		ty => return Err(format!("Unknown type: {}", ty)),
+	};
+
+	// Hex and binary show the len bits of the field rather than the type
+	let bits = data.get_u64(byte, bit, len)?;
+	let hex = format!("0x{:0width$X}", bits, width = len.div_ceil(4) as usize);
+	let bin = format!("0b{:0width$b}", bits, width = len as usize);
+	Ok(match options.format.as_str() {
+		"dec" => decimal,
+		"hex" => hex,
+		"bin" => bin,
+		"all" => format!("{} {} {}", decimal, hex, bin),
+		format => return Err(format!("Unknown format: {}", format)),
+	})
+}
+
+// Parses a decimal, 0x hex or 0b binary number with an optional minus sign
+fn parse_value(text: &str) -> Result<i128> {
+	let (negative, digits) = match text.strip_prefix('-') {
+		Some(rest) => (true, rest),
+		None => (false, text),
+	};
+	let number = if let Some(hex) = digits.strip_prefix("0x") {
+		u64::from_str_radix(&hex.replace('_', ""), 16)
+	} else if let Some(bin) = digits.strip_prefix("0b") {
+		u64::from_str_radix(&bin.replace('_', ""), 2)
+	} else {
+		digits.parse::<u64>()
+	};
+	let number = number.map_err(|_| format!("Invalid value: {}", text))? as i128;
+	if negative { Ok(-number) } else { Ok(number) }
+}
+
+fn set(data: &mut Vec<u8>, options: &Options) -> Result<()> {
+	let len = options.len.ok_or("Missing option --len")?;
+	let value = parse_value(options.value.as_ref().ok_or("Missing option --value")?)?;
+	if value < 0 {
+		let value = i64::try_from(value).map_err(|_| format!("Invalid value: {}", value))?;
+		data.set(options.byte, options.bit, len, value)
+	} else {
+		data.set(options.byte, options.bit, len, value as u64)
+	}
+}
+
+fn dump(data: &[u8], options: &Options) -> Result<String> {
+	let start = options.byte as usize;
+	let end = match options.len {
+		Some(len) => start + len as usize,
+		None => data.len(),
+	};
+	if start > end || end > data.len() {
+		return Err(String::from("Out of range"));
+	}
+	Ok(text::hexdump(&data[start..end], start))
+}
+
+fn run(args: &[String]) -> Result<String> {
+	if args.len() < 2 {
+		return Err(String::from(USAGE));
+	}
+
+	let mut data = std::fs::read(&args[1]).map_err(|e| format!("Unable to read {}: {}", args[1], e))?;
+	let options = parse_options(&args[2..])?;
+
+	match args[0].as_str() {
+		"get"  => get(&data, &options),
+		"set"  => {
+			set(&mut data, &options)?;
+			std::fs::write(&args[1], &data).map_err(|e| format!("Unable to write {}: {}", args[1], e))?;
+			Ok(String::new())
+		},
+		"dump" => dump(&data, &options),
+		_ => Err(String::from(USAGE)),
+	}
+}
+
+fn main() {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	match run(&args) {
+		Ok(output) => {
+			if !output.is_empty() {
+				println!("{}", output.trim_end());
+			}
+		},
+		Err(e) => {
+			eprintln!("{}", e);
+			process::exit(1);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn options(args: &[&str]) -> Result<Options> {
+		parse_options(&args.iter().map(|a| a.to_string()).collect::<Vec<String>>())
+	}
+
+	#[test]
+	fn getting_fields() {
+		let data: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		assert_eq!(get(&data, &options(&["--byte", "1", "--bit", "7", "--len", "3"]).unwrap()).unwrap(), "5");
+		assert_eq!(get(&data, &options(&["--byte", "1", "--bit", "7", "--len", "3", "--as", "i8"]).unwrap()).unwrap(), "-3");
+		assert_eq!(get(&data, &options(&["--len", "0"]).unwrap()).unwrap_err(), "The length parameter must not be zero");
+		assert_eq!(get(&data, &options(&["--byte", "5", "--len", "1"]).unwrap()).unwrap_err(), "Out of range");
+		assert_eq!(get(&data, &options(&[]).unwrap()).unwrap_err(), "Missing option --len");
+		assert_eq!(get(&data, &options(&["--len", "1", "--as", "f32"]).unwrap()).unwrap_err(), "Unknown type: f32");
+
+		assert_eq!(options(&["--byte"]).err().unwrap(), "Missing value for --byte");
+		assert_eq!(options(&["--bit", "-1"]).err().unwrap(), "Invalid value for --bit: -1");
+		assert_eq!(options(&["--offset", "1"]).err().unwrap(), "Unknown option: --offset");
+	}
+
+	#[test]
+	fn formatting_fields() {
+		let data: Vec<u8> = vec!{ 0xFA, 0x50 };
+		let field = |format: &str| get(&data, &options(&["--bit", "4", "--len", "9", "--as", "i16", "--format", format]).unwrap());
+		assert_eq!(field("dec").unwrap(), "-182");
+		// Hex and binary show the 9 bits of the field with leading zeros
+		assert_eq!(field("hex").unwrap(), "0x14A");
+		assert_eq!(field("bin").unwrap(), "0b101001010");
+		assert_eq!(field("all").unwrap(), "-182 0x14A 0b101001010");
+		assert_eq!(field("oct").unwrap_err(), "Unknown format: oct");
+		assert_eq!(get(&data, &options(&["--len", "4", "--format", "hex"]).unwrap()).unwrap(), "0xF");
+	}
+
+	#[test]
+	fn parsing_values() {
+		assert_eq!(parse_value("1500"), Ok(1500));
+		assert_eq!(parse_value("0xBEEF"), Ok(0xBEEF));
+		assert_eq!(parse_value("0b1010_0101"), Ok(0xA5));
+		assert_eq!(parse_value("-0x80"), Ok(-128));
+		assert_eq!(parse_value("-5"), Ok(-5));
+		assert_eq!(parse_value("18446744073709551615"), Ok(u64::MAX as i128));
+		// Overflow and invalid digits
+		assert_eq!(parse_value("0x1_0000_0000_0000_0000").unwrap_err(), "Invalid value: 0x1_0000_0000_0000_0000");
+		assert!(parse_value("18446744073709551616").is_err());
+		assert!(parse_value("0b102").is_err());
+		assert!(parse_value("0xG").is_err());
+		assert!(parse_value("").is_err());
+	}
+
+	#[test]
+	fn setting_fields() {
+		let mut data: Vec<u8> = vec!{ 0x00, 0x00 };
+		set(&mut data, &options(&["--bit", "4", "--len", "9", "--value", "-182"]).unwrap()).unwrap();
+		assert_eq!(data, vec!{ 0x0A, 0x50 });
+		set(&mut data, &options(&["--len", "4", "--value", "0b1111"]).unwrap()).unwrap();
+		assert_eq!(data, vec!{ 0xFA, 0x50 });
+
+		// Nothing is modified on error
+		assert!(set(&mut data, &options(&["--len", "4", "--value", "16"]).unwrap()).is_err());
+		assert!(set(&mut data, &options(&["--len", "4", "--value", "-9"]).unwrap()).is_err());
+		assert_eq!(set(&mut data, &options(&["--len", "64", "--value", "-0x8000_0000_0000_0001"]).unwrap()).unwrap_err(),
+			"Invalid value: -9223372036854775809");
+		assert_eq!(set(&mut data, &options(&["--len", "4"]).unwrap()).unwrap_err(), "Missing option --value");
+		assert_eq!(data, vec!{ 0xFA, 0x50 });
+	}
+
+	#[test]
+	fn dumping_bytes() {
+		let data: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		assert_eq!(dump(&data, &options(&["--byte", "1", "--len", "2"]).unwrap()).unwrap(), text::hexdump(b"al", 1));
+		assert_eq!(dump(&data, &options(&[]).unwrap()).unwrap(), text::hexdump(&data, 0));
+		assert_eq!(dump(&data, &options(&["--byte", "4", "--len", "2"]).unwrap()).unwrap_err(), "Out of range");
+		assert_eq!(run(&[String::from("get")]).unwrap_err(), USAGE);
+	}
+}
@@ -0,0 +1,261 @@
+//! Borrowed views on a range of bits
+
+use super::{Result, BitBuffer, BitTarget, BitValue, ExtractBitsFromVecU8, LargeOffsets, LEN_TOO_BIG_MSG, check_bit_range, read_bits_from_slice, write_bits_to_slice};
+use super::offset64::split;
+
+/// A view on bit_len bits of a byte slice, which start at an arbitrary bit.
+///
+/// All indices are relative to the start of the view, where zero is its first bit.
+/// Like everywhere else in this crate, the bits of a byte are numbered starting at the
+/// **most** significant bit.
+///
+/// ```rust
+/// use bitlab::*;
+/// let frame = [0xA5u8, 0x0F, 0xF0];
+/// // A payload of 12 bits after a 4 bit header
+/// let payload = BitSlice::new(&frame, 4, 12).unwrap();
+/// assert_eq!(payload.read_bits(0, 12).unwrap(), 0x50F);
+/// assert_eq!(payload.get::<i8>(0, 4).unwrap(), 5);
+/// let (kind, rest) = payload.split_at_bit(4).unwrap();
+/// assert_eq!(kind.read_bits(0, 4).unwrap(), 5);
+/// assert_eq!(rest.bit_len(), 8);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BitSlice<'a> {
+	bytes: &'a [u8],
+	start: usize,
+	bit_len: usize,
+}
+
+impl<'a> BitSlice<'a> {
+	/// Creates a view on bit_len bits of bytes starting at the bit start.
+	/// Returns an error if the range exceeds the bytes.
+	pub fn new(bytes: &'a [u8], start: usize, bit_len: usize) -> Result<BitSlice<'a>> {
+		check_bit_range(start, bit_len, bytes.len().saturating_mul(8))?;
+		Ok(BitSlice { bytes, start, bit_len })
+	}
+
+	/// Returns the number of bits in the view
+	pub fn bit_len(&self) -> usize {
+		self.bit_len
+	}
+
+	/// Returns true if the view does not contain any bits
+	pub fn is_empty(&self) -> bool {
+		self.bit_len == 0
+	}
+
+	/// Reads length bits starting at the given bit index and returns them right aligned in a u64
+	pub fn read_bits(&self, index: usize, length: u32) -> Result<u64> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		check_bit_range(index, length as usize, self.bit_len)?;
+		Ok(read_bits_from_slice(self.bytes, self.start + index, length))
+	}
+
+	/// Extracts length bits starting at the given bit index as any integer type.
+	/// See ExtractBitsFromVecU8::get_at.
+	pub fn get<T: BitTarget>(&self, index: usize, length: u32) -> Result<T> {
+		check_bit_range(index, length as usize, self.bit_len)?;
+		let (rest, bit_offset) = split(self.bytes, (self.start + index) as u64)?;
+		rest.get_at(0, bit_offset, length)
+	}
+
+	/// Tests the bit at the given index
+	pub fn get_bit(&self, index: usize) -> Result<bool> {
+		Ok(self.read_bits(index, 1)? == 1)
+	}
+
+	/// Returns a view on bit_len bits of this view starting at the bit index start
+	pub fn slice(&self, start: usize, bit_len: usize) -> Result<BitSlice<'a>> {
+		check_bit_range(start, bit_len, self.bit_len)?;
+		Ok(BitSlice { bytes: self.bytes, start: self.start + start, bit_len })
+	}
+
+	/// Splits the view into the bits before mid and the bits from mid on
+	pub fn split_at_bit(&self, mid: usize) -> Result<(BitSlice<'a>, BitSlice<'a>)> {
+		check_bit_range(mid, 0, self.bit_len)?;
+		Ok((self.slice(0, mid)?, self.slice(mid, self.bit_len - mid)?))
+	}
+
+	/// Copies the bits of the view into a new buffer
+	pub fn to_buffer(&self) -> BitBuffer {
+		let mut buffer = BitBuffer::new();
+		let mut index = 0;
+		while index < self.bit_len {
+			let n = std::cmp::min(64, self.bit_len - index) as u32;
+			// Cannot fail, since n <= 64
+			let _ = buffer.push_bits(read_bits_from_slice(self.bytes, self.start + index, n), n);
+			index += n as usize;
+		}
+		buffer
+	}
+}
+
+impl<'a> From<&'a [u8]> for BitSlice<'a> {
+	/// Creates a view on all bits of the bytes
+	fn from(bytes: &'a [u8]) -> BitSlice<'a> {
+		BitSlice { bytes, start: 0, bit_len: bytes.len() * 8 }
+	}
+}
+
+impl<'a> From<&'a BitBuffer> for BitSlice<'a> {
+	/// Creates a view on all bits of the buffer
+	fn from(buffer: &'a BitBuffer) -> BitSlice<'a> {
+		BitSlice { bytes: buffer.as_bytes(), start: 0, bit_len: buffer.bit_len() }
+	}
+}
+
+/// A mutable view on bit_len bits of a byte slice, which start at an arbitrary bit.
+///
+/// Writes never touch the bits outside of the view, even if they share a byte with it.
+/// Since two views split at a bit in the middle of a byte would share that byte,
+/// there is no mutable split_at_bit. Use slice_mut to work on a part of the view instead.
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut frame = [0xFFu8, 0xFF];
+/// let mut payload = BitSliceMut::new(&mut frame, 3, 10).unwrap();
+/// payload.write_bits(0, 10, 0).unwrap();
+/// payload.slice_mut(8, 2).unwrap().write_bits(0, 2, 0b11).unwrap();
+/// payload.set(2, 3, -1i8).unwrap();
+/// assert_eq!(frame, [0b1110_0111, 0b0001_1111]);
+/// ```
+#[derive(Debug)]
+pub struct BitSliceMut<'a> {
+	bytes: &'a mut [u8],
+	start: usize,
+	bit_len: usize,
+}
+
+impl<'a> BitSliceMut<'a> {
+	/// Creates a mutable view on bit_len bits of bytes starting at the bit start.
+	/// Returns an error if the range exceeds the bytes.
+	pub fn new(bytes: &'a mut [u8], start: usize, bit_len: usize) -> Result<BitSliceMut<'a>> {
+		check_bit_range(start, bit_len, bytes.len().saturating_mul(8))?;
+		Ok(BitSliceMut { bytes, start, bit_len })
+	}
+
+	/// Returns the number of bits in the view
+	pub fn bit_len(&self) -> usize {
+		self.bit_len
+	}
+
+	/// Returns true if the view does not contain any bits
+	pub fn is_empty(&self) -> bool {
+		self.bit_len == 0
+	}
+
+	/// Returns a read only view on the same bits
+	pub fn as_bit_slice(&self) -> BitSlice<'_> {
+		BitSlice { bytes: self.bytes, start: self.start, bit_len: self.bit_len }
+	}
+
+	/// Reads length bits starting at the given bit index and returns them right aligned in a u64
+	pub fn read_bits(&self, index: usize, length: u32) -> Result<u64> {
+		self.as_bit_slice().read_bits(index, length)
+	}
+
+	/// Extracts length bits starting at the given bit index as any integer type. See BitSlice::get.
+	pub fn get<T: BitTarget>(&self, index: usize, length: u32) -> Result<T> {
+		self.as_bit_slice().get(index, length)
+	}
+
+	/// Tests the bit at the given index
+	pub fn get_bit(&self, index: usize) -> Result<bool> {
+		self.as_bit_slice().get_bit(index)
+	}
+
+	/// Inserts value into length bits starting at the given bit index. See InsertBitsIntoVecU8::set.
+	pub fn set<T: BitValue>(&mut self, index: usize, length: u32, value: T) -> Result<()> {
+		check_bit_range(index, length as usize, self.bit_len)?;
+		self.bytes.set_at((self.start + index) as u64, length, value)
+	}
+
+	/// Writes the length least significant bits of value starting at the given bit index
+	pub fn write_bits(&mut self, index: usize, length: u32, value: u64) -> Result<()> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		check_bit_range(index, length as usize, self.bit_len)?;
+		write_bits_to_slice(self.bytes, self.start + index, length, value);
+		Ok(())
+	}
+
+	/// Sets the bit at the given index if bit is true or clears it otherwise
+	pub fn assign_bit(&mut self, index: usize, bit: bool) -> Result<()> {
+		self.write_bits(index, 1, bit as u64)
+	}
+
+	/// Returns a mutable view on bit_len bits of this view starting at the bit index start
+	pub fn slice_mut(&mut self, start: usize, bit_len: usize) -> Result<BitSliceMut<'_>> {
+		check_bit_range(start, bit_len, self.bit_len)?;
+		Ok(BitSliceMut { bytes: self.bytes, start: self.start + start, bit_len })
+	}
+}
+
+impl<'a> From<&'a mut [u8]> for BitSliceMut<'a> {
+	/// Creates a mutable view on all bits of the bytes
+	fn from(bytes: &'a mut [u8]) -> BitSliceMut<'a> {
+		let bit_len = bytes.len() * 8;
+		BitSliceMut { bytes, start: 0, bit_len }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn views_on_bits() {
+		let data = [0x48u8, 0x61, 0x6C, 0x6C, 0x6F];
+		let all = BitSlice::from(&data[..]);
+		assert_eq!(all.bit_len(), 40);
+
+		let view = all.slice(15, 10).unwrap();
+		assert_eq!(view.read_bits(0, 3).unwrap(), 5);
+		assert!(view.get_bit(0).unwrap());
+		assert!(view.read_bits(8, 3).is_err());
+		assert_eq!(view.slice(9, 2).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		let (head, tail) = view.split_at_bit(3).unwrap();
+		assert_eq!((head.bit_len(), tail.bit_len()), (3, 7));
+		assert_eq!(tail.read_bits(0, 7).unwrap(), 0b101_1000);
+		assert!(view.split_at_bit(11).is_err());
+		let (empty, _) = view.split_at_bit(0).unwrap();
+		assert!(empty.is_empty());
+
+		let copy = view.to_buffer();
+		assert_eq!(copy.bit_len(), 10);
+		assert_eq!(copy.as_bytes(), &[0b1011_0110, 0b0000_0000]);
+		assert!(BitSlice::new(&data, 33, 8).is_err());
+		assert_eq!(BitSlice::from(&copy).read_bits(0, 10).unwrap(), 0b10_1101_1000);
+
+		let mut bytes = [0u8; 3];
+		let mut m = BitSliceMut::new(&mut bytes, 6, 12).unwrap();
+		m.write_bits(0, 12, 0xFFF).unwrap();
+		m.assign_bit(11, false).unwrap();
+		assert_eq!(m.read_bits(8, 4).unwrap(), 0b1110);
+		assert!(m.write_bits(1, 12, 0).is_err());
+		{
+			let mut inner = m.slice_mut(2, 4).unwrap();
+			inner.write_bits(0, 4, 0).unwrap();
+			assert!(inner.assign_bit(4, true).is_err());
+		}
+		assert!(!m.get_bit(2).unwrap());
+		assert_eq!(bytes, [0b0000_0011, 0b0000_1111, 0b1000_0000]);
+
+		// Typed access
+		let mut m = BitSliceMut::new(&mut bytes, 6, 12).unwrap();
+		m.set(0, 5, -3i8).unwrap();
+		assert_eq!(m.get::<i16>(0, 5).unwrap(), -3);
+		assert_eq!(m.get::<u128>(0, 12).unwrap(), 0b1110_1011_1110);
+		assert!(m.set(0, 4, 16u8).is_err());
+		assert_eq!(m.set(10, 3, 0u8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(m.get::<u8>(9, 4).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(bytes, [0b0000_0011, 0b1010_1111, 0b1000_0000]);
+		assert_eq!(BitSlice::from(&bytes[..]).get::<u32>(14, 4).unwrap(), 0b1110);
+	}
+}
@@ -0,0 +1,433 @@
+//! An owned, growable sequence of bits
+
+use super::{Result, OUT_OF_RANGE_MSG, LEN_ZERO, LEN_TOO_BIG_MSG, read_bits_from_slice, write_bits_to_slice, check_bit_range, check_value_fits, bulk};
+
+static INVALID_DIGIT_MSG: &str = "Invalid digit: ";
+
+// Returns the characters of a literal without the prefix, underscores and whitespace
+fn literal_digits<'a>(text: &'a str, prefix: &'a str) -> impl Iterator<Item = char> + 'a {
+	let text = text.trim_start();
+	text.strip_prefix(prefix).unwrap_or(text).chars().filter(|&c| c != '_' && !c.is_whitespace())
+}
+
+/// Builds a BitBuffer from (value, width) pairs, which are appended one after the other.
+///
+/// Each value is converted with `as u64` and its width least significant bits are appended, so bools,
+/// unsigned and signed integers (in two's complement) can be mixed. The macro is meant to construct
+/// packed test data and panics if a width exceeds 64 or a value does not fit into its width as an
+/// unsigned or a signed integer, e.g. (0x1FF, 8). Use into_bytes for a Vec<u8>.
+///
+/// ```rust
+/// use bitlab::*;
+/// let b = bits![ (0b010u8, 3), (0x1FFu16, 9), (true, 1), (-1i8, 3) ];
+/// assert_eq!(b.bit_len(), 16);
+/// assert_eq!(b.into_bytes(), vec!{ 0b0101_1111, 0b1111_1111 });
+/// ```
+#[macro_export]
+macro_rules! bits {
+	( $( ( $value:expr, $width:expr ) ),* $(,)? ) => {{
+		#[allow(unused_mut)]
+		let mut buffer = $crate::BitBuffer::new();
+		$(
+			let (value, width): (i128, u32) = (($value) as i128, $width);
+			if width > 64 {
+				panic!("The width of a field in bits! exceeds 64");
+			}
+			if value >= 1 << width || value < -(1 << width >> 1) {
+				panic!("The value {} does not fit into {} bits in bits!", value, width);
+			}
+			buffer.push_bits(value as u64, width).unwrap();
+		)*
+		buffer
+	}};
+}
+
+/// A sequence of bits with an exact bit length, which is not necessarily a multiple of 8.
+///
+/// The bits are stored big endian in a Vec<u8>, i.e. bit index zero is the
+/// **most** significant bit of the first byte. The unused bits of the last byte are always zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitBuffer {
+	bytes: Vec<u8>,
+	bit_len: usize,
+}
+
+impl BitBuffer {
+	/// Creates an empty buffer
+	pub fn new() -> BitBuffer {
+		BitBuffer { bytes: Vec::new(), bit_len: 0 }
+	}
+
+	/// Creates a buffer from the first bit_len bits of a byte vector.
+	///
+	/// The bits beyond bit_len are cleared. Returns an error if the vector has less than bit_len bits.
+	pub fn from_bytes(mut bytes: Vec<u8>, bit_len: usize) -> Result<BitBuffer> {
+		if bit_len > bytes.len() * 8 {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+
+		bytes.truncate(bit_len.div_ceil(8));
+		let mut buffer = BitBuffer { bytes, bit_len };
+		buffer.clear_padding();
+		Ok(buffer)
+	}
+
+	/// Returns the number of bits in the buffer
+	pub fn bit_len(&self) -> usize {
+		self.bit_len
+	}
+
+	/// Returns true if the buffer does not contain any bits
+	pub fn is_empty(&self) -> bool {
+		self.bit_len == 0
+	}
+
+	/// Returns the underlying bytes. The unused bits of the last byte are zero.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Consumes the buffer and returns the underlying bytes
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+
+	/// Appends a single bit at the end of the buffer
+	pub fn push_bit(&mut self, bit: bool) {
+		if self.bit_len.is_multiple_of(8) {
+			self.bytes.push(0);
+		}
+		if bit {
+			let last = self.bytes.len() - 1;
+			self.bytes[last] |= 0b1000_0000 >> (self.bit_len % 8);
+		}
+		self.bit_len += 1;
+	}
+
+	// Appends n zero bits at the end of the buffer. The unused bits of the last byte are already zero.
+	pub(crate) fn push_zeros(&mut self, n: usize) {
+		self.bit_len += n;
+		self.bytes.resize(self.bit_len.div_ceil(8), 0);
+	}
+
+	/// Appends the length least significant bits of value at the end of the buffer
+	pub fn push_bits(&mut self, value: u64, length: u32) -> Result<()> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		for i in (0..length).rev() {
+			self.push_bit(value & (1 << i) != 0);
+		}
+		Ok(())
+	}
+
+	/// Reads length bits starting at the given bit index and returns them right aligned in a u64
+	pub fn read_bits(&self, index: usize, length: u32) -> Result<u64> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		check_bit_range(index, length as usize, self.bit_len)?;
+		Ok(read_bits_from_slice(&self.bytes, index, length))
+	}
+
+	/// Tests the bit at the given index, where zero is the **most** significant bit of the first byte
+	pub fn get_bit(&self, index: usize) -> Result<bool> {
+		if index >= self.bit_len {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		Ok(self.bytes[index / 8] & (0b1000_0000 >> (index % 8)) != 0)
+	}
+
+	/// Shifts all bits of the buffer by amount bits towards the first bit.
+	/// The length of the buffer does not change and the last amount bits are cleared.
+	pub fn shl_bits(&mut self, amount: usize) {
+		bulk::shl_bits(&mut self.bytes, amount);
+	}
+
+	/// Shifts all bits of the buffer by amount bits towards the last bit.
+	/// The length of the buffer does not change and the first amount bits are cleared.
+	pub fn shr_bits(&mut self, amount: usize) {
+		bulk::shr_bits(&mut self.bytes, amount);
+		self.clear_padding();
+	}
+
+	/// Inserts the length least significant bits of value before the bit at_bit, so that the
+	/// following bits are moved towards the end and the buffer grows by length bits. at_bit may
+	/// be the length of the buffer to append the bits. Convert a Vec<u8> with BitBuffer::from to
+	/// edit it. Returns an error if length is zero or value requires more than length bits.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut b = BitBuffer::from(vec!{ 0b1111_0000 });
+	/// b.insert_bits(4, 0b101, 3).unwrap();
+	/// assert_eq!(b.bit_len(), 11);
+	/// assert_eq!(b.as_bytes(), &[0b1111_1010, 0b0000_0000]);
+	/// b.remove_bits(2, 5).unwrap();
+	/// assert_eq!(b.as_bytes(), &[0b1100_0000]);
+	/// ```
+	pub fn insert_bits(&mut self, at_bit: usize, value: u64, length: u32) -> Result<()> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		check_value_fits(value, length)?;
+		if at_bit > self.bit_len {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+
+		let bit_len = self.bit_len + length as usize;
+		let mut bytes = vec!{ 0u8; bit_len.div_ceil(8) };
+		bulk::copy_bits(&self.bytes, 0, &mut bytes, 0, at_bit)?;
+		write_bits_to_slice(&mut bytes, at_bit, length, value);
+		bulk::copy_bits(&self.bytes, at_bit, &mut bytes, at_bit + length as usize, self.bit_len - at_bit)?;
+		self.bytes = bytes;
+		self.bit_len = bit_len;
+		Ok(())
+	}
+
+	/// Removes length bits starting at at_bit, so that the following bits are moved towards the
+	/// start and the buffer shrinks by length bits
+	pub fn remove_bits(&mut self, at_bit: usize, length: usize) -> Result<()> {
+		match at_bit.checked_add(length) {
+			Some(end) if end <= self.bit_len => {},
+			_ => return Err(s!(OUT_OF_RANGE_MSG)),
+		}
+
+		let bit_len = self.bit_len - length;
+		let mut bytes = vec!{ 0u8; bit_len.div_ceil(8) };
+		bulk::copy_bits(&self.bytes, 0, &mut bytes, 0, at_bit)?;
+		bulk::copy_bits(&self.bytes, at_bit + length, &mut bytes, at_bit, bit_len - at_bit)?;
+		self.bytes = bytes;
+		self.bit_len = bit_len;
+		Ok(())
+	}
+
+	// Clears the unused bits of the last byte
+	fn clear_padding(&mut self) {
+		let rest = self.bit_len % 8;
+		if rest > 0 {
+			if let Some(last) = self.bytes.last_mut() {
+				*last &= 0xFFu8 << (8 - rest);
+			}
+		}
+	}
+
+	/// Packs a slice of bools into a buffer, where true is a set bit.
+	/// The packed bytes for transmission are returned by into_bytes.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let b = BitBuffer::from_bools(&[true, false, true, true, false, false, false, false, true]);
+	/// assert_eq!(b.bit_len(), 9);
+	/// assert!(b.to_bools()[8]);
+	/// assert_eq!(b.into_bytes(), vec!{ 0b1011_0000, 0b1000_0000 });
+	/// ```
+	pub fn from_bools(bits: &[bool]) -> BitBuffer {
+		bits.iter().copied().collect()
+	}
+
+	/// Unpacks the bits of the buffer into a vector of bools
+	pub fn to_bools(&self) -> Vec<bool> {
+		(0..self.bit_len).map(|index| self.bytes[index / 8] & (0b1000_0000 >> (index % 8)) != 0).collect()
+	}
+
+	/// Creates a buffer from a string of binary digits with an optional prefix 0b.
+	/// Underscores and whitespace are ignored, so the digits may be grouped as in a protocol specification.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let b = BitBuffer::from_bin_str("0110_1 01").unwrap();
+	/// assert_eq!(b.bit_len(), 7);
+	/// assert_eq!(b.as_bytes(), &[0b0110_1010]);
+	/// ```
+	pub fn from_bin_str(text: &str) -> Result<BitBuffer> {
+		let mut buffer = BitBuffer::new();
+		for c in literal_digits(text, "0b") {
+			match c {
+				'0' => buffer.push_bit(false),
+				'1' => buffer.push_bit(true),
+				_ => return Err(s!(INVALID_DIGIT_MSG) + &c.to_string()),
+			}
+		}
+		Ok(buffer)
+	}
+
+	/// Creates a buffer from the first bit_len bits of a string of hex digits with an optional prefix 0x.
+	/// Underscores and whitespace are ignored. Returns an error if there are less than bit_len bits.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let b = BitBuffer::from_hex_str("6C 6F", 14).unwrap();
+	/// assert_eq!(b.bit_len(), 14);
+	/// assert_eq!(b.as_bytes(), &[0x6C, 0x6C]);
+	/// ```
+	pub fn from_hex_str(text: &str, bit_len: usize) -> Result<BitBuffer> {
+		let mut buffer = BitBuffer::new();
+		for c in literal_digits(text, "0x") {
+			let digit = c.to_digit(16).ok_or_else(|| s!(INVALID_DIGIT_MSG) + &c.to_string())?;
+			buffer.push_bits(digit as u64, 4)?;
+		}
+		if bit_len > buffer.bit_len {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		BitBuffer::from_bytes(buffer.bytes, bit_len)
+	}
+}
+
+impl std::iter::FromIterator<bool> for BitBuffer {
+	/// Packs the bools in the order of the iterator
+	fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> BitBuffer {
+		let mut buffer = BitBuffer::new();
+		buffer.extend(iter);
+		buffer
+	}
+}
+
+impl Extend<bool> for BitBuffer {
+	/// Appends the bools at the end of the buffer
+	fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+		for bit in iter {
+			self.push_bit(bit);
+		}
+	}
+}
+
+impl From<Vec<u8>> for BitBuffer {
+	/// Takes over all bits of the vector
+	fn from(bytes: Vec<u8>) -> BitBuffer {
+		let bit_len = bytes.len() * 8;
+		BitBuffer { bytes, bit_len }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn building_a_bit_buffer() {
+		let mut b = BitBuffer::new();
+		assert!(b.is_empty());
+
+		for bit in [true, false, true, true, false, false, false, false, true, true] {
+			b.push_bit(bit);
+		}
+		assert_eq!(b.bit_len(), 10);
+		assert_eq!(b.as_bytes(), &[0b1011_0000, 0b1100_0000]);
+		assert!(b.get_bit(9).unwrap());
+		assert!(b.get_bit(10).is_err());
+		assert_eq!(b.read_bits(2, 8).unwrap(), 0b1100_0011);
+
+		// The padding bits are cleared
+		let c = BitBuffer::from_bytes(vec!{ 0xB0, 0xFF, 0xFF }, 10).unwrap();
+		assert_eq!(c, b);
+
+		b.push_bits(0b101, 3).unwrap();
+		assert_eq!(b.bit_len(), 13);
+		assert_eq!(b.read_bits(8, 5).unwrap(), 0b1_1101);
+		assert!(b.read_bits(8, 6).is_err());
+		assert_eq!(b.read_bits(usize::MAX, 2).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		match BitBuffer::from_bytes(vec!{ 0xB0 }, 9) {
+			Ok(_) => panic!("Missed the range check"),
+			Err(e) => assert_eq!(e, OUT_OF_RANGE_MSG),
+		}
+	}
+
+	#[test]
+	fn building_with_the_bits_macro() {
+		let b = bits![ (0b1u8, 1), (0xABCDu16, 16), (-2i32, 3), (false, 2), (u64::MAX, 64), ];
+		assert_eq!(b.bit_len(), 86);
+		assert_eq!(b.read_bits(0, 17).unwrap(), 0x1ABCD);
+		assert_eq!(b.read_bits(17, 5).unwrap(), 0b1_1000);
+		assert_eq!(b.read_bits(22, 64).unwrap(), u64::MAX);
+		assert_eq!(bits![ (0x48u8, 8), (0x61u8, 8) ], BitBuffer::from(vec!{ 0x48, 0x61 }));
+		assert!(bits![].is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn building_with_too_wide_fields() {
+		bits![ (1u8, 65) ];
+	}
+
+	#[test]
+	#[should_panic(expected = "The value 511 does not fit into 8 bits in bits!")]
+	fn building_with_too_large_values() {
+		bits![ (-128i8, 8), (255u8, 8), (0x1FF, 8) ];
+	}
+
+	#[test]
+	fn converting_bools() {
+		let bools = [true, true, false, false, true, false, true, false, false, true, true];
+		let b: BitBuffer = bools.iter().copied().collect();
+		assert_eq!(b.bit_len(), 11);
+		assert_eq!(b.as_bytes(), &[0b1100_1010, 0b0110_0000]);
+		assert_eq!(b, BitBuffer::from_bools(&bools));
+		assert_eq!(b.to_bools(), bools.to_vec());
+		assert!(BitBuffer::from_bools(&[]).is_empty());
+
+		// Whole bytes of a vector
+		let v = BitBuffer::from(vec!{ 0x81u8 }).to_bools();
+		assert_eq!(v, vec!{ true, false, false, false, false, false, false, true });
+		let mut c = BitBuffer::from_bools(&v);
+		c.extend(vec!{ true });
+		assert_eq!(c.into_bytes(), vec!{ 0x81, 0x80 });
+	}
+
+	#[test]
+	fn parsing_literals() {
+		let b = BitBuffer::from_bin_str("0b1100_1010 011\n").unwrap();
+		assert_eq!(b, BitBuffer::from_bools(&[true, true, false, false, true, false, true, false, false, true, true]));
+		assert!(BitBuffer::from_bin_str("").unwrap().is_empty());
+		assert_eq!(BitBuffer::from_bin_str("0120").unwrap_err(), "Invalid digit: 2");
+		assert!(BitBuffer::from_bin_str("b01").is_err());
+
+		let h = BitBuffer::from_hex_str("0xCA_6", 11).unwrap();
+		assert_eq!(h, b);
+		assert_eq!(BitBuffer::from_hex_str("abc", 12).unwrap().as_bytes(), &[0xAB, 0xC0]);
+		assert_eq!(BitBuffer::from_hex_str("abc", 13).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(BitBuffer::from_hex_str("0xG", 4).unwrap_err(), "Invalid digit: G");
+		assert!(BitBuffer::from_hex_str("", 0).unwrap().is_empty());
+	}
+
+	#[test]
+	fn shifting_a_bit_buffer() {
+		let mut b = BitBuffer::from_bools(&[true, false, true, true, false, false, true, true, true, true]);
+		b.shl_bits(3);
+		assert_eq!(b.bit_len(), 10);
+		assert_eq!(b.as_bytes(), &[0b1001_1110, 0b0000_0000]);
+		b.shr_bits(5);
+		assert_eq!(b.as_bytes(), &[0b0000_0100, 0b1100_0000]);
+		b.shr_bits(9);
+		assert_eq!(b.as_bytes(), &[0, 0]);
+	}
+
+	#[test]
+	fn inserting_and_removing_bits() {
+		// Rewrite a 4 bit field as a 12 bit field
+		let mut b = BitBuffer::from(vec!{ 0xA5, 0xFF });
+		b.remove_bits(4, 4).unwrap();
+		b.insert_bits(4, 0xABC, 12).unwrap();
+		assert_eq!(b.bit_len(), 24);
+		assert_eq!(b.as_bytes(), &[0xAA, 0xBC, 0xFF]);
+
+		b.insert_bits(24, 1, 1).unwrap();
+		assert_eq!(b.insert_bits(0, 0, 0).unwrap_err(), LEN_ZERO);
+		assert!(b.insert_bits(0, 0b100, 2).is_err());
+		assert_eq!(b.bit_len(), 25);
+		assert_eq!(b.read_bits(23, 2).unwrap(), 0b11);
+		assert_eq!(b.insert_bits(26, 1, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(b.insert_bits(0, 1, 65).is_err());
+
+		b.remove_bits(0, 20).unwrap();
+		assert_eq!(b.as_bytes(), &[0b1111_1000]);
+		assert_eq!(b.remove_bits(1, 5).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(b.remove_bits(1, usize::MAX).is_err());
+		b.remove_bits(0, 5).unwrap();
+		assert!(b.is_empty());
+		assert_eq!(b, BitBuffer::new());
+	}
+}
@@ -0,0 +1,410 @@
+//! Bulk operations on large buffers
+//!
+//! Operations, which may run for a long time on very large buffers, have variants accepting a
+//! [`Progress`](struct.Progress.html) object. Its callback is called regularly with the number of
+//! processed bytes and the total number of bytes. Returning `ControlFlow::Break(())` from the
+//! callback cancels the operation, which then returns an error.
+
+use std::ops::ControlFlow;
+use super::{Result, BitBuffer, LEN_ZERO, LEN_TOO_BIG_MSG, check_bit_range, read_bits_from_slice, write_bits_to_slice};
+use super::scan::find_bits;
+
+/// The error message of a cancelled operation
+pub static CANCELLED_MSG: &str = "The operation was cancelled";
+
+// The number of bytes, which are searched between two reports
+const SEARCH_CHUNK_SIZE: usize = 4096;
+
+/// Reports the progress of a bulk operation to a callback
+///
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use bitlab::bulk::*;
+/// let source = vec!{ 0xAB; 1000 };
+/// let mut target = vec!{ 0x00; 1000 };
+/// let mut calls = 0;
+/// let mut progress = Progress::new(100, |done, total| {
+///     calls += 1;
+///     assert!(done <= total);
+///     ControlFlow::Continue(())
+/// });
+/// copy_bits_with_progress(&source, 0, &mut target, 0, 8000, &mut progress).unwrap();
+/// assert_eq!(source, target);
+/// drop(progress);
+/// assert_eq!(calls, 10);
+/// ```
+pub struct Progress<F> where F: FnMut(usize, usize) -> ControlFlow<()> {
+	interval: usize,
+	next: usize,
+	callback: F,
+}
+
+impl<F> Progress<F> where F: FnMut(usize, usize) -> ControlFlow<()> {
+	/// Creates a progress reporter, which calls the callback every interval_bytes bytes
+	/// (at least every byte) and once at the end of the operation.
+	pub fn new(interval_bytes: usize, callback: F) -> Progress<F> {
+		let interval = interval_bytes.max(1);
+		Progress { interval, next: interval, callback }
+	}
+
+	/// Called by the operations with the number of processed bytes.
+	/// Returns an error if the callback requests to cancel the operation.
+	pub(crate) fn report(&mut self, done: usize, total: usize) -> Result<()> {
+		if done >= self.next || done == total {
+			self.next = (done / self.interval + 1) * self.interval;
+			if let ControlFlow::Break(()) = (self.callback)(done, total) {
+				return Err(s!(CANCELLED_MSG));
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Copies length bits from source to target. Both positions are counted in bits from the
+/// most significant bit of the first byte and need not be aligned.
+pub fn copy_bits(source: &[u8], source_bit_offset: usize, target: &mut [u8], target_bit_offset: usize, length: usize) -> Result<()> {
+	copy_bits_with_progress(source, source_bit_offset, target, target_bit_offset, length,
+		&mut Progress::new(usize::MAX, |_, _| ControlFlow::Continue(())))
+}
+
+/// Same as copy_bits, but reports the progress. If the operation is cancelled,
+/// the bytes copied so far remain in target.
+pub fn copy_bits_with_progress<F>(source: &[u8], source_bit_offset: usize, target: &mut [u8], target_bit_offset: usize,
+	length: usize, progress: &mut Progress<F>) -> Result<()> where F: FnMut(usize, usize) -> ControlFlow<()> {
+	check_bit_range(source_bit_offset, length, source.len().saturating_mul(8))?;
+	check_bit_range(target_bit_offset, length, target.len().saturating_mul(8))?;
+
+	let total = length.div_ceil(8);
+	let mut done = 0;
+	while done < length {
+		// Copy up to 64 bits at once
+		let n = std::cmp::min(64, length - done);
+		let bits = read_bits_from_slice(source, source_bit_offset + done, n as u32);
+		write_bits_to_slice(target, target_bit_offset + done, n as u32, bits);
+		done += n;
+		progress.report(done.div_ceil(8), total)?;
+	}
+
+	Ok(())
+}
+
+/// Same as [`BitScan::find_pattern`](../trait.BitScan.html#tymethod.find_pattern), but reports the progress.
+/// The reported bytes are counted from the start of source.
+pub fn find_pattern_with_progress<F>(source: &[u8], pattern: u64, pattern_len: u32, from_bit: usize,
+	progress: &mut Progress<F>) -> Result<Option<usize>> where F: FnMut(usize, usize) -> ControlFlow<()> {
+	find_with_progress(source, pattern, None, pattern_len, from_bit, progress)
+}
+
+/// Same as [`BitScan::find_masked_pattern`](../trait.BitScan.html#tymethod.find_masked_pattern), but reports the progress.
+/// The reported bytes are counted from the start of source.
+pub fn find_masked_pattern_with_progress<F>(source: &[u8], pattern: u64, mask: u64, pattern_len: u32, from_bit: usize,
+	progress: &mut Progress<F>) -> Result<Option<usize>> where F: FnMut(usize, usize) -> ControlFlow<()> {
+	find_with_progress(source, pattern, Some(mask), pattern_len, from_bit, progress)
+}
+
+// Searches the source in chunks and reports the progress after each chunk without a match
+fn find_with_progress<F>(source: &[u8], pattern: u64, mask: Option<u64>, pattern_len: u32, mut from_bit: usize,
+	progress: &mut Progress<F>) -> Result<Option<usize>> where F: FnMut(usize, usize) -> ControlFlow<()> {
+	let bit_len = source.len().saturating_mul(8);
+	loop {
+		// Matches, which start within the chunk, may end after it
+		let chunk_end = from_bit.saturating_add(SEARCH_CHUNK_SIZE * 8).min(bit_len);
+		let search_end = chunk_end.saturating_add(pattern_len as usize).min(bit_len);
+		if let Some(index) = find_bits(source, search_end, pattern, mask, pattern_len, from_bit)? {
+			return Ok(Some(index));
+		}
+		if chunk_end >= bit_len {
+			progress.report(source.len(), source.len())?;
+			return Ok(None);
+		}
+		progress.report(chunk_end / 8, source.len())?;
+		from_bit = chunk_end;
+	}
+}
+
+/// Same as [`BitBuffer::from_bools`](../struct.BitBuffer.html#method.from_bools), but reports the
+/// progress in packed bytes
+pub fn pack_bools_with_progress<F>(bits: &[bool], progress: &mut Progress<F>) -> Result<BitBuffer>
+	where F: FnMut(usize, usize) -> ControlFlow<()> {
+	let total = bits.len().div_ceil(8);
+	let mut buffer = BitBuffer::new();
+	for chunk in bits.chunks(64) {
+		let value = chunk.iter().fold(0, |acc, &bit| acc << 1 | bit as u64);
+		buffer.push_bits(value, chunk.len() as u32)?;
+		progress.report(buffer.bit_len().div_ceil(8), total)?;
+	}
+	Ok(buffer)
+}
+
+/// Same as [`BitBuffer::to_bools`](../struct.BitBuffer.html#method.to_bools), but reports the
+/// progress in unpacked bytes
+pub fn unpack_bools_with_progress<F>(bits: &BitBuffer, progress: &mut Progress<F>) -> Result<Vec<bool>>
+	where F: FnMut(usize, usize) -> ControlFlow<()> {
+	let bytes = bits.as_bytes();
+	let mut result = Vec::with_capacity(bits.bit_len());
+	for (i, &byte) in bytes.iter().enumerate() {
+		let n = std::cmp::min(8, bits.bit_len() - i * 8);
+		result.extend((0..n).map(|k| byte & (0x80 >> k) != 0));
+		progress.report(i + 1, bytes.len())?;
+	}
+	Ok(result)
+}
+
+/// A bitwise operation, which combines the bits of a target with other bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BitOp {
+	/// Keeps the bits, which are set in both
+	And,
+	/// Sets the bits, which are set in either
+	Or,
+	/// Inverts the bits of the target, which are set in the other bits
+	Xor,
+}
+
+impl BitOp {
+	fn apply(self, a: u64, b: u64) -> u64 {
+		match self {
+			BitOp::And => a & b,
+			BitOp::Or => a | b,
+			BitOp::Xor => a ^ b,
+		}
+	}
+}
+
+/// Combines length bits of target with the same number of bits of source using op, e.g.
+/// target = target ^ source for BitOp::Xor. Both positions are counted in bits from the most
+/// significant bit of the first byte and need not be aligned. The bits outside of the range are not modified.
+///
+/// ```rust
+/// use bitlab::bulk::*;
+/// let key = [0b1111_0000u8];
+/// let mut data = [0b1010_1010u8, 0b1010_1010];
+/// combine_bits(&key, 0, &mut data, 4, 8, BitOp::Xor).unwrap();
+/// assert_eq!(data, [0b1010_0101, 0b1010_1010]);
+/// ```
+pub fn combine_bits(source: &[u8], source_bit_offset: usize, target: &mut [u8], target_bit_offset: usize, length: usize, op: BitOp) -> Result<()> {
+	check_bit_range(source_bit_offset, length, source.len().saturating_mul(8))?;
+	check_bit_range(target_bit_offset, length, target.len().saturating_mul(8))?;
+
+	let mut done = 0;
+	while done < length {
+		let n = std::cmp::min(64, length - done) as u32;
+		let a = read_bits_from_slice(target, target_bit_offset + done, n);
+		let b = read_bits_from_slice(source, source_bit_offset + done, n);
+		write_bits_to_slice(target, target_bit_offset + done, n, op.apply(a, b));
+		done += n as usize;
+	}
+
+	Ok(())
+}
+
+/// Combines length bits of target with the mask_len least significant bits of mask using op.
+/// The mask is repeated from bit_offset on, so that e.g. a scrambling sequence or a pattern of
+/// reserved bits can be applied to a whole range. The last repetition may be incomplete.
+///
+/// ```rust
+/// use bitlab::bulk::*;
+/// // Clear every other bit
+/// let mut data = [0xFFu8, 0xFF];
+/// combine_with_mask(&mut data, 2, 12, 0b10, 2, BitOp::And).unwrap();
+/// assert_eq!(data, [0b1110_1010, 0b1010_1011]);
+/// ```
+pub fn combine_with_mask(target: &mut [u8], bit_offset: usize, length: usize, mask: u64, mask_len: u32, op: BitOp) -> Result<()> {
+	if mask_len == 0 {
+		return Err(s!(LEN_ZERO));
+	}
+	if mask_len > 64 {
+		return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+	}
+	check_bit_range(bit_offset, length, target.len().saturating_mul(8))?;
+
+	let mut done = 0;
+	while done < length {
+		let n = std::cmp::min(mask_len as usize, length - done) as u32;
+		// The first n bits of the mask
+		let b = (mask << (64 - mask_len)) >> (64 - n);
+		let a = read_bits_from_slice(target, bit_offset + done, n);
+		write_bits_to_slice(target, bit_offset + done, n, op.apply(a, b));
+		done += n as usize;
+	}
+
+	Ok(())
+}
+
+/// Inverts length bits of target starting at bit_offset
+pub fn invert_bits(target: &mut [u8], bit_offset: usize, length: usize) -> Result<()> {
+	combine_with_mask(target, bit_offset, length, u64::MAX, 64, BitOp::Xor)
+}
+
+/// Shifts all bits of target by amount bits towards the first bit. The first amount bits are
+/// dropped and the last amount bits are cleared, so that e.g. a payload after a header of a few
+/// bits becomes byte aligned.
+///
+/// ```rust
+/// use bitlab::bulk::*;
+/// let mut data = [0b1011_0100u8, 0b1100_0000];
+/// shl_bits(&mut data, 3);
+/// assert_eq!(data, [0b1010_0110, 0b0000_0000]);
+/// ```
+pub fn shl_bits(target: &mut [u8], amount: usize) {
+	let len = target.len();
+	let byte_shift = std::cmp::min(amount / 8, len);
+	let bit_shift = (amount % 8) as u32;
+	for i in 0..len - byte_shift {
+		let high = target[i + byte_shift] << bit_shift;
+		let low = match target.get(i + byte_shift + 1) {
+			Some(&next) if bit_shift > 0 => next >> (8 - bit_shift),
+			_ => 0,
+		};
+		target[i] = high | low;
+	}
+	for byte in &mut target[len - byte_shift..] {
+		*byte = 0;
+	}
+}
+
+/// Shifts all bits of target by amount bits towards the last bit. The last amount bits are
+/// dropped and the first amount bits are cleared.
+///
+/// ```rust
+/// use bitlab::bulk::*;
+/// let mut data = [0b1011_0100u8, 0b1100_0001];
+/// shr_bits(&mut data, 3);
+/// assert_eq!(data, [0b0001_0110, 0b1001_1000]);
+/// ```
+pub fn shr_bits(target: &mut [u8], amount: usize) {
+	let len = target.len();
+	let byte_shift = std::cmp::min(amount / 8, len);
+	let bit_shift = (amount % 8) as u32;
+	for i in (byte_shift..len).rev() {
+		let low = target[i - byte_shift] >> bit_shift;
+		let high = if bit_shift > 0 && i > byte_shift { target[i - byte_shift - 1] << (8 - bit_shift) } else { 0 };
+		target[i] = high | low;
+	}
+	for byte in &mut target[..byte_shift] {
+		*byte = 0;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn copying_with_progress() {
+		let source: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		let mut target: Vec<u8> = vec!{ 0xFF; 6 };
+
+		// Copy "allo" to bit offset 3
+		copy_bits(&source, 8, &mut target, 3, 32).unwrap();
+		assert_eq!(target, vec!{ 0b1110_1100, 0b0010_1101, 0b1000_1101, 0b1000_1101, 0xFF, 0xFF });
+
+		assert_eq!(copy_bits(&source, 9, &mut target, 0, 32).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(copy_bits(&source, 0, &mut target, 17, 32).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(copy_bits(&source, usize::MAX, &mut target, 0, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(copy_bits(&source, 0, &mut target, 1, usize::MAX).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		// Cancel after the first report
+		let big = vec!{ 0x55u8; 100 };
+		let mut copy = vec!{ 0x00u8; 100 };
+		let mut reports = Vec::new();
+		let mut progress = Progress::new(16, |done, total| {
+			reports.push((done, total));
+			ControlFlow::Break(())
+		});
+		assert_eq!(copy_bits_with_progress(&big, 0, &mut copy, 0, 800, &mut progress).unwrap_err(), CANCELLED_MSG);
+		assert_eq!(reports, vec!{ (16, 100) });
+		assert_eq!(&copy[..16], &big[..16]);
+		assert_eq!(copy[16], 0);
+	}
+
+	#[test]
+	fn searching_and_packing_with_progress() {
+		let mut haystack = vec!{ 0u8; 10_000 };
+		haystack[9_000] = 0x01;
+		haystack[9_001] = 0x80;
+		let mut reports = Vec::new();
+		let mut report = |done, total| {
+			reports.push((done, total));
+			ControlFlow::Continue(())
+		};
+		assert_eq!(find_pattern_with_progress(&haystack, 0b11, 2, 0, &mut Progress::new(4096, &mut report)).unwrap(), Some(72_007));
+		assert_eq!(find_masked_pattern_with_progress(&haystack, 0b1001, 0b1001, 4, 72_008, &mut Progress::new(4096, &mut report)).unwrap(), None);
+		assert!(find_pattern_with_progress(&haystack, 0b100, 2, 0, &mut Progress::new(4096, &mut report)).is_err());
+		assert_eq!(reports, vec!{ (4096, 10_000), (8192, 10_000), (10_000, 10_000) });
+
+		let mut progress = Progress::new(8192, |_, _| ControlFlow::Break(()));
+		assert_eq!(find_pattern_with_progress(&haystack, 0b11, 2, 0, &mut progress).unwrap_err(), CANCELLED_MSG);
+
+		// A match across the end of a chunk
+		haystack[4095] = 0x01;
+		haystack[4096] = 0x80;
+		let mut progress = Progress::new(1, |_, _| ControlFlow::Continue(()));
+		assert_eq!(find_pattern_with_progress(&haystack, 0b11, 2, 0, &mut progress).unwrap(), Some(32_767));
+
+		let bools: Vec<bool> = (0..1000).map(|i| i % 3 == 0).collect();
+		let mut calls = 0;
+		let mut count = |_, _| {
+			calls += 1;
+			ControlFlow::Continue(())
+		};
+		let packed = pack_bools_with_progress(&bools, &mut Progress::new(50, &mut count)).unwrap();
+		assert_eq!(packed, BitBuffer::from_bools(&bools));
+		assert_eq!(unpack_bools_with_progress(&packed, &mut Progress::new(50, &mut count)).unwrap(), bools);
+		assert_eq!(calls, 3 + 3);
+	}
+
+	#[test]
+	fn bitwise_operations() {
+		let source: Vec<u8> = vec!{ 0x0F, 0xF0, 0xAA, 0x55, 0x00, 0xFF, 0x12, 0x34, 0x56 };
+		let mut target: Vec<u8> = vec!{ 0xFF; 10 };
+
+		// Unaligned ranges of more than 64 bits
+		combine_bits(&source, 4, &mut target, 7, 68, BitOp::And).unwrap();
+		combine_bits(&source, 4, &mut target, 7, 68, BitOp::Or).unwrap();
+		let mut copy = vec!{ 0xFFu8; 10 };
+		copy_bits(&source, 4, &mut copy, 7, 68).unwrap();
+		assert_eq!(target, copy);
+
+		// x ^ x = 0
+		combine_bits(&source, 4, &mut target, 7, 68, BitOp::Xor).unwrap();
+		assert_eq!(target, vec!{ 0xFE, 0, 0, 0, 0, 0, 0, 0, 0, 0x1F });
+		assert_eq!(combine_bits(&source, 5, &mut target, 0, 68, BitOp::Or).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(combine_bits(&source, usize::MAX, &mut target, 0, 2, BitOp::Or).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(combine_bits(&source, 0, &mut target, usize::MAX, 2, BitOp::Or).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		invert_bits(&mut target, 7, 68).unwrap();
+		assert_eq!(target, vec!{ 0xFF; 10 });
+		assert!(invert_bits(&mut target, 1, 80).is_err());
+
+		combine_with_mask(&mut target, 0, 80, 0b001, 3, BitOp::Xor).unwrap();
+		assert_eq!(&target[..3], &[0b1101_1011, 0b0110_1101, 0b1011_0110]);
+		assert_eq!(combine_with_mask(&mut target, 0, 8, 0, 0, BitOp::And).unwrap_err(), LEN_ZERO);
+		assert!(combine_with_mask(&mut target, 0, 8, 0, 65, BitOp::And).is_err());
+		assert_eq!(combine_with_mask(&mut target, usize::MAX, 2, 0, 1, BitOp::And).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn shifting_bits() {
+		let original: Vec<u8> = vec!{ 0x12, 0x34, 0x56, 0x78 };
+		let mut v = original.clone();
+		shl_bits(&mut v, 12);
+		assert_eq!(v, vec!{ 0x45, 0x67, 0x80, 0x00 });
+		shr_bits(&mut v, 12);
+		assert_eq!(v, vec!{ 0x00, 0x04, 0x56, 0x78 });
+
+		let mut v = original.clone();
+		shr_bits(&mut v, 16);
+		assert_eq!(v, vec!{ 0x00, 0x00, 0x12, 0x34 });
+		shl_bits(&mut v, 0);
+		assert_eq!(v, vec!{ 0x00, 0x00, 0x12, 0x34 });
+		shl_bits(&mut v, 33);
+		assert_eq!(v, vec!{ 0; 4 });
+
+		let mut v = original;
+		shr_bits(&mut v, usize::MAX);
+		assert_eq!(v, vec!{ 0; 4 });
+		shl_bits(&mut [], 3);
+	}
+}
@@ -0,0 +1,97 @@
+//! The Internet checksum (RFC 1071)
+//!
+//! The checksum is the ones' complement of the ones' complement sum of the 16 bit words of a
+//! region, as used by IPv4, UDP, TCP and ICMP. The region may start at any bit, and a final
+//! partial word is padded with zeros.
+//!
+//! ```rust
+//! use bitlab::checksum::*;
+//! // An IPv4 header with a cleared checksum field at byte 10
+//! let mut header = vec!{ 0x45u8, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11,
+//!     0x00, 0x00, 0xC0, 0xA8, 0x00, 0x01, 0xC0, 0xA8, 0x00, 0xC7 };
+//! insert_internet_checksum(&mut header, 0, 0, 160, 10, 0).unwrap();
+//! assert_eq!(&header[10..12], &[0xB8, 0x61]);
+//! assert!(verify_internet_checksum(&header, 0, 0, 160).unwrap());
+//! ```
+
+use super::{Result, range_start, read_bits_from_slice, write_bits_to_slice};
+
+/// Computes the Internet checksum of a region.
+///
+/// Parameters:
+///
+/// - **source** the data source
+/// - **byte_offset** (u32) the number of bytes to skip in source
+/// - **bit_offset** (u32) the start position of the region. Zero is the most significant bit
+/// - **length** (usize) the number of bits in the region
+pub fn internet_checksum(source: &[u8], byte_offset: u32, bit_offset: u32, length: usize) -> Result<u16> {
+	let start = range_start(source.len(), byte_offset, bit_offset, length)?;
+
+	let mut sum: u64 = 0;
+	let mut index = 0;
+	while index < length {
+		let n = std::cmp::min(16, length - index) as u32;
+		// A partial word is padded with zeros on the right
+		sum += read_bits_from_slice(source, start + index, n) << (16 - n);
+		index += n as usize;
+	}
+
+	// Fold the carries back into the lower 16 bits
+	while sum > 0xFFFF {
+		sum = (sum & 0xFFFF) + (sum >> 16);
+	}
+	Ok(!(sum as u16))
+}
+
+/// Returns true if the checksum of a region, which includes its checksum field, is correct.
+/// See internet_checksum for the parameters.
+pub fn verify_internet_checksum(source: &[u8], byte_offset: u32, bit_offset: u32, length: usize) -> Result<bool> {
+	Ok(internet_checksum(source, byte_offset, bit_offset, length)? == 0)
+}
+
+/// Computes the Internet checksum of a region and writes it into the 16 bit field at
+/// field_byte_offset and field_bit_offset. The field is cleared first, so it may be part of the region.
+/// In that case it must start at a multiple of 16 bits from the start of the region, so that
+/// verify_internet_checksum accepts the result. See internet_checksum for the other parameters.
+pub fn insert_internet_checksum(target: &mut [u8], byte_offset: u32, bit_offset: u32, length: usize,
+	field_byte_offset: u32, field_bit_offset: u32) -> Result<u16> {
+	range_start(target.len(), byte_offset, bit_offset, length)?;
+	let field = range_start(target.len(), field_byte_offset, field_bit_offset, 16)?;
+
+	write_bits_to_slice(target, field, 16, 0);
+	let checksum = internet_checksum(target, byte_offset, bit_offset, length)?;
+	write_bits_to_slice(target, field, 16, checksum as u64);
+	Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn internet_checksums() {
+		// The example of RFC 1071, section 3
+		let data = [0x00u8, 0x01, 0xF2, 0x03, 0xF4, 0xF5, 0xF6, 0xF7];
+		assert_eq!(internet_checksum(&data, 0, 0, 64).unwrap(), !0xDDF2);
+		assert_eq!(internet_checksum(&[], 0, 0, 0).unwrap(), 0xFFFF);
+
+		// An odd number of bytes is padded with a zero byte
+		assert_eq!(internet_checksum(&data, 0, 0, 56).unwrap(), internet_checksum(&[0, 1, 0xF2, 3, 0xF4, 0xF5, 0xF6, 0], 0, 0, 64).unwrap());
+
+		// The same words shifted by 3 bits
+		let shifted = [0b1110_0000u8, 0x00, 0x3E, 0x40, 0x7E, 0x9E, 0xBE, 0xDE, 0xFF];
+		assert_eq!(internet_checksum(&shifted, 0, 3, 64).unwrap(), !0xDDF2);
+		assert_eq!(internet_checksum(&shifted, 1, 0, 72).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		// A checksum field in the middle of a byte, which is part of the region
+		let mut frame = vec!{ 0xFFu8; 12 };
+		let checksum = insert_internet_checksum(&mut frame, 0, 4, 88, 4, 4).unwrap();
+		assert_eq!(read_bits_from_slice(&frame, 36, 16), checksum as u64);
+		assert_eq!(frame[11], 0xFF);
+		assert!(verify_internet_checksum(&frame, 0, 4, 88).unwrap());
+		frame[0] ^= 1;
+		assert!(!verify_internet_checksum(&frame, 0, 4, 88).unwrap());
+		assert_eq!(insert_internet_checksum(&mut frame, 0, 0, 96, 11, 0).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+}
@@ -0,0 +1,410 @@
+//! Cyclic redundancy checks
+//!
+//! A CRC is described by the usual parameters (width, polynomial, initial value, reflection of
+//! the input and the output and the final XOR value) in a [`CrcAlgorithm`](struct.CrcAlgorithm.html).
+//! A [`Crc`](struct.Crc.html) engine precomputes a table for the algorithm.
+//!
+//! ```rust
+//! use bitlab::crc::*;
+//! let crc = Crc::new(&CRC_32).unwrap();
+//! assert_eq!(crc.checksum(b"123456789"), 0xCBF4_3926);
+//! ```
+//!
+//! checksum_bits() computes the CRC of a field, which may start and end in the middle of a byte.
+//!
+//! With the feature **rayon**, checksum_parallel() computes the CRCs of chunks on several threads
+//! and combines them by polynomial arithmetic. The result is identical to checksum().
+
+use std::ops::ControlFlow;
+use super::{Result, range_start, read_bits_from_slice};
+use super::bulk::Progress;
+
+static WIDTH_MSG: &str = "The width of a CRC must be between 1 and 64 bits";
+
+/// The parameters of a CRC algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrcAlgorithm {
+	/// The number of bits (1 to 64)
+	pub width: u32,
+	/// The generator polynomial without the leading term, most significant bit first
+	pub poly: u64,
+	/// The initial value of the register
+	pub init: u64,
+	/// Whether the bits of each input byte are processed least significant bit first
+	pub refin: bool,
+	/// Whether the register is reflected before the final XOR
+	pub refout: bool,
+	/// The value XORed to the register at the end
+	pub xorout: u64,
+}
+
+/// CRC-8 (SMBus)
+pub const CRC_8: CrcAlgorithm = CrcAlgorithm { width: 8, poly: 0x07, init: 0x00, refin: false, refout: false, xorout: 0x00 };
+
+/// CRC-16/ARC
+pub const CRC_16_ARC: CrcAlgorithm = CrcAlgorithm { width: 16, poly: 0x8005, init: 0x0000, refin: true, refout: true, xorout: 0x0000 };
+
+/// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE
+pub const CRC_16_IBM_3740: CrcAlgorithm = CrcAlgorithm { width: 16, poly: 0x1021, init: 0xFFFF, refin: false, refout: false, xorout: 0x0000 };
+
+/// CRC-32 as used by Ethernet, zlib and PNG
+pub const CRC_32: CrcAlgorithm = CrcAlgorithm { width: 32, poly: 0x04C1_1DB7, init: 0xFFFF_FFFF, refin: true, refout: true, xorout: 0xFFFF_FFFF };
+
+/// CRC-32C (Castagnoli)
+pub const CRC_32C: CrcAlgorithm = CrcAlgorithm { width: 32, poly: 0x1EDC_6F41, init: 0xFFFF_FFFF, refin: true, refout: true, xorout: 0xFFFF_FFFF };
+
+/// CRC-64/XZ
+pub const CRC_64_XZ: CrcAlgorithm = CrcAlgorithm { width: 64, poly: 0x42F0_E1EB_A9EA_3693, init: u64::MAX, refin: true, refout: true, xorout: u64::MAX };
+
+// Reverses the order of the width least significant bits
+fn reflect(value: u64, width: u32) -> u64 {
+	value.reverse_bits() >> (64 - width)
+}
+
+/// A CRC engine for one algorithm
+#[derive(Clone)]
+pub struct Crc {
+	algorithm: CrcAlgorithm,
+	mask: u64,
+	table: [u64; 256],
+}
+
+impl std::fmt::Debug for Crc {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Crc").field("algorithm", &self.algorithm).finish()
+	}
+}
+
+impl Crc {
+	/// Creates an engine for the algorithm. Returns an error if the width is not supported.
+	pub fn new(algorithm: &CrcAlgorithm) -> Result<Crc> {
+		let width = algorithm.width;
+		if width == 0 || width > 64 {
+			return Err(s!(WIDTH_MSG));
+		}
+
+		let mut table = [0u64; 256];
+		if algorithm.refin {
+			// The register holds the reflected remainder in its least significant bits
+			let poly = reflect(algorithm.poly, width);
+			for (i, entry) in table.iter_mut().enumerate() {
+				let mut r = i as u64;
+				for _ in 0..8 {
+					r = if r & 1 == 1 { (r >> 1) ^ poly } else { r >> 1 };
+				}
+				*entry = r;
+			}
+		} else {
+			// The register holds the remainder in its most significant bits
+			let poly = algorithm.poly << (64 - width);
+			for (i, entry) in table.iter_mut().enumerate() {
+				let mut r = (i as u64) << 56;
+				for _ in 0..8 {
+					r = if r >> 63 == 1 { (r << 1) ^ poly } else { r << 1 };
+				}
+				*entry = r;
+			}
+		}
+
+		let mask = u64::MAX >> (64 - width);
+		Ok(Crc { algorithm: *algorithm, mask, table })
+	}
+
+	/// Returns the algorithm
+	pub fn algorithm(&self) -> &CrcAlgorithm {
+		&self.algorithm
+	}
+
+	// The register for the initial value
+	fn initial_register(&self) -> u64 {
+		let a = &self.algorithm;
+		let init = a.init & self.mask;
+		if a.refin { reflect(init, a.width) } else { init << (64 - a.width) }
+	}
+
+	// Feeds bytes into the register
+	fn update(&self, mut r: u64, data: &[u8]) -> u64 {
+		if self.algorithm.refin {
+			for &b in data {
+				r = (r >> 8) ^ self.table[((r ^ b as u64) & 0xFF) as usize];
+			}
+		} else {
+			for &b in data {
+				r = (r << 8) ^ self.table[((r >> 56) ^ b as u64) as usize];
+			}
+		}
+		r
+	}
+
+	// Converts the register to the remainder polynomial, most significant bit first
+	fn register_to_poly(&self, r: u64) -> u64 {
+		let a = &self.algorithm;
+		if a.refin { reflect(r, a.width) } else { r >> (64 - a.width) }
+	}
+
+	// Applies the output reflection and the final XOR to a remainder polynomial
+	fn poly_to_checksum(&self, p: u64) -> u64 {
+		let a = &self.algorithm;
+		let out = if a.refout { reflect(p, a.width) } else { p };
+		(out ^ a.xorout) & self.mask
+	}
+
+	fn checksum_to_poly(&self, c: u64) -> u64 {
+		let a = &self.algorithm;
+		let v = (c ^ a.xorout) & self.mask;
+		if a.refout { reflect(v, a.width) } else { v }
+	}
+
+	fn finish(&self, r: u64) -> u64 {
+		self.poly_to_checksum(self.register_to_poly(r))
+	}
+
+	/// Computes the CRC of data
+	pub fn checksum(&self, data: &[u8]) -> u64 {
+		self.finish(self.update(self.initial_register(), data))
+	}
+
+	// Feeds the n least significant bits of value into the register. Like the bits of a byte,
+	// they are processed least significant bit first if the input is reflected.
+	fn update_bits(&self, mut r: u64, value: u64, n: u32) -> u64 {
+		let a = &self.algorithm;
+		if a.refin {
+			let poly = reflect(a.poly, a.width);
+			for i in 0..n {
+				r ^= (value >> i) & 1;
+				r = if r & 1 == 1 { (r >> 1) ^ poly } else { r >> 1 };
+			}
+		} else {
+			let poly = a.poly << (64 - a.width);
+			for i in (0..n).rev() {
+				r ^= ((value >> i) & 1) << 63;
+				r = if r >> 63 == 1 { (r << 1) ^ poly } else { r << 1 };
+			}
+		}
+		r
+	}
+
+	/// Computes the CRC of length bits, which start at an arbitrary bit.
+	///
+	/// The bits are grouped into bytes from the start of the field on, where the first bit is the
+	/// most significant bit of the first byte. A field, which consists of whole bytes, gives the
+	/// same CRC as checksum() on these bytes. The bits of a final partial byte are processed in
+	/// the same order as the bits of a whole byte, i.e. least significant bit first if the input
+	/// is reflected.
+	///
+	/// Parameters:
+	///
+	/// - **data** the data source
+	/// - **byte_offset** (u32) the number of bytes to skip in data
+	/// - **bit_offset** (u32) the start position of the field. Zero is the most significant bit
+	/// - **length** (usize) the number of bits in the field
+	///
+	/// ```rust
+	/// use bitlab::crc::*;
+	/// let crc = Crc::new(&CRC_16_IBM_3740).unwrap();
+	/// // "12" after a 4 bit header
+	/// let frame = [0xA3u8, 0x13, 0x20];
+	/// assert_eq!(crc.checksum_bits(&frame, 0, 4, 16).unwrap(), crc.checksum(b"12"));
+	/// ```
+	pub fn checksum_bits(&self, data: &[u8], byte_offset: u32, bit_offset: u32, length: usize) -> Result<u64> {
+		let start = range_start(data.len(), byte_offset, bit_offset, length)?;
+
+		let mut r = self.initial_register();
+		if start.is_multiple_of(8) {
+			r = self.update(r, &data[start / 8..(start + length) / 8]);
+		} else {
+			for i in 0..length / 8 {
+				let b = read_bits_from_slice(data, start + i * 8, 8) as u8;
+				r = self.update(r, &[b]);
+			}
+		}
+
+		let rest = (length % 8) as u32;
+		if rest > 0 {
+			let value = read_bits_from_slice(data, start + length - rest as usize, rest);
+			r = self.update_bits(r, value, rest);
+		}
+		Ok(self.finish(r))
+	}
+
+	/// Same as checksum, but reports the progress. Returns an error if the operation is cancelled.
+	pub fn checksum_with_progress<F>(&self, data: &[u8], progress: &mut Progress<F>) -> Result<u64>
+		where F: FnMut(usize, usize) -> ControlFlow<()> {
+		let mut r = self.initial_register();
+		let mut done = 0;
+		for chunk in data.chunks(4096) {
+			r = self.update(r, chunk);
+			done += chunk.len();
+			progress.report(done, data.len())?;
+		}
+		Ok(self.finish(r))
+	}
+
+	// Multiplies the polynomial a by x modulo the generator polynomial
+	fn mul_x(&self, a: u64) -> u64 {
+		let carry = (a >> (self.algorithm.width - 1)) & 1 == 1;
+		let r = (a << 1) & self.mask;
+		if carry { r ^ (self.algorithm.poly & self.mask) } else { r }
+	}
+
+	// Multiplies two polynomials modulo the generator polynomial (carry-less)
+	fn mul_mod(&self, a: u64, b: u64) -> u64 {
+		let mut r = 0;
+		for i in (0..self.algorithm.width).rev() {
+			r = self.mul_x(r);
+			if (b >> i) & 1 == 1 {
+				r ^= a;
+			}
+		}
+		r
+	}
+
+	// Returns x^(8 * n_bytes) modulo the generator polynomial
+	fn x_pow_bytes(&self, n_bytes: usize) -> u64 {
+		let mut result = 1;
+		let mut base = self.mul_x(1);
+		let mut n = n_bytes as u128 * 8;
+		while n > 0 {
+			if n & 1 == 1 {
+				result = self.mul_mod(result, base);
+			}
+			base = self.mul_mod(base, base);
+			n >>= 1;
+		}
+		result
+	}
+
+	/// Returns the CRC of the concatenation of two messages from their CRCs,
+	/// where second_len is the length of the second message in bytes.
+	///
+	/// ```rust
+	/// use bitlab::crc::*;
+	/// let crc = Crc::new(&CRC_32).unwrap();
+	/// let (a, b) = (crc.checksum(b"1234"), crc.checksum(b"56789"));
+	/// assert_eq!(crc.combine(a, b, 5), crc.checksum(b"123456789"));
+	/// ```
+	pub fn combine(&self, first: u64, second: u64, second_len: usize) -> u64 {
+		// The register after the concatenation is the register after the first message times
+		// x^(8 * second_len) plus the register after the second message started from zero.
+		// The CRC of the second message has been started from init instead of zero.
+		let shift = self.x_pow_bytes(second_len);
+		let init = self.algorithm.init & self.mask;
+		let p = self.mul_mod(self.checksum_to_poly(first) ^ init, shift) ^ self.checksum_to_poly(second);
+		self.poly_to_checksum(p)
+	}
+
+	/// Computes the CRC of data in chunks of chunk_size bytes on the rayon thread pool.
+	/// The result is identical to checksum().
+	#[cfg(feature = "rayon")]
+	pub fn checksum_parallel(&self, data: &[u8], chunk_size: usize) -> u64 {
+		use rayon::prelude::*;
+
+		let parts: Vec<(u64, usize)> = data.par_chunks(chunk_size.max(1))
+			.map(|chunk| (self.checksum(chunk), chunk.len()))
+			.collect();
+
+		let mut parts = parts.into_iter();
+		match parts.next() {
+			Some((first, _)) => parts.fold(first, |crc, (part, len)| self.combine(crc, part, len)),
+			None => self.checksum(&[]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::{BitWriter, OUT_OF_RANGE_MSG};
+
+	#[test]
+	fn check_values() {
+		// The check value of each algorithm is the CRC of "123456789"
+		let check = b"123456789";
+		assert_eq!(Crc::new(&CRC_8).unwrap().checksum(check), 0xF4);
+		assert_eq!(Crc::new(&CRC_16_ARC).unwrap().checksum(check), 0xBB3D);
+		assert_eq!(Crc::new(&CRC_16_IBM_3740).unwrap().checksum(check), 0x29B1);
+		assert_eq!(Crc::new(&CRC_32).unwrap().checksum(check), 0xCBF4_3926);
+		assert_eq!(Crc::new(&CRC_32C).unwrap().checksum(check), 0xE306_9283);
+		assert_eq!(Crc::new(&CRC_64_XZ).unwrap().checksum(check), 0x995D_C9BB_DF19_39FA);
+
+		// CRC-5/USB and CRC-3/ROHC
+		let crc5 = CrcAlgorithm { width: 5, poly: 0x05, init: 0x1F, refin: true, refout: true, xorout: 0x1F };
+		assert_eq!(Crc::new(&crc5).unwrap().checksum(check), 0x19);
+		let crc3 = CrcAlgorithm { width: 3, poly: 0x3, init: 0x7, refin: true, refout: true, xorout: 0x0 };
+		assert_eq!(Crc::new(&crc3).unwrap().checksum(check), 0x6);
+
+		assert_eq!(Crc::new(&CrcAlgorithm { width: 65, ..CRC_32 }).unwrap_err(), WIDTH_MSG);
+
+		// Combining the CRCs of two parts gives the CRC of the whole message
+		for a in [CRC_8, CRC_16_ARC, CRC_16_IBM_3740, CRC_32, CRC_64_XZ, crc5, crc3].iter() {
+			let crc = Crc::new(a).unwrap();
+			for split in 0..=check.len() {
+				let (first, second) = check.split_at(split);
+				assert_eq!(crc.combine(crc.checksum(first), crc.checksum(second), second.len()), crc.checksum(check));
+			}
+		}
+
+		// Cancelling
+		let data = vec!{ 0x55u8; 10000 };
+		let crc = Crc::new(&CRC_32).unwrap();
+		let mut calls = 0;
+		{
+			let mut progress = Progress::new(1000, |_, _| { calls += 1; ControlFlow::Continue(()) });
+			assert_eq!(crc.checksum_with_progress(&data, &mut progress).unwrap(), crc.checksum(&data));
+		}
+		assert_eq!(calls, 3);
+		let mut progress = Progress::new(1000, |_, _| ControlFlow::Break(()));
+		assert!(crc.checksum_with_progress(&data, &mut progress).is_err());
+	}
+
+	#[test]
+	fn checksums_of_bit_ranges() {
+		let check = b"123456789";
+		for a in [CRC_8, CRC_16_ARC, CRC_16_IBM_3740, CRC_32, CRC_64_XZ].iter() {
+			let crc = Crc::new(a).unwrap();
+			assert_eq!(crc.checksum_bits(check, 0, 0, 72).unwrap(), crc.checksum(check));
+			assert_eq!(crc.checksum_bits(check, 2, 0, 16).unwrap(), crc.checksum(&check[2..4]));
+			assert_eq!(crc.checksum_bits(check, 0, 0, 0).unwrap(), crc.checksum(&[]));
+
+			// The message shifted by 5 bits between ones
+			let mut shifted = vec!{ 0u8; 11 };
+			let mut w = BitWriter::new();
+			w.write_bits(0b11111, 5).unwrap();
+			for &b in check.iter() {
+				w.write_bits(b as u64, 8).unwrap();
+			}
+			w.write_bits(0b111, 3).unwrap();
+			shifted[..10].copy_from_slice(w.as_buffer().as_bytes());
+			assert_eq!(crc.checksum_bits(&shifted, 0, 5, 72).unwrap(), crc.checksum(check));
+			assert_eq!(crc.checksum_bits(&shifted, 1, 2, 79), Err(s!(OUT_OF_RANGE_MSG)));
+		}
+
+		// A non reflected CRC of a field with a partial byte is the remainder of the polynomial division
+		let crc = Crc::new(&CRC_16_IBM_3740).unwrap();
+		for length in 1..=72 {
+			let mut r = 0xFFFFu64;
+			for i in 0..length {
+				let bit = read_bits_from_slice(check, i, 1);
+				let top = ((r >> 15) & 1) ^ bit;
+				r = (r << 1) & 0xFFFF;
+				if top == 1 {
+					r ^= 0x1021;
+				}
+			}
+			assert_eq!(crc.checksum_bits(check, 0, 0, length).unwrap(), r);
+		}
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	fn parallel_checksums() {
+		let data: Vec<u8> = (0..100_000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8).collect();
+		for a in [CRC_8, CRC_16_IBM_3740, CRC_32, CRC_32C, CRC_64_XZ].iter() {
+			let crc = Crc::new(a).unwrap();
+			for &chunk_size in [1, 1000, 4096, 99_999, 1 << 20].iter() {
+				assert_eq!(crc.checksum_parallel(&data, chunk_size), crc.checksum(&data));
+			}
+		}
+		assert_eq!(Crc::new(&CRC_32).unwrap().checksum_parallel(&[], 16), 0);
+	}
+}
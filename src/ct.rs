@@ -0,0 +1,114 @@
+//! Constant time comparison of bit ranges
+//!
+//! The functions in this module are meant for comparing secrets like MACs or
+//! authentication tags, which are stored at arbitrary bit offsets in a frame.
+//! The run time only depends on the length of the compared ranges and their
+//! offsets, but never on the contents. In particular there is no early exit
+//! at the first mismatching bit.
+
+use super::{Result, LEN_ZERO, range_start};
+
+/// Returns the 8 bits starting at the absolute bit position pos.
+/// Bits beyond the end of the buffer are returned as zero.
+#[inline]
+fn byte_at(source: &[u8], pos: usize) -> u8 {
+	let index = pos / 8;
+	let shift = pos % 8;
+
+	let high = source[index] << shift;
+
+	// The shift is derived from the offsets only, so branching on it does not leak the data
+	if shift == 0 {
+		high
+	} else {
+		let next = if index + 1 < source.len() { source[index + 1] } else { 0 };
+		high | (next >> (8 - shift))
+	}
+}
+
+/// Compares two ranges of bits in constant time and returns true if they are equal.
+///
+/// Both ranges have the same length, but may start at different (unaligned) positions
+/// in different buffers. All bytes of both ranges are always visited.
+///
+/// Parameters:
+///
+/// - **a** the first data source
+/// - **a_byte_offset** (u32) the number of bytes to skip in a
+/// - **a_bit_offset** (u32) the start position of the bits in a. Zero is the most significant bit
+/// - **b** the second data source
+/// - **b_byte_offset** (u32) the number of bytes to skip in b
+/// - **b_bit_offset** (u32) the start position of the bits in b. Zero is the most significant bit
+/// - **length** (u32) the number of bits to be compared.
+///
+/// ```rust
+/// use bitlab::ct::ct_eq_bits;
+/// let a: Vec<u8> = vec!{ 0b0000_1011, 0b1010_0000 }; // 1011_1010 at bit offset 4
+/// let b: Vec<u8> = vec!{ 0b1011_1010 };
+/// assert!(ct_eq_bits(&a, 0, 4, &b, 0, 0, 8).unwrap());
+/// assert!(!ct_eq_bits(&a, 0, 3, &b, 0, 0, 8).unwrap());
+/// ```
+pub fn ct_eq_bits(a: &[u8], a_byte_offset: u32, a_bit_offset: u32,
+	b: &[u8], b_byte_offset: u32, b_bit_offset: u32, length: u32) -> Result<bool> {
+	if length == 0 { return Err(s!(LEN_ZERO)); }
+
+	let length = length as usize;
+	let a_start = range_start(a.len(), a_byte_offset, a_bit_offset, length)?;
+	let b_start = range_start(b.len(), b_byte_offset, b_bit_offset, length)?;
+
+	let mut diff: u8 = 0;
+
+	// Compare all complete bytes
+	let n_full_bytes = length / 8;
+	for i in 0..n_full_bytes {
+		diff |= byte_at(a, a_start + i * 8) ^ byte_at(b, b_start + i * 8);
+	}
+
+	// Compare the remaining bits, if any. Only the leading bits of the last byte are relevant
+	let n_rest = length % 8;
+	if n_rest > 0 {
+		let mask = 0xFFu8 << (8 - n_rest);
+		let pos = n_full_bytes * 8;
+		diff |= (byte_at(a, a_start + pos) ^ byte_at(b, b_start + pos)) & mask;
+	}
+
+	// Prevent the optimizer from turning the accumulation into an early exit
+	Ok(std::hint::black_box(diff) == 0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn comparing_unaligned_ranges() {
+		let a: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		let b: Vec<u8> = vec!{ 0x00, 0x61, 0x6C, 0x00 };
+
+		// Same alignment
+		assert!(ct_eq_bits(&a, 1, 0, &b, 1, 0, 16).unwrap());
+
+		// 0x616C shifted by 3 bits into c = 0b000_0110_0001_0110_1100_0_0000
+		let c: Vec<u8> = vec!{ 0b0000_1100, 0b0010_1101, 0b1000_0000 };
+		assert!(ct_eq_bits(&a, 1, 0, &c, 0, 3, 16).unwrap());
+		assert!(ct_eq_bits(&a, 0, 8, &c, 0, 3, 13).unwrap());
+		assert!(!ct_eq_bits(&a, 1, 0, &c, 0, 2, 16).unwrap());
+
+		// A difference in the very last bit is detected
+		let d: Vec<u8> = vec!{ 0b0000_1100, 0b0010_1101, 0b1001_0000 };
+		assert!(ct_eq_bits(&c, 0, 3, &d, 0, 3, 16).unwrap());
+		assert!(!ct_eq_bits(&c, 0, 3, &d, 0, 3, 17).unwrap());
+
+		match ct_eq_bits(&a, 4, 1, &b, 0, 0, 8) {
+			Ok(_) => panic!("Missed the range check"),
+			Err(e) => assert_eq!(e, OUT_OF_RANGE_MSG),
+		}
+		assert_eq!(ct_eq_bits(&a, u32::MAX, u32::MAX, &b, 0, 0, u32::MAX).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		match ct_eq_bits(&a, 0, 0, &b, 0, 0, 0) {
+			Ok(_) => panic!("Missed the length check"),
+			Err(e) => assert_eq!(e, LEN_ZERO),
+		}
+	}
+}
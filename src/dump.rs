@@ -0,0 +1,116 @@
+//! Hexdumps with a highlighted range of bits
+//!
+//! Finding an off-by-one error in a bit offset is easier if the bits are shown in their context.
+//! hexdump_highlighted renders the same hexdump as [text::hexdump](../text/fn.hexdump.html) and marks
+//! the bytes, which contain the range. Below each line with marked bytes, these bytes are shown in
+//! binary, where the bits outside of the range are replaced by dots (or not colored).
+//!
+//! ```rust
+//! use bitlab::dump::{hexdump_highlighted, Highlight};
+//! // 4 bits starting at byte 1, bit 7
+//! let text = hexdump_highlighted(b"Hallo", 0, 1, 7, 4, Highlight::Underline).unwrap();
+//! assert_eq!(text, "\
+//! 00000000  48 61 6C 6C 6F                                   Hallo
+//!              ^^ ^^
+//!              .......1 011.....
+//! ");
+//! ```
+
+use std::fmt::Write;
+use super::{Result, OUT_OF_RANGE_MSG, LEN_ZERO, is_within};
+use super::text::{hexdump_line, REVERSE, RESET};
+
+/// How the range of bits is highlighted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Highlight {
+	/// The bytes are underlined with ^^ and the bits outside of the range are shown as dots
+	Underline,
+	/// The bytes and the bits are shown in reverse video with ANSI escape sequences
+	Color,
+}
+
+/// Renders bytes as a hexdump with 16 bytes per line like text::hexdump and highlights the length bits,
+/// which start at byte_offset and bit_offset. The first byte has the address first_address.
+/// Returns an error if length is zero or the range exceeds the bytes.
+pub fn hexdump_highlighted(data: &[u8], first_address: usize, byte_offset: u32, bit_offset: u32, length: u32, style: Highlight) -> Result<String> {
+	if length == 0 {
+		return Err(s!(LEN_ZERO));
+	}
+	if !is_within(data.len(), byte_offset, bit_offset, length) {
+		return Err(s!(OUT_OF_RANGE_MSG));
+	}
+
+	let start = byte_offset as usize * 8 + bit_offset as usize;
+	let end = start + length as usize;
+	let touched = |byte: usize| byte * 8 < end && byte * 8 + 8 > start;
+	let color = style == Highlight::Color;
+	let mut text = String::new();
+
+	for (i, line) in data.chunks(16).enumerate() {
+		let first = i * 16;
+		hexdump_line(&mut text, first_address + first, line, |j| color && touched(first + j));
+
+		let marked: Vec<usize> = (first..first + line.len()).filter(|&byte| touched(byte)).collect();
+		let first_marked = match marked.first() {
+			Some(&byte) => byte,
+			None => continue,
+		};
+		// The column of the first marked byte
+		let indent = " ".repeat(10 + 3 * (first_marked - first));
+
+		if !color {
+			text.push_str(&indent);
+			let markers: Vec<&str> = (first_marked..first + line.len()).map(|byte| if touched(byte) { "^^" } else { "  " }).collect();
+			text.push_str(markers.join(" ").trim_end());
+			text.push('\n');
+		}
+
+		text.push_str(&indent);
+		let bytes: Vec<String> = marked.iter().map(|&byte| {
+			let mut bits = String::new();
+			for k in 0..8 {
+				let position = byte * 8 + k;
+				let digit = if data[byte] & (0x80 >> k) != 0 { '1' } else { '0' };
+				let inside = position >= start && position < end;
+				match (inside, color) {
+					(true, true) => { let _ = write!(bits, "{}{}{}", REVERSE, digit, RESET); },
+					(false, false) => bits.push('.'),
+					_ => bits.push(digit),
+				}
+			}
+			bits
+		}).collect();
+		text.push_str(&bytes.join(" "));
+		text.push('\n');
+	}
+
+	Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn highlighting_bits() {
+		let data: Vec<u8> = (0x40..0x62).collect();
+		// The range spans two lines
+		let text = hexdump_highlighted(&data, 0x100, 15, 4, 12, Highlight::Underline).unwrap();
+		let lines: Vec<&str> = text.lines().collect();
+		assert_eq!(lines.len(), 7);
+		assert_eq!(lines[1], format!("{}^^", " ".repeat(55)));
+		assert_eq!(lines[2], format!("{}....1111", " ".repeat(55)));
+		assert_eq!(lines[3], "00000110  50 51 52 53 54 55 56 57 58 59 5A 5B 5C 5D 5E 5F  PQRSTUVWXYZ[\\]^_");
+		assert_eq!(lines[4], "          ^^");
+		assert_eq!(lines[5], "          01010000");
+		assert!(lines[6].starts_with("00000120  60 61"));
+
+		let text = hexdump_highlighted(&data[..2], 0, 0, 6, 4, Highlight::Color).unwrap();
+		let (r, n) = (REVERSE, RESET);
+		assert_eq!(text, format!("00000000  {r}40{n} {r}41{n}{}  @A\n{}010000{r}0{n}{r}0{n} {r}0{n}{r}1{n}000001\n",
+			" ".repeat(42), " ".repeat(10), r = r, n = n));
+
+		assert_eq!(hexdump_highlighted(&data, 0, 33, 1, 8, Highlight::Color).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(hexdump_highlighted(&data, 0, 0, 0, 0, Highlight::Color).unwrap_err(), LEN_ZERO);
+	}
+}
@@ -0,0 +1,201 @@
+//! Recording of extractions and insertions for debugging decoders
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use super::*;
+use super::text::hexdump;
+
+/// The kind of a recorded operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+	/// A range of bits was extracted
+	Get,
+	/// A range of bits was inserted
+	Set,
+}
+
+/// A recorded extraction or insertion
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+	/// Extraction or insertion
+	pub kind: OperationKind,
+	/// The byte offset as given by the caller
+	pub byte_offset: u32,
+	/// The bit offset as given by the caller
+	pub bit_offset: u32,
+	/// The number of bits
+	pub length: u32,
+	/// The type, as which the bits were interpreted (e.g. "u16")
+	pub interpretation: String,
+	/// The extracted or inserted value or the error message
+	pub result: Result<String>,
+}
+
+/// Wraps a Vec<u8> and records every extraction and insertion.
+///
+/// Since the wrapper implements the same traits as Vec<u8>, it can replace the vector
+/// in a decoder to show its work: explain() renders the recorded operations
+/// as text followed by a hexdump of the data.
+///
+/// ```rust
+/// use bitlab::*;
+/// use bitlab::explain::ExplainBuffer;
+/// let b = ExplainBuffer::new(vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F });
+/// assert_eq!(b.get_u16(1, 7, 3).unwrap(), 5);
+/// assert!(b.explain().starts_with("get   byte 1 bit 7 (bits 15..=17) length 3 as u16 = 5\n"));
+/// ```
+#[derive(Debug, Default)]
+pub struct ExplainBuffer {
+	data: Vec<u8>,
+	log: RefCell<Vec<Operation>>,
+}
+
+impl ExplainBuffer {
+	/// Wraps the data with an empty log
+	pub fn new(data: Vec<u8>) -> ExplainBuffer {
+		ExplainBuffer { data, log: RefCell::new(Vec::new()) }
+	}
+
+	/// Returns the wrapped data
+	pub fn data(&self) -> &Vec<u8> {
+		&self.data
+	}
+
+	/// Returns the wrapped data and discards the log
+	pub fn into_inner(self) -> Vec<u8> {
+		self.data
+	}
+
+	/// Returns a copy of the recorded operations
+	pub fn operations(&self) -> Vec<Operation> {
+		self.log.borrow().clone()
+	}
+
+	/// Clears the log
+	pub fn clear(&self) {
+		self.log.borrow_mut().clear();
+	}
+
+	fn record<T: ToString>(&self, kind: OperationKind, byte_offset: u32, bit_offset: u32, length: u32,
+		interpretation: &str, result: &Result<T>) {
+		self.log.borrow_mut().push(Operation {
+			kind, byte_offset, bit_offset, length,
+			interpretation: s!(interpretation),
+			result: match result {
+				Ok(v) => Ok(v.to_string()),
+				Err(e) => Err(e.clone()),
+			},
+		});
+	}
+
+	/// Renders the recorded operations, one per line, followed by a hexdump of the data
+	pub fn explain(&self) -> String {
+		let mut text = String::new();
+
+		for op in self.log.borrow().iter() {
+			let first = op.byte_offset as u64 * 8 + op.bit_offset as u64;
+			let _ = write!(text, "{:<5} byte {} bit {} (bits {}..={}) length {} as {}",
+				match op.kind { OperationKind::Get => "get", OperationKind::Set => "set" },
+				op.byte_offset, op.bit_offset, first, (first + op.length as u64).saturating_sub(1), op.length, op.interpretation);
+			let _ = match &op.result {
+				Ok(v) => writeln!(text, " = {}", v),
+				Err(e) => writeln!(text, " failed: {}", e),
+			};
+		}
+
+		text.push('\n');
+		text.push_str(&hexdump(&self.data, 0));
+		text
+	}
+}
+
+macro_rules! def_explained_get_fn {
+	($name:ident, $t:ty) => (
+		fn $name(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<$t> {
+			let result = self.data.$name(byte_offset, bit_offset, length);
+			self.record(OperationKind::Get, byte_offset, bit_offset, length, stringify!($t), &result);
+			result
+		}
+	)
+}
+
+impl ExtractBitsFromVecU8 for ExplainBuffer {
+	def_explained_get_fn!(get_u8, u8);
+	def_explained_get_fn!(get_i8, i8);
+	def_explained_get_fn!(get_u16, u16);
+	def_explained_get_fn!(get_i16, i16);
+	def_explained_get_fn!(get_u32, u32);
+	def_explained_get_fn!(get_i32, i32);
+	def_explained_get_fn!(get_u64, u64);
+	def_explained_get_fn!(get_i64, i64);
+	def_explained_get_fn!(get_u128, u128);
+	def_explained_get_fn!(get_i128, i128);
+}
+
+impl InsertBitsIntoVecU8 for ExplainBuffer {
+	fn set<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: std::marker::Sized, T: SignedInfo,
+		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
+		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
+		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
+		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+		T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
+		T : std::string::ToString, T: SingleBits + Copy {
+		let result = self.data.set(byte_offset, bit_offset, length, value).map(|_| value.to_string());
+		self.record(OperationKind::Set, byte_offset, bit_offset, length, std::any::type_name::<T>(), &result);
+		result.map(|_| ())
+	}
+
+	fn set_fields(&mut self, fields: &[(u32, u32, u32, u64)]) -> Result<()> {
+		// Check all fields first, so that nothing is modified on error. The failed field is recorded.
+		for &(byte_offset, bit_offset, length, value) in fields {
+			let check = check_field(self.data.len(), byte_offset, bit_offset, length).and_then(|_| check_value_fits(value, length));
+			if let Err(e) = check {
+				self.record(OperationKind::Set, byte_offset, bit_offset, length, "u64", &Err::<u64, _>(e.clone()));
+				return Err(e);
+			}
+		}
+		for &(byte_offset, bit_offset, length, value) in fields {
+			self.set(byte_offset, bit_offset, length, value)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn explaining_a_decoder() {
+		let mut b = ExplainBuffer::new(vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F });
+		assert_eq!(b.get_u8(0, 1, 3).unwrap(), 4);
+		assert!(b.get_i32(4, 0, 9).is_err());
+		b.set(1, 15, 2, 3u8).unwrap();
+
+		let ops = b.operations();
+		assert_eq!(ops.len(), 3);
+		assert_eq!(ops[0], Operation {
+			kind: OperationKind::Get, byte_offset: 0, bit_offset: 1, length: 3,
+			interpretation: s!("u8"), result: Ok(s!("4")) });
+		assert_eq!(ops[1].result, Err(s!(OUT_OF_RANGE_MSG)));
+		assert_eq!(ops[2].kind, OperationKind::Set);
+
+		assert_eq!(b.explain(),
+			"get   byte 0 bit 1 (bits 1..=3) length 3 as u8 = 4\n\
+			 get   byte 4 bit 0 (bits 32..=40) length 9 as i32 failed: Out of range\n\
+			 set   byte 1 bit 15 (bits 23..=24) length 2 as u8 = 3\n\
+			 \n\
+			 00000000  48 61 6D EC 6F                                   Ham.o\n");
+
+		b.clear();
+		assert!(b.operations().is_empty());
+
+		// Nothing is inserted, if one of the fields is out of range
+		assert_eq!(b.set_fields(&[(0, 0, 8, 0), (4, 4, 5, 0)]).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(b.operations().len(), 1);
+		assert_eq!(b.operations()[0].byte_offset, 4);
+		b.clear();
+		assert_eq!(b.into_inner(), vec!{ 0x48, 0x61, 0x6D, 0xEC, 0x6F });
+	}
+}
@@ -0,0 +1,224 @@
+//! Forward error correction
+
+use super::{Result, LEN_ZERO, LEN_TOO_BIG_MSG, read_bits_from_slice, range_start};
+
+static ZERO_REPEATS_MSG: &str = "The number of repeats must not be zero";
+static DOUBLE_ERROR_MSG: &str = "The Hamming code contains two errors";
+
+/// Decodes a field, which is transmitted several times in a row, by a bitwise majority vote.
+///
+/// The copies of the field follow each other without gaps, i.e. copy k starts
+/// k * length bits after the first one. Returns the majority value and the number of
+/// bits in all copies, which disagree with it. If the vote for a bit is a tie
+/// (only possible for an even number of repeats), the bit is decoded as zero.
+///
+/// Parameters:
+///
+/// - **source** the data source
+/// - **byte_offset** (u32) the number of bytes to skip in source
+/// - **bit_offset** (u32) the start position of the first copy. Zero is the most significant bit
+/// - **length** (u32) the length of a single copy in bits (at most 64)
+/// - **repeats** (u32) the number of copies
+///
+/// ```rust
+/// use bitlab::fec::decode_repetition;
+/// // Three copies of a 4 bit field: 1010 1000 1010 --> 1010 with one disagreeing bit
+/// let v: Vec<u8> = vec!{ 0b1010_1000, 0b1010_0000 };
+/// assert_eq!(decode_repetition(&v, 0, 0, 4, 3).unwrap(), (0b1010, 1));
+/// ```
+pub fn decode_repetition(source: &[u8], byte_offset: u32, bit_offset: u32, length: u32, repeats: u32) -> Result<(u64, u32)> {
+	if length == 0 { return Err(s!(LEN_ZERO)); }
+	if length > 64 { return Err(s!(LEN_TOO_BIG_MSG) + "u64"); }
+	if repeats == 0 { return Err(s!(ZERO_REPEATS_MSG)); }
+
+	let start = range_start(source.len(), byte_offset, bit_offset, length as usize * repeats as usize)?;
+
+	let copies: Vec<u64> = (0..repeats as usize)
+		.map(|k| read_bits_from_slice(source, start + k * length as usize, length))
+		.collect();
+
+	let mut result: u64 = 0;
+	let mut disagreements = 0;
+	for bit in (0..length).rev() {
+		let ones = copies.iter().filter(|&&c| c & (1 << bit) != 0).count() as u32;
+		if ones * 2 > repeats {
+			result |= 1 << bit;
+			disagreements += repeats - ones;
+		} else {
+			disagreements += ones;
+		}
+	}
+
+	Ok((result, disagreements))
+}
+
+// The bit positions 1 to 7 of a Hamming(7,4) code word, where position 1 is the most
+// significant bit. The parity bits are at the positions 1, 2 and 4 and the data bits at 3, 5, 6 and 7.
+fn code_bit(code: u8, position: u32) -> u8 {
+	(code >> (7 - position)) & 1
+}
+
+// Returns the XOR of the positions of all ones, which is zero for a valid code word
+fn syndrome(code: u8) -> u32 {
+	(1..=7).filter(|&p| code_bit(code, p) == 1).fold(0, |s, p| s ^ p)
+}
+
+/// Encodes the 4 least significant bits of data as a 7 bit Hamming(7,4) code word.
+///
+/// The code word is returned in the 7 least significant bits in the usual order p1 p2 d1 p3 d2 d3 d4,
+/// where d1 is the most significant bit of the data.
+///
+/// ```rust
+/// use bitlab::fec::*;
+/// assert_eq!(encode_hamming74(0b1011), 0b011_0011);
+/// assert_eq!(decode_hamming74(0b011_1011), (0b1011, true));
+/// ```
+pub fn encode_hamming74(data: u8) -> u8 {
+	let d = |i: u32| (data >> (3 - i)) & 1;
+	let p1 = d(0) ^ d(1) ^ d(3);
+	let p2 = d(0) ^ d(2) ^ d(3);
+	let p3 = d(1) ^ d(2) ^ d(3);
+	p1 << 6 | p2 << 5 | d(0) << 4 | p3 << 3 | d(1) << 2 | d(2) << 1 | d(3)
+}
+
+/// Decodes the 7 least significant bits of code as a Hamming(7,4) code word.
+/// Returns the data and whether a single bit error has been corrected.
+/// Two errors are miscorrected, use the Hamming(8,4) code to detect them.
+pub fn decode_hamming74(code: u8) -> (u8, bool) {
+	let code = code & 0x7F;
+	let s = syndrome(code);
+	let corrected = if s == 0 { code } else { code ^ (1 << (7 - s)) };
+	let data = code_bit(corrected, 3) << 3 | code_bit(corrected, 5) << 2 | code_bit(corrected, 6) << 1 | code_bit(corrected, 7);
+	(data, s != 0)
+}
+
+/// Encodes the 4 least significant bits of data as an 8 bit Hamming(8,4) code word, which is
+/// the Hamming(7,4) code word followed by a parity bit over all of its bits.
+pub fn encode_hamming84(data: u8) -> u8 {
+	let code = encode_hamming74(data);
+	code << 1 | (code.count_ones() % 2) as u8
+}
+
+/// Decodes a Hamming(8,4) code word. Returns the data and whether a single bit error has been
+/// corrected or an error if two bits are wrong.
+///
+/// ```rust
+/// use bitlab::fec::*;
+/// let code = encode_hamming84(0b1011);
+/// assert_eq!(decode_hamming84(code ^ 0b0010_0000).unwrap(), (0b1011, true));
+/// assert!(decode_hamming84(code ^ 0b0010_0100).is_err());
+/// ```
+pub fn decode_hamming84(code: u8) -> Result<(u8, bool)> {
+	let odd = code.count_ones() % 2 == 1;
+	let (data, corrected) = decode_hamming74(code >> 1);
+	match (corrected, odd) {
+		// A wrong syndrome with an even number of ones needs two errors
+		(true, false) => Err(s!(DOUBLE_ERROR_MSG)),
+		// The overall parity bit itself may be the wrong one
+		(_, true) => Ok((data, true)),
+		(false, false) => Ok((data, false)),
+	}
+}
+
+/// The Hamming codes supported by decode_hamming
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HammingCode {
+	/// 7 bit code words, which correct single errors
+	Hamming74,
+	/// 8 bit code words, which correct single errors and detect double errors
+	Hamming84,
+}
+
+/// Decodes count consecutive Hamming code words, which may start at any bit.
+/// Returns the data nibbles and the number of corrected code words.
+///
+/// Parameters:
+///
+/// - **source** the data source
+/// - **byte_offset** (u32) the number of bytes to skip in source
+/// - **bit_offset** (u32) the start position of the first code word. Zero is the most significant bit
+/// - **count** (u32) the number of code words
+/// - **code** (HammingCode) the length of the code words
+///
+/// ```rust
+/// use bitlab::fec::*;
+/// // The code words of 0xA and 0x5 after a 2 bit header, the first one with an error
+/// let v: Vec<u8> = vec!{ 0b1110_1001, 0b0001_0010, 0b1100_0000 };
+/// assert_eq!(decode_hamming(&v, 0, 2, 2, HammingCode::Hamming84).unwrap(), (vec!{ 0xA, 0x5 }, 1));
+/// ```
+pub fn decode_hamming(source: &[u8], byte_offset: u32, bit_offset: u32, count: u32, code: HammingCode) -> Result<(Vec<u8>, u32)> {
+	let length = match code {
+		HammingCode::Hamming74 => 7,
+		HammingCode::Hamming84 => 8,
+	};
+
+	let start = range_start(source.len(), byte_offset, bit_offset, length as usize * count as usize)?;
+
+	let mut data = Vec::with_capacity(count as usize);
+	let mut n_corrected = 0;
+	for k in 0..count as usize {
+		let word = read_bits_from_slice(source, start + k * length as usize, length) as u8;
+		let (nibble, corrected) = match code {
+			HammingCode::Hamming74 => decode_hamming74(word),
+			HammingCode::Hamming84 => decode_hamming84(word)?,
+		};
+		data.push(nibble);
+		n_corrected += corrected as u32;
+	}
+
+	Ok((data, n_corrected))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn majority_vote() {
+		// Five copies of a 7 bit field starting at bit offset 3
+		// 101 | 1100101 1100101 0100101 0101101 1100100 | 00
+		let v: Vec<u8> = vec!{ 0b1011_1001, 0b0111_0010, 0b1010_0101, 0b0101_1011, 0b1001_0000 };
+		assert_eq!(decode_repetition(&v, 0, 3, 7, 5).unwrap(), (0b110_0101, 4));
+
+		// No errors
+		assert_eq!(decode_repetition(&v, 0, 3, 7, 2).unwrap(), (0b110_0101, 0));
+
+		// The most significant bit is a tie and decoded as zero
+		assert_eq!(decode_repetition(&v, 0, 3, 7, 4).unwrap(), (0b010_0101, 3));
+
+		assert_eq!(decode_repetition(&v, 0, 6, 7, 5).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(decode_repetition(&v, 0, 3, 7, 0).unwrap_err(), ZERO_REPEATS_MSG);
+		assert_eq!(decode_repetition(&v, 0, 3, 0, 5).unwrap_err(), LEN_ZERO);
+	}
+
+	#[test]
+	fn hamming_codes() {
+		for data in 0..16u8 {
+			let code = encode_hamming74(data);
+			assert_eq!(decode_hamming74(code), (data, false));
+			let extended = encode_hamming84(data);
+			assert_eq!(extended >> 1, code);
+			assert_eq!(extended.count_ones() % 2, 0);
+			assert_eq!(decode_hamming84(extended).unwrap(), (data, false));
+
+			for i in 0..8 {
+				if i < 7 {
+					assert_eq!(decode_hamming74(code ^ (1 << i)), (data, true));
+				}
+				assert_eq!(decode_hamming84(extended ^ (1 << i)).unwrap(), (data, true));
+				for j in 0..i {
+					assert_eq!(decode_hamming84(extended ^ (1 << i) ^ (1 << j)).unwrap_err(), DOUBLE_ERROR_MSG);
+				}
+			}
+		}
+
+		// The code words of 1, 2 and 3 at bit offset 5, the second one with an error
+		// 10101 | 1101001 0101011 1000011 | 0000
+		let v: Vec<u8> = vec!{ 0b1010_1110, 0b1001_0101, 0b0111_0000, 0b1100_0000 };
+		assert_eq!(decode_hamming(&v, 0, 5, 3, HammingCode::Hamming74).unwrap(), (vec!{ 1, 2, 3 }, 1));
+		assert_eq!(decode_hamming(&v, 0, 5, 0, HammingCode::Hamming74).unwrap(), (vec!{}, 0));
+		assert_eq!(decode_hamming(&v, 0, 5, 4, HammingCode::Hamming74).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(decode_hamming(&[0b0000_0011], 0, 0, 1, HammingCode::Hamming84).unwrap_err(), DOUBLE_ERROR_MSG);
+	}
+}
@@ -0,0 +1,365 @@
+//! C interface (requires the feature **ffi**)
+//!
+//! The functions wrap the extraction and insertion of bit ranges in byte buffers, the
+//! [`BitReader`](../struct.BitReader.html), the [`BitWriter`](../struct.BitWriter.html) and
+//! the CRC engine [`Crc`](../crc/struct.Crc.html), so that C and C++ programs can use them.
+//! All types are plain C types or opaque pointers, so that a header can be generated with cbindgen.
+//! To build a shared or static library, compile the crate with e.g.
+//!
+//! ```cli
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! The functions return a [`BitlabStatus`](enum.BitlabStatus.html). After an error,
+//! bitlab_last_error() returns the error message.
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use super::*;
+use super::crc::{Crc, CrcAlgorithm};
+
+thread_local! {
+	static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// The result of a call
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitlabStatus {
+	/// Success
+	Ok = 0,
+	/// The operation failed. bitlab_last_error() returns the reason.
+	Error = 1,
+	/// A required pointer was null
+	NullPointer = 2,
+}
+
+fn fail(msg: String) -> BitlabStatus {
+	LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(msg).unwrap_or_default());
+	BitlabStatus::Error
+}
+
+/// Returns the message of the last error in the calling thread. The string is owned by the library
+/// and remains valid until the next failing call in the same thread.
+#[no_mangle]
+pub extern "C" fn bitlab_last_error() -> *const c_char {
+	LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
+
+/// Extracts length bits (at most 64) as an unsigned integer.
+/// Same as [`get_u64`](../trait.ExtractBitsFromVecU8.html#tymethod.get_u64) for a Vec<u8>.
+///
+/// # Safety
+///
+/// data must point to len readable bytes and out must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_get_u64(data: *const u8, len: usize, byte_offset: u32, bit_offset: u32,
+	length: u32, out: *mut u64) -> BitlabStatus {
+	if data.is_null() || out.is_null() { return BitlabStatus::NullPointer; }
+
+	match slice::get_u64(std::slice::from_raw_parts(data, len), byte_offset, bit_offset, length) {
+		Ok(v) => {
+			*out = v;
+			BitlabStatus::Ok
+		},
+		Err(e) => fail(e),
+	}
+}
+
+/// Extracts length bits (at most 64) as a two's complement signed integer.
+/// Same as [`get_i64`](../trait.ExtractBitsFromVecU8.html#tymethod.get_i64) for a Vec<u8>.
+///
+/// # Safety
+///
+/// data must point to len readable bytes and out must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_get_i64(data: *const u8, len: usize, byte_offset: u32, bit_offset: u32,
+	length: u32, out: *mut i64) -> BitlabStatus {
+	if data.is_null() || out.is_null() { return BitlabStatus::NullPointer; }
+
+	match slice::get_i64(std::slice::from_raw_parts(data, len), byte_offset, bit_offset, length) {
+		Ok(v) => {
+			*out = v;
+			BitlabStatus::Ok
+		},
+		Err(e) => fail(e),
+	}
+}
+
+/// Inserts an unsigned value into length bits (at most 64).
+/// Same as [`set`](../trait.InsertBitsIntoVecU8.html#tymethod.set) for a Vec<u8> and a u64 value.
+///
+/// # Safety
+///
+/// data must point to len writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_set_u64(data: *mut u8, len: usize, byte_offset: u32, bit_offset: u32,
+	length: u32, value: u64) -> BitlabStatus {
+	if data.is_null() { return BitlabStatus::NullPointer; }
+
+	match slice::set_u64(std::slice::from_raw_parts_mut(data, len), byte_offset, bit_offset, length, value) {
+		Ok(()) => BitlabStatus::Ok,
+		Err(e) => fail(e),
+	}
+}
+
+/// Inserts a signed value as a two's complement integer into length bits (at most 64).
+/// Same as [`set`](../trait.InsertBitsIntoVecU8.html#tymethod.set) for a Vec<u8> and an i64 value.
+///
+/// # Safety
+///
+/// data must point to len writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_set_i64(data: *mut u8, len: usize, byte_offset: u32, bit_offset: u32,
+	length: u32, value: i64) -> BitlabStatus {
+	if data.is_null() { return BitlabStatus::NullPointer; }
+
+	match slice::set_i64(std::slice::from_raw_parts_mut(data, len), byte_offset, bit_offset, length, value) {
+		Ok(()) => BitlabStatus::Ok,
+		Err(e) => fail(e),
+	}
+}
+
+/// Creates an empty [`BitWriter`](../struct.BitWriter.html). The writer must be released with bitlab_writer_free().
+#[no_mangle]
+pub extern "C" fn bitlab_writer_new() -> *mut BitWriter {
+	Box::into_raw(Box::new(BitWriter::new()))
+}
+
+/// Releases a writer created by bitlab_writer_new(). Null is ignored.
+///
+/// # Safety
+///
+/// writer must be null or a pointer returned by bitlab_writer_new(), which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_writer_free(writer: *mut BitWriter) {
+	if !writer.is_null() {
+		drop(Box::from_raw(writer));
+	}
+}
+
+/// Appends the length (at most 64) least significant bits of value
+///
+/// # Safety
+///
+/// writer must be null or a valid pointer returned by bitlab_writer_new().
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_writer_write_bits(writer: *mut BitWriter, value: u64, length: u32) -> BitlabStatus {
+	match writer.as_mut() {
+		Some(w) => match w.write_bits(value, length) {
+			Ok(()) => BitlabStatus::Ok,
+			Err(e) => fail(e),
+		},
+		None => BitlabStatus::NullPointer,
+	}
+}
+
+/// Returns the number of bits written so far or zero, if writer is null
+///
+/// # Safety
+///
+/// writer must be null or a valid pointer returned by bitlab_writer_new().
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_writer_bit_len(writer: *const BitWriter) -> usize {
+	writer.as_ref().map_or(0, |w| w.bit_len())
+}
+
+/// Returns a pointer to the written bytes and stores their number in len.
+/// The unused bits of the last byte are zero. The pointer remains valid until the next
+/// write or until the writer is released. Returns null, if writer is null.
+///
+/// # Safety
+///
+/// writer must be null or a valid pointer returned by bitlab_writer_new() and len must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_writer_bytes(writer: *const BitWriter, len: *mut usize) -> *const u8 {
+	match writer.as_ref() {
+		Some(w) => {
+			let bytes = w.as_buffer().as_bytes();
+			if !len.is_null() { *len = bytes.len(); }
+			bytes.as_ptr()
+		},
+		None => std::ptr::null(),
+	}
+}
+
+/// Creates a [`BitReader`](../struct.BitReader.html), which reads len bytes at data.
+/// The reader must be released with bitlab_reader_free(). Returns null, if data is null.
+///
+/// # Safety
+///
+/// data must point to len readable bytes, which remain valid and unmodified until the reader is released.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_reader_new(data: *const u8, len: usize) -> *mut BitReader<'static> {
+	if data.is_null() { return std::ptr::null_mut(); }
+	Box::into_raw(Box::new(BitReader::new(std::slice::from_raw_parts(data, len))))
+}
+
+/// Releases a reader created by bitlab_reader_new(). Null is ignored.
+///
+/// # Safety
+///
+/// reader must be null or a pointer returned by bitlab_reader_new(), which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_reader_free(reader: *mut BitReader<'static>) {
+	if !reader.is_null() {
+		drop(Box::from_raw(reader));
+	}
+}
+
+/// Reads length bits (at most 64) as an unsigned integer. A failing read does not move the position.
+///
+/// # Safety
+///
+/// reader must be null or a valid pointer returned by bitlab_reader_new() and out must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_reader_read_bits(reader: *mut BitReader<'static>, length: u32, out: *mut u64) -> BitlabStatus {
+	if out.is_null() { return BitlabStatus::NullPointer; }
+
+	match reader.as_mut() {
+		Some(r) => match r.read_bits(length) {
+			Ok(v) => {
+				*out = v;
+				BitlabStatus::Ok
+			},
+			Err(e) => fail(e),
+		},
+		None => BitlabStatus::NullPointer,
+	}
+}
+
+/// Reads length bits (at most 64) as a two's complement signed integer. A failing read does not move the position.
+///
+/// # Safety
+///
+/// reader must be null or a valid pointer returned by bitlab_reader_new() and out must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_reader_read_signed_bits(reader: *mut BitReader<'static>, length: u32, out: *mut i64) -> BitlabStatus {
+	if out.is_null() { return BitlabStatus::NullPointer; }
+
+	match reader.as_mut() {
+		Some(r) => match r.read_signed_bits(length) {
+			Ok(v) => {
+				*out = v;
+				BitlabStatus::Ok
+			},
+			Err(e) => fail(e),
+		},
+		None => BitlabStatus::NullPointer,
+	}
+}
+
+/// Returns the number of bits read so far or zero, if reader is null
+///
+/// # Safety
+///
+/// reader must be null or a valid pointer returned by bitlab_reader_new().
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_reader_position(reader: *const BitReader<'static>) -> usize {
+	reader.as_ref().map_or(0, |r| r.position())
+}
+
+/// Creates a CRC engine for the algorithm with the given parameters, see
+/// [`CrcAlgorithm`](../crc/struct.CrcAlgorithm.html). The engine must be released with bitlab_crc_free().
+/// Returns null for invalid parameters, in which case bitlab_last_error() returns the reason.
+#[no_mangle]
+pub extern "C" fn bitlab_crc_new(width: u32, poly: u64, init: u64, refin: bool, refout: bool, xorout: u64) -> *mut Crc {
+	match Crc::new(&CrcAlgorithm { width, poly, init, refin, refout, xorout }) {
+		Ok(crc) => Box::into_raw(Box::new(crc)),
+		Err(e) => {
+			fail(e);
+			std::ptr::null_mut()
+		},
+	}
+}
+
+/// Releases an engine created by bitlab_crc_new(). Null is ignored.
+///
+/// # Safety
+///
+/// crc must be null or a pointer returned by bitlab_crc_new(), which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_crc_free(crc: *mut Crc) {
+	if !crc.is_null() {
+		drop(Box::from_raw(crc));
+	}
+}
+
+/// Computes the CRC of len bytes at data
+///
+/// # Safety
+///
+/// crc must be null or a valid pointer returned by bitlab_crc_new(), data must point to len readable bytes
+/// and out must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitlab_crc_checksum(crc: *const Crc, data: *const u8, len: usize, out: *mut u64) -> BitlabStatus {
+	if data.is_null() || out.is_null() { return BitlabStatus::NullPointer; }
+
+	match crc.as_ref() {
+		Some(c) => {
+			*out = c.checksum(std::slice::from_raw_parts(data, len));
+			BitlabStatus::Ok
+		},
+		None => BitlabStatus::NullPointer,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::ffi::CStr;
+
+	#[test]
+	fn calling_through_the_c_interface() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		let mut u: u64 = 0;
+		let mut i: i64 = 0;
+		unsafe {
+			assert_eq!(bitlab_get_u64(v.as_ptr(), v.len(), 1, 7, 3, &mut u), BitlabStatus::Ok);
+			assert_eq!(u, 5);
+			assert_eq!(bitlab_get_i64(v.as_ptr(), v.len(), 1, 7, 3, &mut i), BitlabStatus::Ok);
+			assert_eq!(i, -3);
+
+			assert_eq!(bitlab_get_u64(v.as_ptr(), v.len(), 4, 1, 8, &mut u), BitlabStatus::Error);
+			assert_eq!(CStr::from_ptr(bitlab_last_error()).to_str().unwrap(), OUT_OF_RANGE_MSG);
+			assert_eq!(bitlab_get_u64(std::ptr::null(), 0, 0, 0, 1, &mut u), BitlabStatus::NullPointer);
+
+			assert_eq!(bitlab_set_i64(v.as_mut_ptr(), v.len(), 0, 4, 4, -1), BitlabStatus::Ok);
+			assert_eq!(bitlab_set_u64(v.as_mut_ptr(), v.len(), 4, 0, 8, 0x21), BitlabStatus::Ok);
+			assert_eq!(v, vec!{ 0x4F, 0x61, 0x6C, 0x6C, 0x21 });
+			assert_eq!(bitlab_set_u64(v.as_mut_ptr(), v.len(), 0, 0, 3, 8), BitlabStatus::Error);
+			assert_eq!(CStr::from_ptr(bitlab_last_error()).to_str().unwrap(),
+				"Failed to insert 8 as a 3 bit unsigned integer variable, since it requires at least 4 bits.");
+
+			let w = bitlab_writer_new();
+			assert_eq!(bitlab_writer_write_bits(w, 0b101, 3), BitlabStatus::Ok);
+			assert_eq!(bitlab_writer_write_bits(w, 0xFF, 8), BitlabStatus::Ok);
+			assert_eq!(bitlab_writer_bit_len(w), 11);
+			let mut len = 0;
+			let bytes = bitlab_writer_bytes(w, &mut len);
+			assert_eq!(std::slice::from_raw_parts(bytes, len), &[0b1011_1111, 0b1110_0000]);
+			bitlab_writer_free(w);
+
+			assert_eq!(bitlab_writer_write_bits(std::ptr::null_mut(), 1, 1), BitlabStatus::NullPointer);
+
+			let r = bitlab_reader_new(v.as_ptr(), v.len());
+			assert_eq!(bitlab_reader_read_bits(r, 4, &mut u), BitlabStatus::Ok);
+			assert_eq!(u, 4);
+			assert_eq!(bitlab_reader_read_signed_bits(r, 4, &mut i), BitlabStatus::Ok);
+			assert_eq!(i, -1);
+			assert_eq!(bitlab_reader_read_bits(r, 33, &mut u), BitlabStatus::Error);
+			assert_eq!(bitlab_reader_position(r), 8);
+			bitlab_reader_free(r);
+			assert!(bitlab_reader_new(std::ptr::null(), 0).is_null());
+			assert_eq!(bitlab_reader_read_bits(std::ptr::null_mut(), 1, &mut u), BitlabStatus::NullPointer);
+
+			let c = bitlab_crc_new(32, 0x04C1_1DB7, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF);
+			assert_eq!(bitlab_crc_checksum(c, b"123456789".as_ptr(), 9, &mut u), BitlabStatus::Ok);
+			assert_eq!(u, 0xCBF4_3926);
+			bitlab_crc_free(c);
+			assert!(bitlab_crc_new(0, 0, 0, false, false, 0).is_null());
+			assert_eq!(bitlab_crc_checksum(std::ptr::null(), v.as_ptr(), v.len(), &mut u), BitlabStatus::NullPointer);
+		}
+	}
+}
@@ -0,0 +1,260 @@
+//! Typed descriptors of bit fields
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use super::*;
+
+/// Describes the position, the length and the type of a bit field, so that a protocol can be
+/// defined once as a set of constants and used for both parsing and serialization.
+///
+/// ```rust
+/// use bitlab::*;
+/// // The first bits of an IPv4 header
+/// const VERSION: BitField<u8> = BitField::new(0, 0, 4);
+/// const IHL: BitField<u8> = BitField::new(0, 4, 4);
+/// const TOTAL_LENGTH: BitField<u16> = BitField::new(2, 0, 16);
+///
+/// let mut header = vec!{ 0u8; 4 };
+/// VERSION.write(&mut header, 4).unwrap();
+/// IHL.write(&mut header, 5).unwrap();
+/// TOTAL_LENGTH.write(&mut header, 1500).unwrap();
+/// assert_eq!(header, vec!{ 0x45, 0x00, 0x05, 0xDC });
+/// assert_eq!(IHL.read(&header).unwrap(), 5);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitField<T> {
+	byte_offset: u32,
+	bit_offset: u32,
+	length: u32,
+	value_type: PhantomData<T>,
+}
+
+impl<T> BitField<T> {
+	/// Creates a field of length bits, which starts at byte_offset and bit_offset.
+	/// The range is checked when the field is read or written.
+	pub const fn new(byte_offset: u32, bit_offset: u32, length: u32) -> BitField<T> {
+		BitField { byte_offset, bit_offset, length, value_type: PhantomData }
+	}
+
+	/// Returns the number of bytes to skip
+	pub fn byte_offset(&self) -> u32 {
+		self.byte_offset
+	}
+
+	/// Returns the start position within the bytes after byte_offset. Zero is the most significant bit
+	pub fn bit_offset(&self) -> u32 {
+		self.bit_offset
+	}
+
+	/// Returns the number of bits
+	pub fn length(&self) -> u32 {
+		self.length
+	}
+}
+
+impl<T: BitTarget> BitField<T> {
+	/// Extracts the field from a Vec<u8> or a byte slice
+	pub fn read<V: ExtractBitsFromVecU8 + ?Sized>(&self, source: &V) -> Result<T> {
+		source.get_at(self.byte_offset, self.bit_offset, self.length)
+	}
+}
+
+impl<T> BitField<T>
+	where T: std::marker::Sized, T: SignedInfo,
+	T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
+	T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
+	T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
+	T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+	T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
+	T : std::string::ToString, T: SingleBits + Copy {
+
+	/// Inserts value into the field of a Vec<u8> or a mutable byte slice
+	pub fn write<V: InsertBitsIntoVecU8 + ?Sized>(&self, target: &mut V, value: T) -> Result<()> {
+		target.set(self.byte_offset, self.bit_offset, self.length, value)
+	}
+}
+
+/// A bit field with a fixed position and length, which are checked at compile time.
+///
+/// BIT_OFFSET is the start of the field, where zero is the most significant bit of the first byte.
+/// Creating a field, whose length is zero or exceeds the size of T, fails to compile. Reading
+/// and writing works on byte arrays, whose size is known at compile time as well, so that
+/// a field beyond the end of the array fails to compile instead of returning an error.
+///
+/// ```rust
+/// use bitlab::*;
+/// const SEQUENCE: Field<12, 20, u32> = Field::new();
+/// let mut frame = [0u8; 4];
+/// SEQUENCE.write(&mut frame, 0xABCDE).unwrap();
+/// assert_eq!(frame, [0x00, 0x0A, 0xBC, 0xDE]);
+/// // Cannot fail, so there is no Result
+/// assert_eq!(SEQUENCE.read(&frame), 0xABCDE);
+/// ```
+///
+/// ```rust,compile_fail
+/// use bitlab::*;
+/// // 9 bits do not fit into a u8
+/// let field: Field<0, 9, u8> = Field::new();
+/// ```
+///
+/// ```rust,compile_fail
+/// use bitlab::*;
+/// // 20 bits starting at bit 16 do not fit into 4 bytes
+/// const SEQUENCE: Field<16, 20, u32> = Field::new();
+/// SEQUENCE.read(&[0u8; 4]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Field<const BIT_OFFSET: u32, const LEN: u32, T> {
+	value_type: PhantomData<T>,
+}
+
+impl<const BIT_OFFSET: u32, const LEN: u32, T> Field<BIT_OFFSET, LEN, T> {
+	/// Creates the field. Fails to compile if LEN is zero or exceeds the size of T.
+	pub const fn new() -> Field<BIT_OFFSET, LEN, T> {
+		const { assert!(LEN > 0 && LEN as usize <= std::mem::size_of::<T>() * 8, "The length of the field does not fit the type") };
+		Field { value_type: PhantomData }
+	}
+
+	/// Returns a BitField with the same position and length for data of unknown size
+	pub const fn bit_field(&self) -> BitField<T> {
+		BitField::new(0, BIT_OFFSET, LEN)
+	}
+}
+
+impl<const BIT_OFFSET: u32, const LEN: u32, T> Default for Field<BIT_OFFSET, LEN, T> {
+	fn default() -> Field<BIT_OFFSET, LEN, T> {
+		Field::new()
+	}
+}
+
+impl<const BIT_OFFSET: u32, const LEN: u32, T: BitTarget> Field<BIT_OFFSET, LEN, T> {
+	/// Extracts the field from an array. Fails to compile if the field exceeds the array.
+	pub fn read<const N: usize>(&self, source: &[u8; N]) -> T {
+		const { assert!(BIT_OFFSET as usize + LEN as usize <= N * 8, "The field exceeds the array") };
+		match source[..].get_at(0, BIT_OFFSET, LEN) {
+			Ok(value) => value,
+			Err(e) => unreachable!("The range has been checked at compile time: {}", e),
+		}
+	}
+}
+
+impl<const BIT_OFFSET: u32, const LEN: u32, T> Field<BIT_OFFSET, LEN, T>
+	where T: std::marker::Sized, T: SignedInfo,
+	T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
+	T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
+	T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
+	T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+	T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
+	T : std::string::ToString, T: SingleBits + Copy {
+
+	/// Inserts value into the field of an array. Fails to compile if the field exceeds the array.
+	/// Returns an error if the value does not fit into LEN bits.
+	pub fn write<const N: usize>(&self, target: &mut [u8; N], value: T) -> Result<()> {
+		const { assert!(BIT_OFFSET as usize + LEN as usize <= N * 8, "The field exceeds the array") };
+		target[..].set(0, BIT_OFFSET, LEN, value)
+	}
+}
+
+/// Iterates over consecutive fields of the same length in a buffer. See ExtractBitsFromVecU8::iter_fields.
+#[derive(Clone, Debug)]
+pub struct FieldIter<'a, V: ?Sized> {
+	source: &'a V,
+	position: u64,
+	field_len: u32,
+	done: bool,
+}
+
+impl<'a, V: ExtractBitsFromVecU8 + ?Sized> FieldIter<'a, V> {
+	pub(crate) fn new(source: &'a V, byte_offset: u32, bit_offset: u32, field_len: u32) -> FieldIter<'a, V> {
+		FieldIter { source, position: byte_offset as u64 * 8 + bit_offset as u64, field_len, done: false }
+	}
+}
+
+impl<'a, V: ExtractBitsFromVecU8 + ?Sized> Iterator for FieldIter<'a, V> {
+	type Item = Result<u64>;
+
+	fn next(&mut self) -> Option<Result<u64>> {
+		if self.done {
+			return None;
+		}
+		if self.field_len == 0 || self.field_len > 64 {
+			// An invalid field length is reported once
+			self.done = true;
+			return Some(Err(if self.field_len == 0 { s!(LEN_ZERO) } else { s!(LEN_TOO_BIG_MSG) + "u64" }));
+		}
+		let byte_offset = match u32::try_from(self.position / 8) {
+			Ok(byte_offset) => byte_offset,
+			Err(_) => { self.done = true; return None; },
+		};
+		match self.source.get_u64(byte_offset, (self.position % 8) as u32, self.field_len) {
+			Ok(value) => {
+				self.position += self.field_len as u64;
+				Some(Ok(value))
+			},
+			// The buffer ends before the next field
+			Err(_) => { self.done = true; None },
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn typed_fields() {
+		const FLAGS: BitField<u8> = BitField::new(0, 5, 3);
+		const OFFSET: BitField<i16> = BitField::new(1, 3, 13);
+		const TIMESTAMP: BitField<u64> = BitField::new(3, 0, 40);
+
+		let mut packet = [0u8; 8];
+		FLAGS.write(&mut packet[..], 0b101).unwrap();
+		OFFSET.write(&mut packet[..], -1000).unwrap();
+		TIMESTAMP.write(&mut packet[..], 0x12_3456_789A).unwrap();
+		assert_eq!(packet, [0x05, 0x1C, 0x18, 0x12, 0x34, 0x56, 0x78, 0x9A]);
+		assert_eq!(FLAGS.read(&packet[..]).unwrap(), 0b101);
+		assert_eq!(OFFSET.read(&packet[..]).unwrap(), -1000);
+
+		let v = packet.to_vec();
+		assert_eq!(TIMESTAMP.read(&v).unwrap(), 0x12_3456_789A);
+		assert_eq!(BitField::<u8>::new(7, 6, 3).read(&v).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(FLAGS.write(&mut packet[..], 8).is_err());
+		assert_eq!((OFFSET.byte_offset(), OFFSET.bit_offset(), OFFSET.length()), (1, 3, 13));
+	}
+
+	#[test]
+	fn compile_time_checked_fields() {
+		const KIND: Field<0, 3, u8> = Field::new();
+		const VALUE: Field<3, 29, i32> = Field::new();
+		const CRC: Field<32, 8, u8> = Field::new();
+
+		let mut frame = [0u8; 5];
+		KIND.write(&mut frame, 6).unwrap();
+		VALUE.write(&mut frame, -2).unwrap();
+		CRC.write(&mut frame, 0x5A).unwrap();
+		assert_eq!(frame, [0xDF, 0xFF, 0xFF, 0xFE, 0x5A]);
+		assert_eq!(KIND.read(&frame), 6);
+		assert_eq!(VALUE.read(&frame), -2);
+		assert_eq!(CRC.read(&frame), 0x5A);
+		assert!(KIND.write(&mut frame, 8).is_err());
+		assert_eq!(VALUE.bit_field().read(&frame.to_vec()).unwrap(), -2);
+	}
+
+	#[test]
+	fn iterating_over_fields() {
+		// Four 10 bit samples
+		let v: Vec<u8> = vec!{ 0x00, 0x7F, 0xFF, 0x00, 0x01 };
+		let samples: Vec<u64> = v.iter_fields(0, 0, 10).map(|x| x.unwrap()).collect();
+		assert_eq!(samples, vec!{ 1, 0x3FF, 0x3C0, 1 });
+
+		// The trailing bits, which do not make up a whole field, are ignored
+		assert_eq!(v[..].iter_fields(0, 3, 12).count(), 3);
+		assert_eq!(v.iter_fields(4, 7, 2).count(), 0);
+		assert_eq!(v.iter_fields(9, 0, 8).count(), 0);
+
+		let mut invalid = v.iter_fields(0, 0, 0);
+		assert_eq!(invalid.next().unwrap().unwrap_err(), LEN_ZERO);
+		assert!(invalid.next().is_none());
+		assert_eq!(v.iter_fields(0, 0, 65).next().unwrap().unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u64");
+	}
+}
@@ -0,0 +1,152 @@
+//! Checked construction of frames with a fixed layout
+
+use super::{Result, Layout, Value, DynamicBits, BitValue, LEN_ZERO, LEN_TOO_BIG_MSG, check_value_fits};
+
+static NO_SUCH_FIELD_MSG: &str = "There is no field named ";
+static WRITTEN_TWICE_MSG: &str = "The field has already been written: ";
+static NOT_WRITTEN_MSG: &str = "The fields have not been written: ";
+
+/// Builds a frame from the fields of a [`Layout`](struct.Layout.html), each of which must be written exactly once.
+///
+/// Composing a header with repeated set calls silently leaves a forgotten field at zero.
+/// The builder instead returns an error from finish, which names the fields without a value,
+/// and refuses to write a field twice. The fields follow each other without gaps, starting
+/// at the **most** significant bit of the first byte. The unused bits of the last byte are zero.
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut frame = FrameBuilder::new(&[("version", 4), ("ihl", 4), ("reserved", 3), ("offset", 13)]).unwrap();
+/// frame.set("version", 4u8).unwrap();
+/// frame.set("ihl", 5u8).unwrap();
+/// frame.set("offset", 185u16).unwrap();
+/// assert_eq!(frame.clone().finish().unwrap_err(), "The fields have not been written: reserved");
+///
+/// frame.set("reserved", 0u8).unwrap();
+/// assert_eq!(frame.finish().unwrap(), vec!{ 0x45, 0x00, 0xB9 });
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameBuilder {
+	layout: Layout,
+	written: Vec<bool>,
+	bytes: Vec<u8>,
+}
+
+impl FrameBuilder {
+	/// Creates a builder for the fields in the given order, which are pairs of a name and a length of 1 to 64 bits.
+	/// Returns an error for an invalid or duplicate name or an invalid length, see Layout::parse.
+	pub fn new(fields: &[(&str, u32)]) -> Result<FrameBuilder> {
+		let mut description = String::new();
+		for &(name, length) in fields {
+			if length == 0 {
+				return Err(s!(LEN_ZERO));
+			}
+			if length > 64 {
+				return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+			}
+			description += &format!("{}:u{},", name, length);
+		}
+		Ok(FrameBuilder::from_layout(Layout::parse(&description)?))
+	}
+
+	/// Creates a builder for the fields of a layout
+	pub fn from_layout(layout: Layout) -> FrameBuilder {
+		FrameBuilder {
+			written: vec!{ false; layout.fields().len() },
+			bytes: vec!{ 0u8; layout.bit_len().div_ceil(8) as usize },
+			layout,
+		}
+	}
+
+	/// Returns the total number of bits of the fields
+	pub fn bit_len(&self) -> usize {
+		self.layout.bit_len() as usize
+	}
+
+	/// Writes the integer value into the field with the given name. Returns an error if there is no such field,
+	/// if the field has already been written or if the value requires more bits than the field has.
+	/// Signed values are written in two's complement.
+	pub fn set<T: BitValue>(&mut self, name: &str, value: T) -> Result<()> {
+		// Check the value before it is narrowed to 64 bits
+		let index = self.index(name)?;
+		check_value_fits(value, self.layout.fields()[index].length)?;
+
+		if value.is_signed() {
+			self.set_value(name, &Value::I64(value.as_()))
+		} else {
+			self.set_value(name, &Value::U64(value.as_()))
+		}
+	}
+
+	/// Same as set for any Value, e.g. a float or the bytes of a field of another type in the layout.
+	/// See DynamicBits::set_dyn.
+	pub fn set_value(&mut self, name: &str, value: &Value) -> Result<()> {
+		let index = self.index(name)?;
+		if self.written[index] {
+			return Err(s!(WRITTEN_TWICE_MSG) + name);
+		}
+		let field = &self.layout.fields()[index];
+		self.bytes.set_dyn(0, field.bit_offset, field.length, value)?;
+		self.written[index] = true;
+		Ok(())
+	}
+
+	// Returns the index of the field with the given name
+	fn index(&self, name: &str) -> Result<usize> {
+		self.layout.fields().iter().position(|f| f.name == name).ok_or_else(|| s!(NO_SUCH_FIELD_MSG) + name)
+	}
+
+	/// Returns the names of the fields, which have not been written yet
+	pub fn missing(&self) -> Vec<&str> {
+		self.layout.fields().iter().zip(self.written.iter()).filter(|(_, &w)| !w).map(|(f, _)| f.name.as_str()).collect()
+	}
+
+	/// Consumes the builder and returns the bytes of the frame. Returns an error if a field has not been written.
+	pub fn finish(self) -> Result<Vec<u8>> {
+		let missing = self.missing();
+		if !missing.is_empty() {
+			return Err(s!(NOT_WRITTEN_MSG) + &missing.join(", "));
+		}
+		Ok(self.bytes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn building_frames() {
+		let mut frame = FrameBuilder::new(&[("flag", 1), ("delta", 5), ("id", 64), ("crc", 3)]).unwrap();
+		assert_eq!(frame.bit_len(), 73);
+		assert_eq!(frame.missing(), vec!{ "flag", "delta", "id", "crc" });
+
+		frame.set("delta", -3i8).unwrap();
+		assert_eq!(frame.set("delta", 1u8).unwrap_err(), s!(WRITTEN_TWICE_MSG) + "delta");
+		assert_eq!(frame.set("length", 1u8).unwrap_err(), s!(NO_SUCH_FIELD_MSG) + "length");
+		assert!(frame.set("crc", 8u8).is_err());
+		// 128 bit values are checked before they are narrowed to 64 bits
+		assert!(frame.set("flag", 0x1_0000_0000_0000_0001u128).is_err());
+		assert!(frame.set("crc", i128::MIN + 1).is_err());
+		frame.set("id", u64::MAX).unwrap();
+		assert_eq!(frame.clone().finish().unwrap_err(), s!(NOT_WRITTEN_MSG) + "flag, crc");
+
+		frame.set("flag", 1u8).unwrap();
+		frame.set("crc", 0b101u8).unwrap();
+		assert!(frame.missing().is_empty());
+		// 1 11101 111... 101 and zero padding
+		assert_eq!(frame.finish().unwrap(), vec!{ 0xF7, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0x80 });
+
+		assert_eq!(FrameBuilder::new(&[("a", 1), ("a", 2)]).unwrap_err(), "Duplicate field name a");
+		assert_eq!(FrameBuilder::new(&[("a", 0)]).unwrap_err(), LEN_ZERO);
+		assert!(FrameBuilder::new(&[("a", 65)]).is_err());
+		assert!(FrameBuilder::new(&[("a,b", 1)]).is_err());
+		assert_eq!(FrameBuilder::new(&[]).unwrap().finish().unwrap(), Vec::<u8>::new());
+
+		// Layouts with other types than integers
+		let mut frame = FrameBuilder::from_layout(Layout::parse("x:f32, id:bytes2").unwrap());
+		frame.set_value("x", &Value::F32(-0.5)).unwrap();
+		assert!(frame.set_value("id", &Value::Bytes(vec!{ 0xBE })).is_err());
+		frame.set_value("id", &Value::Bytes(vec!{ 0xBE, 0xEF })).unwrap();
+		assert_eq!(frame.finish().unwrap(), vec!{ 0xBF, 0x00, 0x00, 0x00, 0xBE, 0xEF });
+	}
+}
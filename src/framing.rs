@@ -0,0 +1,159 @@
+//! Extraction of frames delimited by flag sequences
+//!
+//! Serial links like HDLC and PPP delimit the frames with a flag byte (0x7E). Since a capture
+//! does not necessarily start at a byte boundary, the opening flag is searched at any bit offset.
+//! The escaping of flags within the payload is undone:
+//!
+//! - **Bit stuffing** (HDLC, synchronous PPP): A zero follows every five consecutive ones.
+//! - **Byte stuffing** (asynchronous PPP): The flag and the escape byte are sent as the escape
+//!   byte 0x7D followed by the original byte XOR 0x20.
+//!
+//! ```rust
+//! use bitlab::framing::Framing;
+//! // A frame with the payload 0x7E 0x01 shifted by 4 bits
+//! let capture = [0xF7, 0xE7, 0xD5, 0xE0, 0x17, 0xEF];
+//! let (payload, end) = Framing::PPP.next_frame(&capture, 0).unwrap().unwrap();
+//! assert_eq!(payload, vec!{ 0x7E, 0x01 });
+//! assert_eq!(end, 36);
+//! ```
+
+use super::{Result, BitBuffer, BitScan, read_bits_from_slice};
+use super::stuffing::BitStuffing;
+
+static INCOMPLETE_BYTE_MSG: &str = "The payload is not a whole number of bytes";
+static INCOMPLETE_ESCAPE_MSG: &str = "The payload ends with an escape byte";
+
+/// How flags within the payload are escaped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Escaping {
+	/// The payload is bit stuffed
+	Bits(BitStuffing),
+	/// The payload is byte stuffed with an escape byte. The escaped byte is XORed with the second value.
+	Bytes(u8, u8),
+	/// The payload is not escaped
+	None,
+}
+
+/// Describes the flag and the escaping of frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Framing {
+	/// The flag byte, which delimits the frames
+	pub flag: u8,
+	/// The escaping of the payload
+	pub escaping: Escaping,
+}
+
+impl Framing {
+	/// HDLC and synchronous PPP: Flag 0x7E with bit stuffing
+	pub const HDLC: Framing = Framing { flag: 0x7E, escaping: Escaping::Bits(BitStuffing::HDLC) };
+
+	/// Asynchronous PPP (RFC 1662): Flag 0x7E with the escape byte 0x7D
+	pub const PPP: Framing = Framing { flag: 0x7E, escaping: Escaping::Bytes(0x7D, 0x20) };
+
+	/// Searches the next frame starting at from_bit and returns its unescaped payload and the bit
+	/// index of the closing flag, from which the search for the following frame continues.
+	///
+	/// Consecutive flags between two frames are skipped. Returns None if there is no complete frame,
+	/// or an error if the payload cannot be unescaped.
+	pub fn next_frame(&self, source: &[u8], from_bit: usize) -> Result<Option<(Vec<u8>, usize)>> {
+		let bit_len = source.len() * 8;
+		let mut start = match source.find_pattern(self.flag as u64, 8, from_bit)? {
+			Some(start) => start + 8,
+			None => return Ok(None),
+		};
+
+		loop {
+			// The closing flag. Byte stuffed payloads are aligned to the opening flag.
+			let end = match self.escaping {
+				Escaping::Bytes(_, _) => (start..)
+					.step_by(8)
+					.take_while(|&pos| pos + 8 <= bit_len)
+					.find(|&pos| read_bits_from_slice(source, pos, 8) == self.flag as u64),
+				_ => source.find_pattern(self.flag as u64, 8, start)?,
+			};
+			let end = match end {
+				Some(end) => end,
+				None => return Ok(None),
+			};
+
+			if end == start {
+				// Skip consecutive flags
+				start += 8;
+				continue;
+			}
+
+			return Ok(Some((self.unescape(source, start, end)?, end)));
+		}
+	}
+
+	// Removes the escaping from the payload bits between start and end
+	fn unescape(&self, source: &[u8], start: usize, end: usize) -> Result<Vec<u8>> {
+		let mut bits = BitBuffer::new();
+		for pos in (start..end).step_by(64) {
+			let n = std::cmp::min(64, end - pos) as u32;
+			bits.push_bits(read_bits_from_slice(source, pos, n), n)?;
+		}
+
+		if let Escaping::Bits(rule) = self.escaping {
+			bits = rule.destuff(&bits)?;
+		}
+		if !bits.bit_len().is_multiple_of(8) {
+			return Err(s!(INCOMPLETE_BYTE_MSG));
+		}
+		let bytes = bits.into_bytes();
+
+		match self.escaping {
+			Escaping::Bytes(escape, xor) => {
+				let mut result = Vec::with_capacity(bytes.len());
+				let mut iter = bytes.into_iter();
+				while let Some(byte) = iter.next() {
+					if byte == escape {
+						match iter.next() {
+							Some(escaped) => result.push(escaped ^ xor),
+							None => return Err(s!(INCOMPLETE_ESCAPE_MSG)),
+						}
+					} else {
+						result.push(byte);
+					}
+				}
+				Ok(result)
+			},
+			_ => Ok(bytes),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn extracting_frames() {
+		// Two HDLC frames separated by two flags, 3 bits into the capture: 0x01 0xFF is sent as 0000000 1111 0 1111
+		let mut w = crate::BitWriter::new();
+		w.write_bits(0b101, 3).unwrap();
+		for &(value, length) in &[(0x7E, 8), (0x01, 8), (0b1_1110_1111, 9), (0x7E, 8), (0x7E, 8), (0x42, 8), (0x7E, 8)] {
+			w.write_bits(value, length).unwrap();
+		}
+		let capture = w.into_buffer().into_bytes();
+
+		let (first, end) = Framing::HDLC.next_frame(&capture, 0).unwrap().unwrap();
+		assert_eq!(first, vec!{ 0x01, 0xFF });
+		assert_eq!(end, 28);
+		let (second, end) = Framing::HDLC.next_frame(&capture, end).unwrap().unwrap();
+		assert_eq!(second, vec!{ 0x42 });
+		assert_eq!(Framing::HDLC.next_frame(&capture, end).unwrap(), None);
+
+		// The payload is 9 bits after destuffing
+		let bad = [0x7E, 0x01, 0x3F, 0x00];
+		assert_eq!(Framing::HDLC.next_frame(&bad, 0).unwrap_err(), INCOMPLETE_BYTE_MSG);
+
+		let ppp = [0x7E, 0x7D, 0x5D, 0x7D, 0x5E, 0x11, 0x7E];
+		assert_eq!(Framing::PPP.next_frame(&ppp, 0).unwrap(), Some((vec!{ 0x7D, 0x7E, 0x11 }, 48)));
+		let bad = [0x7E, 0x11, 0x7D, 0x7E];
+		assert_eq!(Framing::PPP.next_frame(&bad, 0).unwrap_err(), INCOMPLETE_ESCAPE_MSG);
+
+		let plain = Framing { flag: 0xAA, escaping: Escaping::None };
+		assert_eq!(plain.next_frame(&[0x00, 0xAA, 0x12, 0x34, 0xAA], 0).unwrap(), Some((vec!{ 0x12, 0x34 }, 32)));
+	}
+}
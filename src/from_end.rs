@@ -0,0 +1,83 @@
+//! Extraction and insertion at positions relative to the end
+//!
+//! Trailers, CRCs and padding lengths are usually specified from the end of a frame. The functions
+//! of this module take the number of bits between the **start** of the field and the end of the
+//! buffer, so that the last 16 bits are addressed with bits_from_end = 16 and length = 16.
+//!
+//! ```rust
+//! use bitlab::*;
+//! // A frame with a 4 bit trailer after the 16 bit CRC
+//! let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x12, 0x34, 0x50 };
+//! assert_eq!(v.get_from_end::<u16>(20, 16).unwrap(), 0x2345);
+//! assert_eq!(v.get_from_end::<u8>(4, 4).unwrap(), 0);
+//! v.set_from_end(4, 4, 0xFu8).unwrap();
+//! assert_eq!(v[4], 0x5F);
+//! ```
+
+use super::{Result, BitTarget, BitValue, ExtractBitsFromVecU8, LargeOffsets, OUT_OF_RANGE_MSG};
+use super::offset64::split;
+
+// Returns the absolute bit position of a field, which starts bits_from_end bits before the end
+fn position(len: usize, bits_from_end: u64) -> Result<u64> {
+	(len as u64).saturating_mul(8).checked_sub(bits_from_end).ok_or_else(|| s!(OUT_OF_RANGE_MSG))
+}
+
+/// Extracts and inserts bits in a Vec<u8> or a byte slice at a position relative to the end.
+/// See the [module documentation](from_end/index.html).
+pub trait FromEnd {
+	/// Extracts length bits starting bits_from_end bits before the end as any integer type.
+	/// See ExtractBitsFromVecU8::get_at.
+	fn get_from_end<T: BitTarget>(&self, bits_from_end: u64, length: u32) -> Result<T>;
+
+	/// Inserts value into length bits starting bits_from_end bits before the end. See InsertBitsIntoVecU8::set.
+	fn set_from_end<T: BitValue>(&mut self, bits_from_end: u64, length: u32, value: T) -> Result<()>;
+}
+
+impl FromEnd for [u8] {
+	fn get_from_end<T: BitTarget>(&self, bits_from_end: u64, length: u32) -> Result<T> {
+		let (rest, bit_offset) = split(self, position(self.len(), bits_from_end)?)?;
+		rest.get_at(0, bit_offset, length)
+	}
+
+	fn set_from_end<T: BitValue>(&mut self, bits_from_end: u64, length: u32, value: T) -> Result<()> {
+		let bit_position = position(self.len(), bits_from_end)?;
+		self.set_at(bit_position, length, value)
+	}
+}
+
+// Vectors use the implementation for slices
+impl FromEnd for Vec<u8> {
+	#[inline]
+	fn get_from_end<T: BitTarget>(&self, bits_from_end: u64, length: u32) -> Result<T> {
+		self.as_slice().get_from_end(bits_from_end, length)
+	}
+
+	#[inline]
+	fn set_from_end<T: BitValue>(&mut self, bits_from_end: u64, length: u32, value: T) -> Result<()> {
+		self.as_mut_slice().set_from_end(bits_from_end, length, value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn positions_from_the_end() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		assert_eq!(v.get_from_end::<u8>(8, 8).unwrap(), 0x6F);
+		assert_eq!(v.get_from_end::<u64>(40, 40).unwrap(), 0x48_616C_6C6F);
+		assert_eq!(v[..].get_from_end::<i8>(3, 3).unwrap(), -1);
+		assert_eq!(v.get_from_end::<i32>(25, 3).unwrap(), v.get_i32(1, 7, 3).unwrap());
+		assert_eq!(v.get_from_end::<u128>(40, 40).unwrap(), 0x48_616C_6C6F);
+		// The field must end before the end of the buffer
+		assert_eq!(v.get_from_end::<u16>(8, 9).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_from_end::<u8>(41, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_from_end::<u8>(0, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		v.set_from_end(12, 8, 0xABu8).unwrap();
+		assert_eq!(&v[3..], &[0x6A, 0xBF]);
+		assert_eq!(v.set_from_end(2, 3, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.set_from_end(u64::MAX, 1, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+}
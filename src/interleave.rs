@@ -0,0 +1,212 @@
+//! Bit interleaving
+//!
+//! Interleavers spread adjacent bits over a longer stretch of the transmitted stream,
+//! so that a burst error is turned into isolated bit errors, which a FEC decoder can correct.
+
+use std::collections::VecDeque;
+use super::{Result, BitBuffer};
+
+static BLOCK_SIZE_MSG: &str = "The number of bits is not a multiple of rows * cols";
+static ZERO_DIMENSION_MSG: &str = "The number of rows and columns must not be zero";
+static DIMENSION_TOO_BIG_MSG: &str = "The number of bits rows * cols is too big";
+static LENGTH_MISMATCH_MSG: &str = "The streams do not have the same length";
+static ODD_LENGTH_MSG: &str = "The number of bits is odd";
+
+/// Block interleaver: The bits are written row by row into a matrix of
+/// rows x cols bits and read column by column.
+///
+/// The number of bits must be a multiple of rows * cols. Each block of rows * cols bits is interleaved separately.
+///
+/// ```rust
+/// use bitlab::*;
+/// // 2 rows, 3 columns: 101 / 100 --> 11 00 10
+/// let b = BitBuffer::from_bytes(vec!{ 0b1011_0000 }, 6).unwrap();
+/// let i = interleave::interleave_block(&b, 2, 3).unwrap();
+/// assert_eq!(i.read_bits(0, 6).unwrap(), 0b11_0010);
+/// assert_eq!(interleave::deinterleave_block(&i, 2, 3).unwrap(), b);
+/// ```
+pub fn interleave_block(source: &BitBuffer, rows: usize, cols: usize) -> Result<BitBuffer> {
+	if rows == 0 || cols == 0 {
+		return Err(s!(ZERO_DIMENSION_MSG));
+	}
+
+	let block_size = rows.checked_mul(cols).ok_or_else(|| s!(DIMENSION_TOO_BIG_MSG))?;
+	if !source.bit_len().is_multiple_of(block_size) {
+		return Err(s!(BLOCK_SIZE_MSG));
+	}
+
+	let mut result = BitBuffer::new();
+	for block in 0..source.bit_len() / block_size {
+		let start = block * block_size;
+		for col in 0..cols {
+			for row in 0..rows {
+				result.push_bit(source.get_bit(start + row * cols + col)?);
+			}
+		}
+	}
+	Ok(result)
+}
+
+/// Reverses interleave_block with the same rows and cols parameters
+pub fn deinterleave_block(source: &BitBuffer, rows: usize, cols: usize) -> Result<BitBuffer> {
+	// Reading column by column is the same as writing row by row into the transposed matrix
+	interleave_block(source, cols, rows)
+}
+
+/// Merges two streams of the same length bit by bit, starting with the first bit of a,
+/// e.g. the I and Q samples of a packed signal.
+///
+/// ```rust
+/// use bitlab::*;
+/// let i = BitBuffer::from_bools(&[true, true, false]);
+/// let q = BitBuffer::from_bools(&[false, true, false]);
+/// let merged = interleave::interleave_streams(&i, &q).unwrap();
+/// assert_eq!(merged.read_bits(0, 6).unwrap(), 0b10_11_00);
+/// assert_eq!(interleave::split_streams(&merged).unwrap(), (i, q));
+/// ```
+pub fn interleave_streams(a: &BitBuffer, b: &BitBuffer) -> Result<BitBuffer> {
+	if a.bit_len() != b.bit_len() {
+		return Err(s!(LENGTH_MISMATCH_MSG));
+	}
+
+	let mut result = BitBuffer::new();
+	for (bit_a, bit_b) in a.to_bools().into_iter().zip(b.to_bools()) {
+		result.push_bit(bit_a);
+		result.push_bit(bit_b);
+	}
+	Ok(result)
+}
+
+/// Reverses interleave_streams. Returns the bits at even and at odd indices as two streams.
+pub fn split_streams(source: &BitBuffer) -> Result<(BitBuffer, BitBuffer)> {
+	if !source.bit_len().is_multiple_of(2) {
+		return Err(s!(ODD_LENGTH_MSG));
+	}
+
+	let bits = source.to_bools();
+	let a = bits.iter().step_by(2).copied().collect();
+	let b = bits.iter().skip(1).step_by(2).copied().collect();
+	Ok((a, b))
+}
+
+/// Convolutional interleaver with an arbitrary delay profile.
+///
+/// The bits are distributed cyclically over a number of branches. Branch i delays its bits by
+/// delays\[i\] passes of the commutator. All delay lines are initially filled with zeros.
+/// The matching deinterleaver is created by inverse() and restores the original stream
+/// after a total delay of max(delays) * number of branches bits.
+///
+/// ```rust
+/// use bitlab::interleave::ConvolutionalInterleaver;
+/// let mut i = ConvolutionalInterleaver::forney(3, 1);
+/// let mut d = i.inverse();
+/// let input = [true, false, true, true, false, true, true, true, false];
+/// let output: Vec<bool> = input.iter().map(|&b| d.process_bit(i.process_bit(b))).collect();
+/// // The total delay is 2 * 3 = 6 bits
+/// assert_eq!(&output[6..], &input[..3]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConvolutionalInterleaver {
+	lines: Vec<VecDeque<bool>>,
+	delays: Vec<usize>,
+	branch: usize,
+}
+
+impl ConvolutionalInterleaver {
+	/// Creates an interleaver, whose branch i has the delay delays\[i\]. Returns an error if delays is empty.
+	pub fn new(delays: &[usize]) -> Result<ConvolutionalInterleaver> {
+		if delays.is_empty() {
+			return Err(s!(ZERO_DIMENSION_MSG));
+		}
+		Ok(ConvolutionalInterleaver::with_delays(delays.to_vec()))
+	}
+
+	// Expects at least one branch
+	fn with_delays(delays: Vec<usize>) -> ConvolutionalInterleaver {
+		ConvolutionalInterleaver {
+			lines: delays.iter().map(|&d| std::iter::repeat_n(false, d).collect()).collect(),
+			delays,
+			branch: 0,
+		}
+	}
+
+	/// Creates a Forney interleaver with the delays 0, step, 2 * step, ... (branches - 1) * step
+	pub fn forney(branches: usize, step: usize) -> ConvolutionalInterleaver {
+		ConvolutionalInterleaver::with_delays((0..branches.max(1)).map(|i| i * step).collect())
+	}
+
+	/// Creates the matching deinterleaver, whose delays are max(delays) - delays\[i\]
+	pub fn inverse(&self) -> ConvolutionalInterleaver {
+		let max = self.delays.iter().copied().max().unwrap_or(0);
+		ConvolutionalInterleaver::with_delays(self.delays.iter().map(|&d| max - d).collect())
+	}
+
+	/// Passes a single bit through the current branch and advances the commutator
+	pub fn process_bit(&mut self, bit: bool) -> bool {
+		let line = &mut self.lines[self.branch];
+		line.push_back(bit);
+		let out = line.pop_front().unwrap_or(bit);
+		self.branch = (self.branch + 1) % self.lines.len();
+		out
+	}
+
+	/// Passes all bits of a buffer through the interleaver
+	pub fn process(&mut self, source: &BitBuffer) -> BitBuffer {
+		let mut result = BitBuffer::new();
+		for i in 0..source.bit_len() {
+			result.push_bit(self.process_bit(source.get_bit(i).unwrap_or(false)));
+		}
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn block_and_convolutional_interleaving() {
+		let v = BitBuffer::from(vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F, 0x00 });
+
+		let i = interleave_block(&v, 4, 6).unwrap();
+		assert_ne!(i, v);
+		assert_eq!(deinterleave_block(&i, 4, 6).unwrap(), v);
+
+		// 0x48 = 0100_1000 as 2 x 4 matrix: 0100 / 1000 --> 01 10 00 00
+		let i = interleave_block(&v, 2, 4).unwrap();
+		assert_eq!(i.read_bits(0, 8).unwrap(), 0b0110_0000);
+
+		assert_eq!(interleave_block(&v, 5, 2).unwrap_err(), BLOCK_SIZE_MSG);
+		assert_eq!(interleave_block(&v, 0, 2).unwrap_err(), ZERO_DIMENSION_MSG);
+		assert_eq!(interleave_block(&v, usize::MAX, 2).unwrap_err(), DIMENSION_TOO_BIG_MSG);
+		assert_eq!(deinterleave_block(&v, 3, usize::MAX / 2).unwrap_err(), DIMENSION_TOO_BIG_MSG);
+
+		// Delay profile with different delays per branch
+		let mut i = ConvolutionalInterleaver::new(&[2, 0, 1]).unwrap();
+		let mut d = i.inverse();
+		let interleaved = i.process(&v);
+		assert_ne!(interleaved, v);
+		let restored = d.process(&interleaved);
+
+		// Total delay = 2 * 3 bits
+		for n in 6..v.bit_len() {
+			assert_eq!(restored.get_bit(n).unwrap(), v.get_bit(n - 6).unwrap());
+		}
+
+		assert!(ConvolutionalInterleaver::new(&[]).is_err());
+	}
+
+	#[test]
+	fn interleaving_two_streams() {
+		let a = BitBuffer::from(vec!{ 0xFF, 0x00 });
+		let b = BitBuffer::from(vec!{ 0x0F, 0xF0 });
+		let merged = interleave_streams(&a, &b).unwrap();
+		assert_eq!(merged.as_bytes(), &[0xAA, 0xFF, 0x55, 0x00]);
+		assert_eq!(split_streams(&merged).unwrap(), (a.clone(), b));
+
+		assert_eq!(interleave_streams(&a, &BitBuffer::new()).unwrap_err(), LENGTH_MISMATCH_MSG);
+		assert_eq!(split_streams(&BitBuffer::from_bools(&[true])).unwrap_err(), ODD_LENGTH_MSG);
+		let (x, y) = split_streams(&BitBuffer::new()).unwrap();
+		assert!(x.is_empty() && y.is_empty());
+	}
+}
@@ -0,0 +1,167 @@
+//! Layouts of bit fields, which are described by a string at run time
+
+use std::collections::BTreeMap;
+use super::*;
+
+/// A field of a [`Layout`](struct.Layout.html)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutField {
+	/// The name of the field
+	pub name: String,
+	/// The start of the field relative to the start of the layout
+	pub bit_offset: u32,
+	/// The number of bits
+	pub length: u32,
+	/// The type of the extracted value
+	pub kind: ValueKind,
+}
+
+/// A sequence of named fields without gaps, which is parsed from a description like
+/// `"version:u4, ihl:u4, dscp:u6, ecn:u2, length:u16"`.
+///
+/// The types are
+///
+/// - **u1** to **u64** unsigned integers, which are extracted as the smallest fitting Value
+/// - **i1** to **i64** signed integers in two's complement
+/// - **f32** and **f64** IEEE 754 floats
+/// - **bool** a single bit
+/// - **bytes1**, **bytes2** ... a number of whole bytes
+///
+/// ```rust
+/// use bitlab::*;
+/// let layout = Layout::parse("version:u4, ihl:u4, dscp:u6, ecn:u2, length:u16").unwrap();
+/// let mut values = layout.extract(&[0x45u8, 0x00, 0x05, 0xDC][..]).unwrap();
+/// assert_eq!(values["length"], Value::U16(1500));
+///
+/// values.insert(String::from("ecn"), Value::U8(3));
+/// assert_eq!(layout.serialize(&values).unwrap(), vec!{ 0x45, 0x03, 0x05, 0xDC });
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+	fields: Vec<LayoutField>,
+	bit_len: u32,
+}
+
+// Returns the length and the kind of a type name
+fn parse_type(ty: &str) -> Option<(u32, ValueKind)> {
+	let n = |digits: &str| digits.parse::<u32>().ok();
+	match ty {
+		"bool" => Some((1, ValueKind::Bool)),
+		"f32" => Some((32, ValueKind::F32)),
+		"f64" => Some((64, ValueKind::F64)),
+		_ if ty.starts_with("bytes") => match n(&ty[5..]) {
+			Some(bytes) if bytes > 0 => Some((bytes.checked_mul(8)?, ValueKind::Bytes)),
+			_ => None,
+		},
+		_ if ty.starts_with('u') => match n(&ty[1..])? {
+			length @ 1..=8 => Some((length, ValueKind::U8)),
+			length @ 9..=16 => Some((length, ValueKind::U16)),
+			length @ 17..=32 => Some((length, ValueKind::U32)),
+			length @ 33..=64 => Some((length, ValueKind::U64)),
+			_ => None,
+		},
+		_ if ty.starts_with('i') => match n(&ty[1..])? {
+			length @ 1..=8 => Some((length, ValueKind::I8)),
+			length @ 9..=16 => Some((length, ValueKind::I16)),
+			length @ 17..=32 => Some((length, ValueKind::I32)),
+			length @ 33..=64 => Some((length, ValueKind::I64)),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+impl Layout {
+	/// Parses a comma separated list of fields in the form name:type. Returns an error for
+	/// an unknown type, a missing or duplicate name or a layout longer than u32::MAX bits.
+	pub fn parse(description: &str) -> Result<Layout> {
+		let mut fields: Vec<LayoutField> = Vec::new();
+		let mut bit_len: u32 = 0;
+
+		for item in description.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+			let (name, ty) = match item.split_once(':') {
+				Some((name, ty)) => (name.trim(), ty.trim()),
+				None => return Err(format!("The field {} has no type", item)),
+			};
+			if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+				return Err(format!("Invalid field name {}", name));
+			}
+			if fields.iter().any(|f| f.name == name) {
+				return Err(format!("Duplicate field name {}", name));
+			}
+			let (length, kind) = parse_type(ty).ok_or_else(|| format!("Unknown type {} of the field {}", ty, name))?;
+
+			fields.push(LayoutField { name: s!(name), bit_offset: bit_len, length, kind });
+			bit_len = bit_len.checked_add(length).ok_or_else(|| s!(OUT_OF_RANGE_MSG))?;
+		}
+
+		Ok(Layout { fields, bit_len })
+	}
+
+	/// Returns the fields in the order of the description
+	pub fn fields(&self) -> &[LayoutField] {
+		&self.fields
+	}
+
+	/// Returns the total number of bits
+	pub fn bit_len(&self) -> u32 {
+		self.bit_len
+	}
+
+	/// Extracts all fields from the start of source and returns the values by name
+	pub fn extract<V: DynamicBits + ?Sized>(&self, source: &V) -> Result<BTreeMap<String, Value>> {
+		self.fields.iter()
+			.map(|f| Ok((f.name.clone(), source.get_dyn(0, f.bit_offset, f.length, f.kind)?)))
+			.collect()
+	}
+
+	/// Inserts the values of all fields into a new vector. The unused bits of the last byte are zero.
+	/// Returns an error if a value is missing or does not fit into its field.
+	pub fn serialize(&self, values: &BTreeMap<String, Value>) -> Result<Vec<u8>> {
+		let mut bytes = vec!{ 0u8; self.bit_len.div_ceil(8) as usize };
+		for f in self.fields.iter() {
+			let value = values.get(&f.name).ok_or_else(|| format!("There is no value for the field {}", f.name))?;
+			bytes.set_dyn(0, f.bit_offset, f.length, value)?;
+		}
+		Ok(bytes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parsed_layouts() {
+		let layout = Layout::parse(" kind:u3,flag : bool, offset:i13, id:bytes2, x:f32, big:u33, ").unwrap();
+		assert_eq!(layout.bit_len(), 3 + 1 + 13 + 16 + 32 + 33);
+		assert_eq!(layout.fields()[2], LayoutField { name: s!("offset"), bit_offset: 4, length: 13, kind: ValueKind::I16 });
+		assert_eq!(layout.fields()[5].kind, ValueKind::U64);
+
+		let mut values = BTreeMap::new();
+		values.insert(s!("kind"), Value::U8(5));
+		values.insert(s!("flag"), Value::Bool(true));
+		values.insert(s!("offset"), Value::I16(-1000));
+		values.insert(s!("id"), Value::Bytes(vec!{ 0xBE, 0xEF }));
+		values.insert(s!("x"), Value::F32(-0.5));
+		values.insert(s!("big"), Value::U64(1 << 32));
+		let bytes = layout.serialize(&values).unwrap();
+		assert_eq!(bytes.len(), 13);
+		assert_eq!(layout.extract(&bytes).unwrap(), values);
+		assert_eq!(layout.extract(&bytes[..12]).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		values.remove("x");
+		assert_eq!(layout.serialize(&values).unwrap_err(), "There is no value for the field x");
+		values.insert(s!("x"), Value::F32(1.0));
+		values.insert(s!("kind"), Value::U8(8));
+		assert!(layout.serialize(&values).is_err());
+
+		assert_eq!(Layout::parse("a:u65").unwrap_err(), "Unknown type u65 of the field a");
+		assert_eq!(Layout::parse("a:u0").unwrap_err(), "Unknown type u0 of the field a");
+		assert_eq!(Layout::parse("a:bytes0").unwrap_err(), "Unknown type bytes0 of the field a");
+		assert_eq!(Layout::parse("a:u1, a:u2").unwrap_err(), "Duplicate field name a");
+		assert_eq!(Layout::parse("a u1").unwrap_err(), "The field a u1 has no type");
+		assert_eq!(Layout::parse(":u1").unwrap_err(), "Invalid field name ");
+		assert_eq!(Layout::parse("").unwrap().bit_len(), 0);
+	}
+}
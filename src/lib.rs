@@ -98,6 +98,19 @@
 //! cargo run --release --example gif
 //! ```
 //! 
+//! # Optional features
+//! 
+//! - **tracing**: The get and set functions for vectors and the set functions for integers
+//!   emit [tracing](https://crates.io/crates/tracing) spans and events at the trace level,
+//!   which contain the offsets, the length, the value and the result.
+//! - **cli**: Builds the command line tool bitlab, which extracts bits from a file
+//!   (`bitlab get file.bin --byte 10 --bit 1 --len 3 --as u8`) or prints a hexdump (`bitlab dump file.bin`).
+//! - **rayon**: Parallel CRC computation with [rayon](https://crates.io/crates/rayon) (crc::Crc::checksum_parallel).
+//! - **ffi**: A C interface in the module [ffi](ffi/index.html) for the extraction and
+//!   insertion of bits in byte buffers, for the BitReader, the BitWriter and for CRCs.
+//! - **wasm**: A JavaScript interface in the module [wasm](wasm/index.html) for the extraction
+//!   and insertion of bits, for layouts and for hexdumps, built with [wasm-bindgen](https://crates.io/crates/wasm-bindgen).
+//! 
 //! # MIT Licence
 //! 
 //! Copyright <2017, Kağan Kayal>
@@ -130,6 +143,12 @@ extern crate num;
 static OUT_OF_RANGE_MSG: &str = "Out of range";
 static LEN_TOO_BIG_MSG: &str = "The length parameter is too big for a ";
 static LEN_ZERO: &str = "The length parameter must not be zero";
+/// The error message of the get_nonzero functions, if the extracted value is zero
+pub static ZERO_VALUE_MSG: &str = "The extracted value is zero";
+static LITTLE_ENDIAN_LEN_MSG: &str = "The length of a little endian field must be a multiple of 8";
+static BCD_DIGIT_MSG: &str = "A BCD digit is greater than 9";
+static STRIDE_ZERO_MSG: &str = "The stride must not be zero";
+static INVALID_UTF8_MSG: &str = "The bytes are not valid UTF-8: ";
 
 // Result-type-alias-idiom
 // Source https://doc.rust-lang.org/book/first-edition/error-handling.html#the-result-type-alias-idiom
@@ -151,8 +170,12 @@ impl TypeInfo for i8  { fn type_of(&self) -> &'static str {"i8"}  }
 impl TypeInfo for i16 { fn type_of(&self) -> &'static str {"i16"} }
 impl TypeInfo for i32 { fn type_of(&self) -> &'static str {"i32"} }
 impl TypeInfo for i64 { fn type_of(&self) -> &'static str {"i64"} }
+impl TypeInfo for u128 { fn type_of(&self) -> &'static str {"u128"} }
+impl TypeInfo for i128 { fn type_of(&self) -> &'static str {"i128"} }
 impl TypeInfo for f32 { fn type_of(&self) -> &'static str {"f32"} }
 impl TypeInfo for f64 { fn type_of(&self) -> &'static str {"f64"} }
+impl TypeInfo for usize { fn type_of(&self) -> &'static str {"usize"} }
+impl TypeInfo for isize { fn type_of(&self) -> &'static str {"isize"} }
 
 /// A trait to find out if a variable type is signed or unsigned for integer types.
 pub trait SignedInfo{
@@ -168,6 +191,18 @@ impl SignedInfo for i8  { fn is_signed(&self) -> bool { true  } }
 impl SignedInfo for i16 { fn is_signed(&self) -> bool { true  } }
 impl SignedInfo for i32 { fn is_signed(&self) -> bool { true  } }
 impl SignedInfo for i64 { fn is_signed(&self) -> bool { true  } }
+impl SignedInfo for u128 { fn is_signed(&self) -> bool { false } }
+impl SignedInfo for i128 { fn is_signed(&self) -> bool { true  } }
+impl SignedInfo for usize { fn is_signed(&self) -> bool { false } }
+impl SignedInfo for isize { fn is_signed(&self) -> bool { true  } }
+
+// The unsigned integer type with the size of usize and isize, to which their implementations delegate
+#[cfg(target_pointer_width = "16")]
+type PointerSized = u16;
+#[cfg(target_pointer_width = "32")]
+type PointerSized = u32;
+#[cfg(target_pointer_width = "64")]
+type PointerSized = u64;
 
 // Convenience macro to shorten String::from("hello") to s!("hello")
 macro_rules! s {
@@ -187,37 +222,187 @@ macro_rules! check_range {
 		if $length == 0 {
 			return Err(s!(LEN_ZERO));
 		}
-		if $bit_offset + $length > std::mem::size_of::<Self>() as u32 * 8 {
-			return Err(s!(OUT_OF_RANGE_MSG));
+		match $bit_offset.checked_add($length) {
+			Some(end) if end <= std::mem::size_of::<Self>() as u32 * 8 => {},
+			_ => return Err(s!(OUT_OF_RANGE_MSG)),
+		}
+	}
+}
+
+// Reads up to 64 bits starting at an absolute bit position, where zero is the most significant bit
+// of the first byte, and returns them right aligned. The caller is responsible for the range checks.
+fn read_bits_from_slice(source: &[u8], bit_position: usize, length: u32) -> u64 {
+	let mut result: u64 = 0;
+	let mut pos = bit_position;
+	let end = bit_position + length as usize;
+
+	// Take as many bits as possible from each byte
+	while pos < end {
+		let bit_in_byte = pos % 8;
+		let n = std::cmp::min(8 - bit_in_byte, end - pos);
+		let bits = ((source[pos / 8] << bit_in_byte) >> (8 - n)) as u64;
+		result = (result << n) | bits;
+		pos += n;
+	}
+
+	result
+}
+
+// Writes the length (up to 64) least significant bits of value starting at an absolute bit position,
+// where zero is the most significant bit of the first byte. The caller is responsible for the range checks.
+fn write_bits_to_slice(target: &mut [u8], bit_position: usize, length: u32, value: u64) {
+	let mut pos = bit_position;
+	let end = bit_position + length as usize;
+
+	// Replace as many bits as possible in each byte
+	while pos < end {
+		let bit_in_byte = pos % 8;
+		let n = std::cmp::min(8 - bit_in_byte, end - pos);
+		let shift = 8 - bit_in_byte - n;
+		let mask = ((0xFFu16 >> (8 - n)) << shift) as u8;
+		let bits = ((value >> (end - pos - n)) as u8) << shift;
+		target[pos / 8] = (target[pos / 8] & !mask) | (bits & mask);
+		pos += n;
+	}
+}
+
+// Checks without overflow, whether a range of bits lies within a vector of len bytes
+fn is_within(len: usize, byte_offset: u32, bit_offset: u32, length: u32) -> bool {
+	byte_offset as u64 * 8 + bit_offset as u64 + length as u64 <= (len as u64).saturating_mul(8)
+}
+
+// Checks without overflow, whether length bits starting at the absolute bit position start lie within bit_len bits
+fn check_bit_range(start: usize, length: usize, bit_len: usize) -> Result<()> {
+	match start.checked_add(length) {
+		Some(end) if end <= bit_len => Ok(()),
+		_ => Err(s!(OUT_OF_RANGE_MSG)),
+	}
+}
+
+// Returns the absolute bit position of byte_offset and bit_offset and checks without overflow,
+// whether length bits starting there lie within a slice of len bytes
+fn range_start(len: usize, byte_offset: u32, bit_offset: u32, length: usize) -> Result<usize> {
+	let start = (byte_offset as usize).checked_mul(8).and_then(|s| s.checked_add(bit_offset as usize))
+		.ok_or_else(|| s!(OUT_OF_RANGE_MSG))?;
+	check_bit_range(start, length, len.saturating_mul(8))?;
+	Ok(start)
+}
+
+// Checks a field of at most 64 bits in a vector of len bytes
+fn check_field(len: usize, byte_offset: u32, bit_offset: u32, length: u32) -> Result<()> {
+	if length == 0 { return Err(s!(LEN_ZERO)); }
+	if length > 64 { return Err(s!(LEN_TOO_BIG_MSG) + "u64"); }
+	if !is_within(len, byte_offset, bit_offset, length) { return Err(s!(OUT_OF_RANGE_MSG)); }
+	Ok(())
+}
+
+// Returns the position of the last of count bits, which are stride bits apart, or None if count
+// is zero. Returns u64::MAX on overflow, which is out of range for every vector.
+fn strided_last(start: u64, stride: u32, count: usize) -> Option<u64> {
+	let gaps = (count as u64).checked_sub(1)?;
+	Some(gaps.checked_mul(stride as u64).and_then(|d| d.checked_add(start)).unwrap_or(u64::MAX))
+}
+
+// Reverses the order of the bytes of a field of length bits, which has been extracted as big endian
+fn little_endian_field(value: u64, length: u32) -> Result<u64> {
+	if !length.is_multiple_of(8) {
+		return Err(s!(LITTLE_ENDIAN_LEN_MSG));
+	}
+	Ok(value.swap_bytes() >> (64 - length))
+}
+
+// Interprets a field of length bits as a sign bit followed by the magnitude. Negative zero becomes zero.
+fn sign_magnitude_field(value: u64, length: u32) -> i64 {
+	let magnitude = (value & u64::MAX.checked_shr(65 - length).unwrap_or(0)) as i64;
+	if value >> (length - 1) & 1 == 1 { -magnitude } else { magnitude }
+}
+
+// Subtracts the bias k from a field in excess-k (offset binary) representation
+fn excess_k_field<T: std::convert::TryFrom<i128>>(value: u64, k: i64, type_name: &str) -> Result<T> {
+	let v = value as i128 - k as i128;
+	T::try_from(v).map_err(|_| format!("The value {} with the bias {} does not fit into an {}", v, k, type_name))
+}
+
+// Returns an error, if value requires more than length bits
+fn check_value_fits<T>(value: T, length: u32) -> Result<()>
+	where T: SignedInfo + std::string::ToString + Copy,
+	T: num::cast::AsPrimitive<u128> + num::cast::AsPrimitive<i128> {
+
+	if value.is_signed() {
+		let n = num::cast::AsPrimitive::<i128>::as_(value).bit_length();
+		if n > length {
+			return Err(format!("Failed to insert {} as a {} bit signed integer variable, since it requires at least {} bits.",
+				&value.to_string(), &length.to_string(), &n.to_string()))
+		}
+	} else {
+		let n = num::cast::AsPrimitive::<u128>::as_(value).bit_length();
+		if n > length {
+			return Err(format!("Failed to insert {} as a {} bit unsigned integer variable, since it requires at least {} bits.",
+				&value.to_string(), &length.to_string(), &n.to_string()))
 		}
 	}
+	Ok(())
 }
 
 /// How many bits does it take to write an unsigned integer?
 pub fn n_required_bits_for_an_unsigned_int(num: u64) -> u32 {
-	// TODO: The performance can be probably improved by a clever lookup strategy
-	let i = num as f64;
-	let j = i.log2();
-	if j > 0_f64 {
-		j.floor() as u32 + 1
-	}
-	else { 1 }
+	num.bit_length()
 }
 
 /// How many bits does it take to write a signed integer?
 pub fn n_required_bits_for_a_signed_int(num: i64) -> u32 {
-	// TODO: The performance can be probably improved by a clever lookup strategy
-	let i = num as f64;
-	let j = i.abs().log2();
-	if j > 0_f64 {
-		j.ceil() as u32 + 1
-	}
-	else { 1 }
+	num.bit_length()
+}
+
+/// A trait to find out how many bits it takes to write an integer.
+///
+/// An unsigned integer needs the bits up to its most significant set bit.
+/// A signed integer n needs ceil(log2(|n|)) + 1 bits.
+/// Zero needs one bit.
+///
+/// ```rust
+/// use bitlab::*;
+/// assert_eq!(5u8.bit_length(), 3);
+/// assert_eq!((-4i32).bit_length(), 3);
+/// assert_eq!(u128::MAX.bit_length(), 128);
+/// ```
+pub trait BitLength {
+	/// Returns the number of bits, which are required to write the value
+	fn bit_length(self) -> u32;
+}
+
+macro_rules! def_bit_length_unsigned {
+	( $( $t:ty ),* ) => { $(
+		impl BitLength for $t {
+			fn bit_length(self) -> u32 {
+				std::cmp::max(1, <$t>::BITS - self.leading_zeros())
+			}
+		}
+	)* }
+}
+
+macro_rules! def_bit_length_signed {
+	( $( $t:ty ),* ) => { $(
+		impl BitLength for $t {
+			fn bit_length(self) -> u32 {
+				let magnitude = self.unsigned_abs();
+				if magnitude <= 1 {
+					1
+				} else {
+					// ceil(log2(magnitude)) + 1
+					<$t>::BITS - (magnitude - 1).leading_zeros() + 1
+				}
+			}
+		}
+	)* }
 }
 
+def_bit_length_unsigned!(u8, u16, u32, u64, u128, usize);
+def_bit_length_signed!(i8, i16, i32, i64, i128, isize);
+
 /// Defines a number of functions, which extract a range of bits from
-/// primitive numeric types (u8, u16, u32 and u64, i8, i16, i32 and i64) and return
-/// the result as one of the following types (u8, u16, u32 and u64, i8, i16, i32 and i64)
+/// primitive numeric types (u8, u16, u32, u64, u128 and usize, i8, i16, i32, i64, i128 and isize) and return
+/// the result as one of the following types (u8, u16, u32, u64 and u128, i8, i16, i32, i64 and i128)
 /// E.g. the a.get_u8(5,3) function extracts the bits 5,6 and 7 of
 /// the variable a and returns the result as a u8 variable
 pub trait ExtractBitsFromIntegralTypes {
@@ -284,6 +469,36 @@ pub trait ExtractBitsFromIntegralTypes {
 	/// - **bit offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit  
 	/// - **length** (u32) the number of bits to be extracted.
 	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64>;
+
+	/// Extracts a range of bits and returns a Result object.
+	///
+	/// Parameters:
+	///
+	/// - **bit offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit  
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> where Self: std::marker::Sized {
+		Ok(self.get_u64(bit_offset, length)? as u128)
+	}
+
+	/// Extracts a range of bits and returns a Result object.
+	///
+	/// Parameters:
+	///
+	/// - **bit offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit  
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> where Self: std::marker::Sized {
+		Ok(self.get_i64(bit_offset, length)? as i128)
+	}
+
+	/// Extracts a range of bits as the type T, which is chosen by the caller, e.g. a.get::<u16>(3, 9).
+	///
+	/// Parameters:
+	///
+	/// - **bit offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit  
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get<T: BitTarget>(self, bit_offset: u32, length: u32) -> Result<T> where Self: std::marker::Sized {
+		T::extract_from(self, bit_offset, length)
+	}
 }
 
 impl ExtractBitsFromIntegralTypes for u8 {
@@ -767,171 +982,1143 @@ impl ExtractBitsFromIntegralTypes for i64 {
 	}
 }
 
-/// Defines a number of functions, which extract a range of bits from a Vec<u8>
-/// There is one function for each variable type to be returned
-/// **Important:** the contents of the vector are assumed to be **big endian** (network order)
-pub trait ExtractBitsFromVecU8 {
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 8 bit unsigned integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_u8(&self, byte_offset: u32, start: u32, length: u32) -> Result<u8>;
-
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 8 bit integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_i8(&self, byte_offset: u32, start: u32, length: u32) -> Result<i8>;
-
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 16 bit unsigned integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_u16(&self, byte_offset: u32, start: u32, length: u32) -> Result<u16>;
+impl ExtractBitsFromIntegralTypes for u128 {
+	fn get_u8(self, bit_offset: u32, length: u32) -> Result<u8> {
+		if length > 8 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u8");
+		}
 
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 16 bit integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_i16(&self, byte_offset: u32, start: u32, length: u32) -> Result<i16>;
+		// Return the result
+		Ok(self.get_u128 (bit_offset, length)? as u8)
+	}
 
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 32 bit unsigned integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_u32(&self, byte_offset: u32, start: u32, length: u32) -> Result<u32>;
+	fn get_i8(self, bit_offset: u32, length: u32) -> Result<i8> {
+		if length > 8 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i8");
+		}
 
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 32 bit integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_i32(&self, byte_offset: u32, start: u32, length: u32) -> Result<i32>;
+		// Return the result
+		Ok(self.get_i128 (bit_offset, length)? as i8)
+	}
 
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 64 bit unsigned integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_u64(&self, byte_offset: u32, start: u32, length: u32) -> Result<u64>;
+	fn get_u16(self, bit_offset: u32, length: u32) -> Result<u16> {
+		if length > 16 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u16");
+		}
 
-	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 64 bit integer or an error message.
-	///
-	/// Parameters:
-	///
-	/// - **byte_offset** (u32) the number of bytes to skip in source
-	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be extracted.
-	fn get_i64(&self, byte_offset: u32, start: u32, length: u32) -> Result<i64>;
-}
+		// Return the result
+		Ok(self.get_u128 (bit_offset, length)? as u16)
+	}
 
-impl ExtractBitsFromVecU8 for Vec<u8> {
-	fn get_u8(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u8> {
-		if length == 0 { return Err(s!(LEN_ZERO)); };
+	fn get_i16(self, bit_offset: u32, length: u32) -> Result<i16> {
+		if length > 16 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i16");
+		}
 
-		if length <= 8 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
-				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
-				let mut bit_offset_copy = bit_offset;
+		// Return the result
+		Ok(self.get_i128 (bit_offset, length)? as i16)
+	}
 
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
-				bit_offset_copy -= (bit_offset_copy / 8) * 8;
+	fn get_u32(self, bit_offset: u32, length: u32) -> Result<u32> {
+		if length > 32 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u32");
+		}
 
-				if bit_offset_copy + length <= 8 {
-					let mut copy: u8 = self[byte_offset_copy as usize];
-					// Assume that the data is given in big endian and
-					// convert it to whatever endianness we have on the users machine
-					copy = u8::from_be(copy);
-					// Lets clear the bits on both sides of the range of bits of interest
-					// First clear the ones on the left side
-					copy <<= bit_offset_copy;
-					// Second, push it all to the right end
-					copy >>= 8 - length;
-					return Ok(copy);
-				} else { // The range of bits spans over 2 bytes (not more)
-					// Copy the first byte
-					let copy1: u8 = self[byte_offset_copy as usize];
+		// Return the result
+		Ok(self.get_u128 (bit_offset, length)? as u32)
+	}
 
-					// Copy that into a bigger variable type
-					let mut copy1_as_u16: u16 = copy1 as u16;
+	fn get_i32(self, bit_offset: u32, length: u32) -> Result<i32> {
+		if length > 32 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i32");
+		}
 
-					// Shift 8 bits to the left, since these are the first 2 of 3 bytes
-					copy1_as_u16 <<= 8;
+		// Return the result
+		Ok(self.get_i128 (bit_offset, length)? as i32)
+	}
 
-					// Now copy the second bytes
-					let copy2: u8 = self[byte_offset_copy  as usize + 1];
+	fn get_u64(self, bit_offset: u32, length: u32) -> Result<u64> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
 
-					// Logical OR these two to get the original 2 bytes
-					let mut result = copy1_as_u16 | (copy2 as u16);
+		// Return the result
+		Ok(self.get_u128 (bit_offset, length)? as u64)
+	}
 
-					// From now on, process like the normal case above
-					result <<= bit_offset_copy;
-					result >>= 16 - length;
-					return Ok(result as u8);
-				}
-			} else {
-				return Err(s!(OUT_OF_RANGE_MSG))
-			}
-		} else {
-			return Err(s!(OUT_OF_RANGE_MSG))
+	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i64");
 		}
+
+		// Return the result
+		Ok(self.get_i128 (bit_offset, length)? as i64)
 	}
 
-	fn get_i8(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i8> {
-		if length == 0 { return Err(s!(LEN_ZERO)); };
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> {
+		check_range!(bit_offset, length);
 
-		if length <= 8 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
-				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
-				let mut bit_offset_copy = bit_offset;
+		// Lets clear the bits on both sides of the range of bits of interest
+		// and push it all to the right end
+		Ok((self << bit_offset) >> (128 - length))
+	}
 
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
-				bit_offset_copy -= (bit_offset_copy / 8) * 8;
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> {
+		check_range!(bit_offset, length);
 
-				if bit_offset_copy + length <= 8 {
-					let mut copy: i8 = self[byte_offset_copy as usize] as i8;
-					// Assume that the data is given in big endian and
-					// convert it to whatever endianness we have on the users machine
-					copy = i8::from_be(copy);
-					// Lets clear the bits on both sides of the range of bits of interest
-					// First clear the ones on the left side
-					copy <<= bit_offset_copy;
-					// Second, push it all to the right end
-					copy >>= 8 - length;
-					return Ok(copy);
-				} else { // The range of bits spans over 2 bytes (not more)
-					// Copy the first byte
-					let copy1: i8 = self[byte_offset_copy as usize] as i8;
+		// The arithmetic shift to the right extends the sign
+		Ok(((self as i128) << bit_offset) >> (128 - length))
+	}
+}
 
-					// Copy that into a bigger variable type
-					let mut copy1_as_i16: i16 = copy1 as i16;
+impl ExtractBitsFromIntegralTypes for i128 {
+	#[inline]
+	fn get_u8(self, bit_offset: u32, length: u32) -> Result<u8> {
+		(self as u128).get_u8 (bit_offset, length)
+	}
 
-					// Shift 8 bits to the left, since these are the first 2 of 3 bytes
-					copy1_as_i16 <<= 8;
+	#[inline]
+	fn get_i8(self, bit_offset: u32, length: u32) -> Result<i8> {
+		(self as u128).get_i8 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16(self, bit_offset: u32, length: u32) -> Result<u16> {
+		(self as u128).get_u16 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16(self, bit_offset: u32, length: u32) -> Result<i16> {
+		(self as u128).get_i16 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32(self, bit_offset: u32, length: u32) -> Result<u32> {
+		(self as u128).get_u32 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32(self, bit_offset: u32, length: u32) -> Result<i32> {
+		(self as u128).get_i32 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64(self, bit_offset: u32, length: u32) -> Result<u64> {
+		(self as u128).get_u64 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64> {
+		(self as u128).get_i64 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> {
+		(self as u128).get_u128 (bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> {
+		(self as u128).get_i128 (bit_offset, length)
+	}
+}
+
+// usize and isize use the implementation for the unsigned type of the same size
+impl ExtractBitsFromIntegralTypes for usize {
+	#[inline]
+	fn get_u8(self, bit_offset: u32, length: u32) -> Result<u8> {
+		(self as PointerSized).get_u8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i8(self, bit_offset: u32, length: u32) -> Result<i8> {
+		(self as PointerSized).get_i8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16(self, bit_offset: u32, length: u32) -> Result<u16> {
+		(self as PointerSized).get_u16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16(self, bit_offset: u32, length: u32) -> Result<i16> {
+		(self as PointerSized).get_i16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32(self, bit_offset: u32, length: u32) -> Result<u32> {
+		(self as PointerSized).get_u32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32(self, bit_offset: u32, length: u32) -> Result<i32> {
+		(self as PointerSized).get_i32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64(self, bit_offset: u32, length: u32) -> Result<u64> {
+		(self as PointerSized).get_u64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64> {
+		(self as PointerSized).get_i64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> {
+		(self as PointerSized).get_u128(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> {
+		(self as PointerSized).get_i128(bit_offset, length)
+	}
+}
+
+impl ExtractBitsFromIntegralTypes for isize {
+	#[inline]
+	fn get_u8(self, bit_offset: u32, length: u32) -> Result<u8> {
+		(self as PointerSized).get_u8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i8(self, bit_offset: u32, length: u32) -> Result<i8> {
+		(self as PointerSized).get_i8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16(self, bit_offset: u32, length: u32) -> Result<u16> {
+		(self as PointerSized).get_u16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16(self, bit_offset: u32, length: u32) -> Result<i16> {
+		(self as PointerSized).get_i16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32(self, bit_offset: u32, length: u32) -> Result<u32> {
+		(self as PointerSized).get_u32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32(self, bit_offset: u32, length: u32) -> Result<i32> {
+		(self as PointerSized).get_i32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64(self, bit_offset: u32, length: u32) -> Result<u64> {
+		(self as PointerSized).get_u64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64> {
+		(self as PointerSized).get_i64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> {
+		(self as PointerSized).get_u128(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> {
+		(self as PointerSized).get_i128(bit_offset, length)
+	}
+}
+
+// Floats use the implementation for their bit pattern
+impl ExtractBitsFromIntegralTypes for f32 {
+	#[inline]
+	fn get_u8(self, bit_offset: u32, length: u32) -> Result<u8> {
+		self.to_bits().get_u8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i8(self, bit_offset: u32, length: u32) -> Result<i8> {
+		self.to_bits().get_i8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16(self, bit_offset: u32, length: u32) -> Result<u16> {
+		self.to_bits().get_u16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16(self, bit_offset: u32, length: u32) -> Result<i16> {
+		self.to_bits().get_i16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32(self, bit_offset: u32, length: u32) -> Result<u32> {
+		self.to_bits().get_u32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32(self, bit_offset: u32, length: u32) -> Result<i32> {
+		self.to_bits().get_i32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64(self, bit_offset: u32, length: u32) -> Result<u64> {
+		self.to_bits().get_u64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64> {
+		self.to_bits().get_i64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> {
+		self.to_bits().get_u128(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> {
+		self.to_bits().get_i128(bit_offset, length)
+	}
+}
+
+// Floats use the implementation for their bit pattern
+impl ExtractBitsFromIntegralTypes for f64 {
+	#[inline]
+	fn get_u8(self, bit_offset: u32, length: u32) -> Result<u8> {
+		self.to_bits().get_u8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i8(self, bit_offset: u32, length: u32) -> Result<i8> {
+		self.to_bits().get_i8(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16(self, bit_offset: u32, length: u32) -> Result<u16> {
+		self.to_bits().get_u16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16(self, bit_offset: u32, length: u32) -> Result<i16> {
+		self.to_bits().get_i16(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32(self, bit_offset: u32, length: u32) -> Result<u32> {
+		self.to_bits().get_u32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32(self, bit_offset: u32, length: u32) -> Result<i32> {
+		self.to_bits().get_i32(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64(self, bit_offset: u32, length: u32) -> Result<u64> {
+		self.to_bits().get_u64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64(self, bit_offset: u32, length: u32) -> Result<i64> {
+		self.to_bits().get_i64(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128(self, bit_offset: u32, length: u32) -> Result<u128> {
+		self.to_bits().get_u128(bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i128(self, bit_offset: u32, length: u32) -> Result<i128> {
+		self.to_bits().get_i128(bit_offset, length)
+	}
+}
+
+/// Defines a number of functions, which extract a range of bits from a Vec<u8> or a byte slice
+/// There is one function for each variable type to be returned
+/// **Important:** the contents of the vector are assumed to be **big endian** (network order)
+///
+/// Since the trait is implemented for [u8], data in a memory mapped file or a borrowed
+/// network buffer can be parsed without copying it into a vector:
+///
+/// ```rust
+/// use bitlab::*;
+/// let frame: &[u8] = &[0x48, 0x61, 0x6C, 0x6C, 0x6F];
+/// assert_eq!(frame.get_u16(1, 7, 3).unwrap(), 5);
+/// assert_eq!(frame[1..].get_u8(0, 7, 3).unwrap(), 5);
+/// ```
+pub trait ExtractBitsFromVecU8 {
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 8 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u8(&self, byte_offset: u32, start: u32, length: u32) -> Result<u8>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 8 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i8(&self, byte_offset: u32, start: u32, length: u32) -> Result<i8>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 16 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u16(&self, byte_offset: u32, start: u32, length: u32) -> Result<u16>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 16 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i16(&self, byte_offset: u32, start: u32, length: u32) -> Result<i16>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 32 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u32(&self, byte_offset: u32, start: u32, length: u32) -> Result<u32>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 32 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i32(&self, byte_offset: u32, start: u32, length: u32) -> Result<i32>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 64 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u64(&self, byte_offset: u32, start: u32, length: u32) -> Result<u64>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 64 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i64(&self, byte_offset: u32, start: u32, length: u32) -> Result<i64>;
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing an 8 bit unsigned integer,
+	/// which must not be zero, or an error message. A zero value is rejected with ZERO_VALUE_MSG.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_nonzero_u8(&self, byte_offset: u32, start: u32, length: u32) -> Result<std::num::NonZeroU8> {
+		std::num::NonZeroU8::new(self.get_u8(byte_offset, start, length)?).ok_or_else(|| s!(ZERO_VALUE_MSG))
+	}
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 16 bit unsigned integer,
+	/// which must not be zero, or an error message. A zero value is rejected with ZERO_VALUE_MSG.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_nonzero_u16(&self, byte_offset: u32, start: u32, length: u32) -> Result<std::num::NonZeroU16> {
+		std::num::NonZeroU16::new(self.get_u16(byte_offset, start, length)?).ok_or_else(|| s!(ZERO_VALUE_MSG))
+	}
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 32 bit unsigned integer,
+	/// which must not be zero, or an error message. A zero value is rejected with ZERO_VALUE_MSG.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_nonzero_u32(&self, byte_offset: u32, start: u32, length: u32) -> Result<std::num::NonZeroU32> {
+		std::num::NonZeroU32::new(self.get_u32(byte_offset, start, length)?).ok_or_else(|| s!(ZERO_VALUE_MSG))
+	}
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 64 bit unsigned integer,
+	/// which must not be zero, or an error message. A zero value is rejected with ZERO_VALUE_MSG.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_nonzero_u64(&self, byte_offset: u32, start: u32, length: u32) -> Result<std::num::NonZeroU64> {
+		std::num::NonZeroU64::new(self.get_u64(byte_offset, start, length)?).ok_or_else(|| s!(ZERO_VALUE_MSG))
+	}
+
+	/// Extracts a range of bits from a Vec<u8> in **sign-magnitude** representation and returns a Result object
+	/// containing a signed 8 bit integer or an error message. The most significant bit of the field is the sign,
+	/// the other bits are the magnitude. Negative zero is returned as zero.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted including the sign bit.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let v: Vec<u8> = vec!{ 0b1011_0011 };
+	/// assert_eq!(v.get_i8_sign_magnitude(0, 0, 4).unwrap(), -3);
+	/// assert_eq!(v.get_i8(0, 0, 4).unwrap(), -5);
+	/// ```
+	fn get_i8_sign_magnitude(&self, byte_offset: u32, start: u32, length: u32) -> Result<i8> {
+		if length > 8 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i8");
+		}
+		Ok(sign_magnitude_field(self.get_u64(byte_offset, start, length)?, length) as i8)
+	}
+
+	/// Same as get_i8_sign_magnitude, but returns a signed 16 bit integer
+	fn get_i16_sign_magnitude(&self, byte_offset: u32, start: u32, length: u32) -> Result<i16> {
+		if length > 16 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i16");
+		}
+		Ok(sign_magnitude_field(self.get_u64(byte_offset, start, length)?, length) as i16)
+	}
+
+	/// Same as get_i8_sign_magnitude, but returns a signed 32 bit integer
+	fn get_i32_sign_magnitude(&self, byte_offset: u32, start: u32, length: u32) -> Result<i32> {
+		if length > 32 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i32");
+		}
+		Ok(sign_magnitude_field(self.get_u64(byte_offset, start, length)?, length) as i32)
+	}
+
+	/// Same as get_i8_sign_magnitude, but returns a signed 64 bit integer
+	fn get_i64_sign_magnitude(&self, byte_offset: u32, start: u32, length: u32) -> Result<i64> {
+		Ok(sign_magnitude_field(self.get_u64(byte_offset, start, length)?, length))
+	}
+
+	/// Extracts a range of bits from a Vec<u8> in **excess-k** representation (offset binary, biased) and returns
+	/// a Result object containing the unsigned value of the field minus k as a signed 8 bit integer or an error message,
+	/// which is also returned if the result does not fit into the type.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted (at most 64).
+	/// - **k** (i64) the bias, e.g. 127 for the exponent of an f32
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // The exponent of the f32 0.25
+	/// let v: Vec<u8> = 0.25f32.to_be_bytes().to_vec();
+	/// assert_eq!(v.get_i8_excess_k(0, 1, 8, 127).unwrap(), -2);
+	/// ```
+	fn get_i8_excess_k(&self, byte_offset: u32, start: u32, length: u32, k: i64) -> Result<i8> {
+		excess_k_field(self.get_u64(byte_offset, start, length)?, k, "i8")
+	}
+
+	/// Same as get_i8_excess_k, but returns a signed 16 bit integer
+	fn get_i16_excess_k(&self, byte_offset: u32, start: u32, length: u32, k: i64) -> Result<i16> {
+		excess_k_field(self.get_u64(byte_offset, start, length)?, k, "i16")
+	}
+
+	/// Same as get_i8_excess_k, but returns a signed 32 bit integer
+	fn get_i32_excess_k(&self, byte_offset: u32, start: u32, length: u32, k: i64) -> Result<i32> {
+		excess_k_field(self.get_u64(byte_offset, start, length)?, k, "i32")
+	}
+
+	/// Same as get_i8_excess_k, but returns a signed 64 bit integer
+	fn get_i64_excess_k(&self, byte_offset: u32, start: u32, length: u32, k: i64) -> Result<i64> {
+		excess_k_field(self.get_u64(byte_offset, start, length)?, k, "i64")
+	}
+
+	/// Extracts a range of bits from a Vec<u8>, whose bytes are in **little endian** order, and returns
+	/// a Result object containing a 16 bit unsigned integer or an error message. The length must be a multiple of 8.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u16_le(&self, byte_offset: u32, start: u32, length: u32) -> Result<u16> {
+		if length > 16 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u16");
+		}
+
+		Ok(little_endian_field(self.get_u64(byte_offset, start, length)?, length)? as u16)
+	}
+
+	/// Extracts a range of bits from a Vec<u8>, whose bytes are in **little endian** order, and returns
+	/// a Result object containing a signed 16 bit integer or an error message. The length must be a multiple of 8.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i16_le(&self, byte_offset: u32, start: u32, length: u32) -> Result<i16> {
+		if length > 16 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i16");
+		}
+
+		let v = little_endian_field(self.get_u64(byte_offset, start, length)?, length)?;
+		// Sign extension
+		Ok(((v << (64 - length)) as i64 >> (64 - length)) as i16)
+	}
+
+	/// Extracts a range of bits from a Vec<u8>, whose bytes are in **little endian** order, and returns
+	/// a Result object containing a 32 bit unsigned integer or an error message. The length must be a multiple of 8.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u32_le(&self, byte_offset: u32, start: u32, length: u32) -> Result<u32> {
+		if length > 32 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u32");
+		}
+
+		Ok(little_endian_field(self.get_u64(byte_offset, start, length)?, length)? as u32)
+	}
+
+	/// Extracts a range of bits from a Vec<u8>, whose bytes are in **little endian** order, and returns
+	/// a Result object containing a signed 32 bit integer or an error message. The length must be a multiple of 8.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i32_le(&self, byte_offset: u32, start: u32, length: u32) -> Result<i32> {
+		if length > 32 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i32");
+		}
+
+		let v = little_endian_field(self.get_u64(byte_offset, start, length)?, length)?;
+		// Sign extension
+		Ok(((v << (64 - length)) as i64 >> (64 - length)) as i32)
+	}
+
+	/// Extracts a range of bits from a Vec<u8>, whose bytes are in **little endian** order, and returns
+	/// a Result object containing a 64 bit unsigned integer or an error message. The length must be a multiple of 8.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u64_le(&self, byte_offset: u32, start: u32, length: u32) -> Result<u64> {
+		little_endian_field(self.get_u64(byte_offset, start, length)?, length)
+	}
+
+	/// Extracts a range of bits from a Vec<u8>, whose bytes are in **little endian** order, and returns
+	/// a Result object containing a signed 64 bit integer or an error message. The length must be a multiple of 8.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i64_le(&self, byte_offset: u32, start: u32, length: u32) -> Result<i64> {
+		let v = little_endian_field(self.get_u64(byte_offset, start, length)?, length)?;
+		// Sign extension
+		Ok(((v << (64 - length)) as i64 >> (64 - length)) as i64)
+	}
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a 128 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u128(&self, byte_offset: u32, start: u32, length: u32) -> Result<u128> {
+		if length > 128 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u128");
+		}
+		if length <= 64 {
+			return Ok(self.get_u64(byte_offset, start, length)? as u128);
+		}
+
+		// Two reads: the leading length - 64 bits and the last 64 bits
+		let high = self.get_u64(byte_offset, start, length - 64)?;
+		let low = self.get_u64(byte_offset, start.checked_add(length - 64).ok_or_else(|| s!(OUT_OF_RANGE_MSG))?, 64)?;
+		Ok(((high as u128) << 64) | low as u128)
+	}
+
+	/// Extracts a range of bits from a Vec<u8> and returns a Result object containing a signed 128 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i128(&self, byte_offset: u32, start: u32, length: u32) -> Result<i128> {
+		if length > 128 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "i128");
+		}
+
+		let v = self.get_u128(byte_offset, start, length)?;
+		// Sign extension
+		Ok(((v << (128 - length)) as i128) >> (128 - length))
+	}
+
+	/// Extracts 32 bits from a Vec<u8> and interprets them as an IEEE 754 single precision float.
+	/// Returns a Result object containing the float or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	fn get_f32(&self, byte_offset: u32, start: u32) -> Result<f32> {
+		Ok(f32::from_bits(self.get_u32(byte_offset, start, 32)?))
+	}
+
+	/// Extracts 64 bits from a Vec<u8> and interprets them as an IEEE 754 double precision float.
+	/// Returns a Result object containing the float or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	fn get_f64(&self, byte_offset: u32, start: u32) -> Result<f64> {
+		Ok(f64::from_bits(self.get_u64(byte_offset, start, 64)?))
+	}
+
+	/// Extracts 16 bits from a Vec<u8> and interprets them as an IEEE 754 half precision float.
+	/// Returns a Result object containing the value as a f32, which represents it exactly, or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let v: Vec<u8> = vec!{ 0x00, 0xF0, 0x00 };
+	/// assert_eq!(v.get_f16(0, 6).unwrap(), 1.0);
+	/// ```
+	fn get_f16(&self, byte_offset: u32, start: u32) -> Result<f32> {
+		Ok(minifloat::F16.decode(self.get_u64(byte_offset, start, 16)?) as f32)
+	}
+
+	/// Extracts 16 bits from a Vec<u8> and interprets them as a bfloat16, i.e. the upper half of an
+	/// IEEE 754 single precision float. Returns a Result object containing the float or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	fn get_bf16(&self, byte_offset: u32, start: u32) -> Result<f32> {
+		Ok(minifloat::BF16.decode(self.get_u64(byte_offset, start, 16)?) as f32)
+	}
+
+	/// Extracts a range of bits as the type T, which is chosen by the caller.
+	/// Returns a Result object containing the value or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+	/// let a: u16 = v.get_at(1, 7, 3).unwrap();
+	/// assert_eq!(a, 5);
+	/// assert_eq!(v.get_at::<i8>(1, 7, 3).unwrap(), -3);
+	/// ```
+	fn get_at<T: BitTarget>(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<T> {
+		T::extract_from_bytes(self, byte_offset, bit_offset, length)
+	}
+
+	/// Extracts a range of bits of any length from a Vec<u8> and returns a Result object containing
+	/// the bits as a vector of bytes or an error message. The bits are **left aligned**, i.e. the first
+	/// extracted bit is the most significant bit of the first byte. If the length is not a multiple of 8,
+	/// the unused least significant bits of the last byte are zero.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+	/// assert_eq!(v.get_bits(0, 4, 20).unwrap(), vec!{ 0x86, 0x16, 0xC0 });
+	/// ```
+	fn get_bits(&self, byte_offset: u32, start: u32, length: u32) -> Result<Vec<u8>> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+
+		// Check the whole range first
+		let last = start.checked_add(length - 1).ok_or_else(|| s!(OUT_OF_RANGE_MSG))?;
+		self.get_u8(byte_offset, last, 1)?;
+
+		let mut bytes = Vec::with_capacity(length.div_ceil(8) as usize);
+		let mut done = 0;
+		while done < length {
+			let n = std::cmp::min(8, length - done);
+			bytes.push(self.get_u8(byte_offset, start + done, n)? << (8 - n));
+			done += n;
+		}
+		Ok(bytes)
+	}
+
+	/// Extracts a packed BCD number, i.e. 4 bits per decimal digit with the most significant digit
+	/// first, and returns a Result object containing its value or an error message.
+	/// A field with more than 19 digits does not fit into a u64.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the first digit. Zero is the most significant bit
+	/// - **digits** (u32) the number of decimal digits.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // 23:59 as an RTC would store it after a 3 bit status field
+	/// let v: Vec<u8> = vec!{ 0b1010_0100, 0b0110_1011, 0b0010_0000 };
+	/// assert_eq!(v.get_bcd(0, 3, 4).unwrap(), 2359);
+	/// ```
+	fn get_bcd(&self, byte_offset: u32, start: u32, digits: u32) -> Result<u64> {
+		if digits > 19 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+
+		let packed = self.get_u128(byte_offset, start, digits * 4)?;
+		let mut value = 0;
+		for i in (0..digits).rev() {
+			let digit = ((packed >> (i * 4)) & 0xF) as u64;
+			if digit > 9 {
+				return Err(s!(BCD_DIGIT_MSG));
+			}
+			value = value * 10 + digit;
+		}
+		Ok(value)
+	}
+
+	/// Extracts a fixed point number in the Q format with int_bits integer bits and frac_bits
+	/// fractional bits and returns a Result object containing its value or an error message.
+	/// A signed number is stored in two's complement and its sign bit counts as an integer bit,
+	/// so a signed Q1.15 number has a length of 16 bits. The length must not exceed 64 bits.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the number. Zero is the most significant bit
+	/// - **int_bits** (u32) the number of integer bits including the sign bit
+	/// - **frac_bits** (u32) the number of fractional bits
+	/// - **signed** (bool) whether the number is signed
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // A temperature in signed Q8.4 format after a 4 bit register address: -10.25
+	/// let v: Vec<u8> = vec!{ 0x3F, 0x5C };
+	/// assert_eq!(v.get_fixed_point(0, 4, 8, 4, true).unwrap(), -10.25);
+	/// ```
+	fn get_fixed_point(&self, byte_offset: u32, start: u32, int_bits: u32, frac_bits: u32, signed: bool) -> Result<f64> {
+		let length = int_bits.checked_add(frac_bits).ok_or_else(|| s!(LEN_TOO_BIG_MSG) + "u64")?;
+		let raw = if signed {
+			self.get_i64(byte_offset, start, length)? as f64
+		} else {
+			self.get_u64(byte_offset, start, length)? as f64
+		};
+		Ok(raw / 2f64.powi(frac_bits as i32))
+	}
+
+	/// Extracts several fields of at most 64 bits each, which are given as tuples of
+	/// (byte_offset, bit_offset, length), and returns a Result object containing their values
+	/// in the same order or an error message. All fields are checked before any of them is extracted.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let v: Vec<u8> = vec!{ 0x45, 0x00, 0x05, 0xDC };
+	/// assert_eq!(v.get_fields(&[(0, 0, 4), (0, 4, 4), (2, 0, 16)]).unwrap(), vec!{ 4, 5, 1500 });
+	/// ```
+	fn get_fields(&self, fields: &[(u32, u32, u32)]) -> Result<Vec<u64>> {
+		fields.iter().map(|&(byte_offset, bit_offset, length)| self.get_u64(byte_offset, bit_offset, length)).collect()
+	}
+
+	/// Returns an iterator over consecutive fields of field_len bits, which starts at the given
+	/// offsets and ends with the last field that completely fits into the vector. Every value
+	/// is a Result object, so that an invalid field length (zero or more than 64) is reported.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // Packed 12 bit samples
+	/// let v: Vec<u8> = vec!{ 0x12, 0x34, 0x56, 0x78 };
+	/// let samples: Vec<u64> = v.iter_fields(0, 0, 12).map(|x| x.unwrap()).collect();
+	/// assert_eq!(samples, vec!{ 0x123, 0x456 });
+	/// ```
+	fn iter_fields(&self, byte_offset: u32, bit_offset: u32, field_len: u32) -> FieldIter<'_, Self> {
+		FieldIter::new(self, byte_offset, bit_offset, field_len)
+	}
+
+	/// Gathers count bits (at most 64), which are stride bits apart, starting at the given offsets,
+	/// and returns them in a u64, where the first bit is the most significant one.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // The flags of the second of three interleaved channels
+	/// let v: Vec<u8> = vec!{ 0b0100_0001, 0b0010_0000 };
+	/// assert_eq!(v.get_strided(0, 1, 3, 4).unwrap(), 0b1011);
+	/// assert_eq!(v.get_strided_bools(0, 1, 3, 4).unwrap(), vec!{ true, false, true, true });
+	/// ```
+	fn get_strided(&self, byte_offset: u32, bit_offset: u32, stride: u32, count: u32) -> Result<u64> {
+		if count > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		Ok(self.get_strided_bools(byte_offset, bit_offset, stride, count as usize)?
+			.into_iter()
+			.fold(0, |acc, bit| acc << 1 | bit as u64))
+	}
+
+	/// Same as get_strided, but returns any number of bits as a vector of bools
+	fn get_strided_bools(&self, byte_offset: u32, bit_offset: u32, stride: u32, count: usize) -> Result<Vec<bool>> {
+		if stride == 0 {
+			return Err(s!(STRIDE_ZERO_MSG));
+		}
+		let start = byte_offset as u64 * 8 + bit_offset as u64;
+		// Check the last position before anything is allocated
+		if let Some(last) = strided_last(start, stride, count) {
+			if last / 8 > u32::MAX as u64 {
+				return Err(s!(OUT_OF_RANGE_MSG));
+			}
+			self.get_u8((last / 8) as u32, (last % 8) as u32, 1)?;
+		}
+		let mut result = Vec::with_capacity(count);
+		for i in 0..count as u64 {
+			let position = start + i * stride as u64;
+			result.push(self.get_u8((position / 8) as u32, (position % 8) as u32, 1)? == 1);
+		}
+		Ok(result)
+	}
+
+	/// Extracts N bytes, which start at an arbitrary bit offset, into an array, e.g. a MAC address
+	/// or a UUID at an unaligned position. Returns an error if N is zero or if the bytes exceed the vector.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // A 3 byte OUI after a 4 bit header
+	/// let v: Vec<u8> = vec!{ 0x50, 0x01, 0xB8, 0x20 };
+	/// assert_eq!(v.get_bytes::<3>(0, 4).unwrap(), [0x00, 0x1B, 0x82]);
+	/// ```
+	fn get_bytes<const N: usize>(&self, byte_offset: u32, bit_offset: u32) -> Result<[u8; N]> {
+		if N == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		let mut result = [0u8; N];
+		for (i, byte) in result.iter_mut().enumerate() {
+			let offset = byte_offset as u64 + i as u64;
+			if offset > u32::MAX as u64 {
+				return Err(s!(OUT_OF_RANGE_MSG));
+			}
+			*byte = self.get_u8(offset as u32, bit_offset, 8)?;
+		}
+		Ok(result)
+	}
+
+	/// Extracts an IPv4 address of 32 bits, which starts at an arbitrary bit offset
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let v: Vec<u8> = vec!{ 0xC0, 0xA8, 0x00, 0x01 };
+	/// assert_eq!(v.get_ipv4(0, 0).unwrap(), std::net::Ipv4Addr::new(192, 168, 0, 1));
+	/// ```
+	fn get_ipv4(&self, byte_offset: u32, bit_offset: u32) -> Result<std::net::Ipv4Addr> {
+		Ok(std::net::Ipv4Addr::from(self.get_bytes::<4>(byte_offset, bit_offset)?))
+	}
+
+	/// Extracts an IPv6 address of 128 bits, which starts at an arbitrary bit offset
+	fn get_ipv6(&self, byte_offset: u32, bit_offset: u32) -> Result<std::net::Ipv6Addr> {
+		Ok(std::net::Ipv6Addr::from(self.get_bytes::<16>(byte_offset, bit_offset)?))
+	}
+
+	/// Extracts a MAC address of 48 bits, which starts at an arbitrary bit offset
+	fn get_mac(&self, byte_offset: u32, bit_offset: u32) -> Result<[u8; 6]> {
+		self.get_bytes::<6>(byte_offset, bit_offset)
+	}
+
+	/// Extracts a UUID of 128 bits, which starts at an arbitrary bit offset
+	///
+	/// ```rust
+	/// # #[cfg(feature = "uuid")] {
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0x00; 17 };
+	/// let id = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+	/// v.set_uuid(0, 3, id).unwrap();
+	/// assert_eq!(v.get_uuid(0, 3).unwrap(), id);
+	/// # }
+	/// ```
+	#[cfg(feature = "uuid")]
+	fn get_uuid(&self, byte_offset: u32, bit_offset: u32) -> Result<uuid::Uuid> {
+		Ok(uuid::Uuid::from_bytes(self.get_bytes::<16>(byte_offset, bit_offset)?))
+	}
+
+	/// Extracts a UTF-8 string of n_bytes bytes, which starts at an arbitrary bit offset.
+	/// The string is borrowed if it starts at a byte boundary.
+	/// Returns an error if the bytes exceed the vector or are not valid UTF-8.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // "Hi" after a 3 bit header
+	/// let v: Vec<u8> = vec!{ 0xA9, 0x0D, 0x20 };
+	/// assert_eq!(v.get_str(0, 3, 2).unwrap(), "Hi");
+	/// ```
+	fn get_str(&self, byte_offset: u32, bit_offset: u32, n_bytes: u32) -> Result<std::borrow::Cow<'_, str>> {
+		let mut bytes = Vec::with_capacity(n_bytes as usize);
+		for i in 0..n_bytes {
+			let offset = byte_offset.checked_add(i).ok_or_else(|| s!(OUT_OF_RANGE_MSG))?;
+			bytes.push(self.get_u8(offset, bit_offset, 8)?);
+		}
+		String::from_utf8(bytes)
+			.map(std::borrow::Cow::Owned)
+			.map_err(|e| s!(INVALID_UTF8_MSG) + &e.utf8_error().to_string())
+	}
+
+	/// Extracts length bits (at most 64) and converts them into a type with TryFrom<u64>, typically an enum.
+	/// Returns an error, which names the value and the type, if the value is not a valid discriminant.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// use std::convert::TryFrom;
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// enum Opcode { Load, Store, Jump }
+	///
+	/// impl TryFrom<u64> for Opcode {
+	///     type Error = ();
+	///     fn try_from(value: u64) -> Result<Self, ()> {
+	///         match value {
+	///             0 => Ok(Opcode::Load),
+	///             1 => Ok(Opcode::Store),
+	///             2 => Ok(Opcode::Jump),
+	///             _ => Err(()),
+	///         }
+	///     }
+	/// }
+	///
+	/// let v: Vec<u8> = vec!{ 0b0010_1100 };
+	/// assert_eq!(v.get_enum::<Opcode>(0, 0, 3).unwrap(), Opcode::Store);
+	/// assert!(v.get_enum::<Opcode>(0, 3, 3).unwrap_err().starts_with("Invalid discriminant 3"));
+	/// ```
+	fn get_enum<E: std::convert::TryFrom<u64>>(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<E> {
+		let value = self.get_u64(byte_offset, bit_offset, length)?;
+		E::try_from(value).map_err(|_| format!("Invalid discriminant {} for {}", value, std::any::type_name::<E>()))
+	}
+}
+
+impl ExtractBitsFromVecU8 for [u8] {
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
+	fn get_u8(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u8> {
+		if length == 0 { return Err(s!(LEN_ZERO)); };
+
+		if length <= 8 {
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
+				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
+				let mut byte_offset_copy = byte_offset as usize;
+				let mut bit_offset_copy = bit_offset;
+
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
+				bit_offset_copy -= (bit_offset_copy / 8) * 8;
+
+				if bit_offset_copy + length <= 8 {
+					let mut copy: u8 = self[byte_offset_copy];
+					// Assume that the data is given in big endian and
+					// convert it to whatever endianness we have on the users machine
+					copy = u8::from_be(copy);
+					// Lets clear the bits on both sides of the range of bits of interest
+					// First clear the ones on the left side
+					copy <<= bit_offset_copy;
+					// Second, push it all to the right end
+					copy >>= 8 - length;
+					return Ok(copy);
+				} else { // The range of bits spans over 2 bytes (not more)
+					// Copy the first byte
+					let copy1: u8 = self[byte_offset_copy];
+
+					// Copy that into a bigger variable type
+					let mut copy1_as_u16: u16 = copy1 as u16;
+
+					// Shift 8 bits to the left, since these are the first 2 of 3 bytes
+					copy1_as_u16 <<= 8;
+
+					// Now copy the second bytes
+					let copy2: u8 = self[byte_offset_copy + 1];
+
+					// Logical OR these two to get the original 2 bytes
+					let mut result = copy1_as_u16 | (copy2 as u16);
+
+					// From now on, process like the normal case above
+					result <<= bit_offset_copy;
+					result >>= 16 - length;
+					return Ok(result as u8);
+				}
+			} else {
+				return Err(s!(OUT_OF_RANGE_MSG))
+			}
+		} else {
+			return Err(s!(OUT_OF_RANGE_MSG))
+		}
+	}
+
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
+	fn get_i8(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i8> {
+		if length == 0 { return Err(s!(LEN_ZERO)); };
+
+		if length <= 8 {
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
+				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
+				let mut byte_offset_copy = byte_offset as usize;
+				let mut bit_offset_copy = bit_offset;
+
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
+				bit_offset_copy -= (bit_offset_copy / 8) * 8;
+
+				if bit_offset_copy + length <= 8 {
+					let mut copy: i8 = self[byte_offset_copy] as i8;
+					// Assume that the data is given in big endian and
+					// convert it to whatever endianness we have on the users machine
+					copy = i8::from_be(copy);
+					// Lets clear the bits on both sides of the range of bits of interest
+					// First clear the ones on the left side
+					copy <<= bit_offset_copy;
+					// Second, push it all to the right end
+					copy >>= 8 - length;
+					return Ok(copy);
+				} else { // The range of bits spans over 2 bytes (not more)
+					// Copy the first byte
+					let copy1: i8 = self[byte_offset_copy] as i8;
+
+					// Copy that into a bigger variable type
+					let mut copy1_as_i16: i16 = copy1 as i16;
+
+					// Shift 8 bits to the left, since these are the first 2 of 3 bytes
+					copy1_as_i16 <<= 8;
 
 					// Now copy the second bytes
-					let copy2: i8 = self[byte_offset_copy as usize + 1] as i8;
+					let copy2: i8 = self[byte_offset_copy + 1] as i8;
 
 					// Logical OR these two to get the original 2 bytes
 					let mut result = copy1_as_i16 | (copy2 as i16);
@@ -949,21 +2136,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 		}
 	}
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
 	fn get_u16(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u16> {
 		if length == 0 { return Err(s!(LEN_ZERO)); };
 
 		if length <= 16 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
 				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
+				let mut byte_offset_copy = byte_offset as usize;
 				let mut bit_offset_copy = bit_offset;
 
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
 				bit_offset_copy -= (bit_offset_copy / 8) * 8;
 
 				if bit_offset_copy + length <= 8 {
 					// Don't touch the original
-					let copy1 = self[byte_offset_copy as usize] as i8;
+					let copy1 = self[byte_offset_copy] as i8;
 
 					// Expand to u16
 					let mut copy2 = copy1 as u16;
@@ -977,13 +2165,13 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy2);
 				} else if bit_offset_copy + length <= 16 {
-					let mut copy1 = self[byte_offset_copy as usize] as u16;
+					let mut copy1 = self[byte_offset_copy] as u16;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 8;
 
-					let copy2 = self[byte_offset_copy as usize + 1] as u16;
+					let copy2 = self[byte_offset_copy + 1] as u16;
 
 					// Logical OR these two to get the original 2 bytes
 					let mut copy3 = copy1 | copy2;
@@ -997,16 +2185,16 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy3);
 				} else { // The range of bits spans over 3 bytes (not more)
-					let mut copy1 = self[byte_offset_copy as usize] as u32;
+					let mut copy1 = self[byte_offset_copy] as u32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 16;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u32;
+					let mut copy2 = self[byte_offset_copy + 1] as u32;
 					copy2 <<= 8;
 
-					let copy3 = self[byte_offset_copy as usize + 2] as u32;
+					let copy3 = self[byte_offset_copy + 2] as u32;
 					// copy3 <<= 0;
 
 					// Logical OR these two to get the original 3 bytes
@@ -1029,21 +2217,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 		}
 	}
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
 	fn get_i16(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i16> {
 		if length == 0 { return Err(s!(LEN_ZERO)); };
 
 		if length <= 16 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
 				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
+				let mut byte_offset_copy = byte_offset as usize;
 				let mut bit_offset_copy = bit_offset;
 
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
 				bit_offset_copy -= (bit_offset_copy / 8) * 8;
 
 				if bit_offset_copy + length <= 8 {
 					// Don't touch the original
-					let copy1 = self[byte_offset_copy as usize] as i8;
+					let copy1 = self[byte_offset_copy] as i8;
 
 					// Expand to i16
 					let mut copy2 = copy1 as i16;
@@ -1057,13 +2246,13 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy2);
 				} else if bit_offset_copy + length <= 16 {
-					let mut copy1 = self[byte_offset_copy as usize] as i16;
+					let mut copy1 = self[byte_offset_copy] as i16;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 8;
 
-					let copy2 = self[byte_offset_copy as usize + 1] as i16;
+					let copy2 = self[byte_offset_copy + 1] as i16;
 
 					// Logical OR these two to get the original 2 bytes
 					let mut copy3 = copy1 | copy2;
@@ -1077,16 +2266,16 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy3);
 				} else { // The range of bits spans over 3 bytes (not more)
-					let mut copy1 = self[byte_offset_copy as usize] as i32;
+					let mut copy1 = self[byte_offset_copy] as i32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 16;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i32;
+					let mut copy2 = self[byte_offset_copy + 1] as i32;
 					copy2 <<= 8;
 
-					let copy3 = self[byte_offset_copy as usize + 2] as i32;
+					let copy3 = self[byte_offset_copy + 2] as i32;
 					// copy3 <<= 0;
 
 					// Logical OR these two to get the original 3 bytes
@@ -1109,21 +2298,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 		}
 	}
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
 	fn get_u32(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u32> {
 		if length == 0 { return Err(s!(LEN_ZERO)); };
 
 		if length <= 32 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
 				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
+				let mut byte_offset_copy = byte_offset as usize;
 				let mut bit_offset_copy = bit_offset;
 
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
 				bit_offset_copy -= (bit_offset_copy / 8) * 8;
 
 				if bit_offset_copy + length <= 8 {
 					// Don't touch the original
-					let copy1 = self[byte_offset_copy as usize] as u8;
+					let copy1 = self[byte_offset_copy];
 
 					// Expand to u32
 					let mut copy2 = copy1 as u32;
@@ -1137,13 +2327,13 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy2);
 				} else if bit_offset_copy + length <= 16 {
-					let mut copy1 = self[byte_offset_copy as usize] as u32;
+					let mut copy1 = self[byte_offset_copy] as u32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 8;
 
-					let copy2 = self[byte_offset_copy as usize + 1] as u32;
+					let copy2 = self[byte_offset_copy + 1] as u32;
 					// copy2 <<= 0;
 
 					// Logical OR these two to get the original two bytes
@@ -1158,16 +2348,16 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy3);
 				} else if bit_offset_copy + length <= 24 {
-					let mut copy1 = self[byte_offset_copy as usize] as u32;
+					let mut copy1 = self[byte_offset_copy] as u32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 16;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u32;
+					let mut copy2 = self[byte_offset_copy + 1] as u32;
 					copy2 <<= 8;
 
-					let copy3 = self[byte_offset_copy as usize + 2] as u32;
+					let copy3 = self[byte_offset_copy + 2] as u32;
 					// copy3 <<= 0;
 
 					// Logical OR these three to get the original three bytes
@@ -1182,19 +2372,19 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy4 as u32);
 				} else if bit_offset_copy + length <= 32 {
-					let mut copy1 = self[byte_offset_copy as usize] as u32;
+					let mut copy1 = self[byte_offset_copy] as u32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 24;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u32;
+					let mut copy2 = self[byte_offset_copy + 1] as u32;
 					copy2 <<= 16;
 
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u32;
+					let mut copy3 = self[byte_offset_copy + 2] as u32;
 					copy3 <<= 8;
 
-					let copy4 = self[byte_offset_copy as usize + 3] as u32;
+					let copy4 = self[byte_offset_copy + 3] as u32;
 					// copy4 <<= 0;
 
 					// Logical OR these four to get the original four bytes
@@ -1209,22 +2399,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy5 as u32);
 				} else {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 32;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 24;
 
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let mut copy3 = self[byte_offset_copy + 2] as u64;
 					copy3 <<= 16;
 
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u64;
+					let mut copy4 = self[byte_offset_copy + 3] as u64;
 					copy4 <<= 8;
 
-					let copy5 = self[byte_offset_copy as usize + 4] as u64;
+					let copy5 = self[byte_offset_copy + 4] as u64;
 					// copy5 <<= 0;
 
 					// Logical OR these five to get the original five bytes
@@ -1247,21 +2437,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 		}
 	}
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
 	fn get_i32(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i32> {
 		if length == 0 { return Err(s!(LEN_ZERO)); };
 
 		if length <= 32 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
 				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
+				let mut byte_offset_copy = byte_offset as usize;
 				let mut bit_offset_copy = bit_offset;
 
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
 				bit_offset_copy -= (bit_offset_copy / 8) * 8;
 
 				if bit_offset_copy + length <= 8 {
 					// Don't touch the original
-					let copy1 = self[byte_offset_copy as usize] as i8;
+					let copy1 = self[byte_offset_copy] as i8;
 
 					// Expand to i32
 					let mut copy2 = copy1 as i32;
@@ -1275,13 +2466,13 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy2);
 				} else if bit_offset_copy + length <= 16 {
-					let mut copy1 = self[byte_offset_copy as usize] as i32;
+					let mut copy1 = self[byte_offset_copy] as i32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 8;
 
-					let copy2 = self[byte_offset_copy as usize + 1] as i32;
+					let copy2 = self[byte_offset_copy + 1] as i32;
 					// copy2 <<= 0;
 
 					// Logical OR these two to get the original 2 bytes
@@ -1296,16 +2487,16 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy3);
 				} else if bit_offset_copy + length <= 24 {
-					let mut copy1 = self[byte_offset_copy as usize] as i32;
+					let mut copy1 = self[byte_offset_copy] as i32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 16;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i32;
+					let mut copy2 = self[byte_offset_copy + 1] as i32;
 					copy2 <<= 8;
 
-					let copy3 = self[byte_offset_copy as usize + 2] as i32;
+					let copy3 = self[byte_offset_copy + 2] as i32;
 					// copy3 <<= 0;
 
 					// Logical OR these two to get the original 3 bytes
@@ -1320,19 +2511,19 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy4 as i32);
 				} else if bit_offset_copy + length <= 32 {
-					let mut copy1 = self[byte_offset_copy as usize] as i32;
+					let mut copy1 = self[byte_offset_copy] as i32;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 24;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i32;
+					let mut copy2 = self[byte_offset_copy + 1] as i32;
 					copy2 <<= 16;
 
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i32;
+					let mut copy3 = self[byte_offset_copy + 2] as i32;
 					copy3 <<= 8;
 
-					let copy4 = self[byte_offset_copy as usize + 3] as i32;
+					let copy4 = self[byte_offset_copy + 3] as i32;
 					// copy4 <<= 0;
 
 					// Logical OR these two to get the original 3 bytes
@@ -1347,22 +2538,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 
 					return Ok(copy5 as i32);
 				} else {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 32;
 
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 24;
 
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let mut copy3 = self[byte_offset_copy + 2] as i64;
 					copy3 <<= 16;
 
-					let mut copy4 = self[byte_offset_copy as usize + 3] as i64;
+					let mut copy4 = self[byte_offset_copy + 3] as i64;
 					copy4 <<= 8;
 
-					let copy5 = self[byte_offset_copy as usize + 4] as i64;
+					let copy5 = self[byte_offset_copy + 4] as i64;
 					// copy5 <<= 0;
 
 					// Logical OR these two to get the original 3 bytes
@@ -1385,21 +2576,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 		}
 	}
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
 	fn get_u64(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u64> {
 		if length == 0 { return Err(s!(LEN_ZERO)); };
 	
 		if length <= 64 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
 				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
+				let mut byte_offset_copy = byte_offset as usize;
 				let mut bit_offset_copy = bit_offset;
 	
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
 				bit_offset_copy -= (bit_offset_copy / 8) * 8;
 	
 				if bit_offset_copy + length <= 8 {
 					// Don't touch the original
-					let copy1 = self[byte_offset_copy as usize] as u8;
+					let copy1 = self[byte_offset_copy];
 	
 					// Expand to u64
 					let mut copy2 = copy1 as u64;
@@ -1413,13 +2605,13 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy2);
 				} else if bit_offset_copy + length <= 16 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 8;
 	
-					let copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let copy2 = self[byte_offset_copy + 1] as u64;
 					// copy2 <<= 0;
 	
 					// Logical OR these two to get the original 2 bytes
@@ -1434,16 +2626,16 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy3);
 				} else if bit_offset_copy + length <= 24 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 16;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 8;
 	
-					let copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let copy3 = self[byte_offset_copy + 2] as u64;
 					// copy3 <<= 0;
 	
 					// Logical OR these three to get the original three bytes
@@ -1458,19 +2650,19 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy4 as u64);
 				} else if bit_offset_copy + length <= 32 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 24;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 16;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let mut copy3 = self[byte_offset_copy + 2] as u64;
 					copy3 <<= 8;
 	
-					let copy4 = self[byte_offset_copy as usize + 3] as u64;
+					let copy4 = self[byte_offset_copy + 3] as u64;
 					// copy4 <<= 0;
 	
 					// Logical OR these four to get the original four bytes
@@ -1485,22 +2677,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy5 as u64);
 				} else if bit_offset_copy + length <= 40 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 32;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 24;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let mut copy3 = self[byte_offset_copy + 2] as u64;
 					copy3 <<= 16;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u64;
+					let mut copy4 = self[byte_offset_copy + 3] as u64;
 					copy4 <<= 8;
 	
-					let copy5 = self[byte_offset_copy as usize + 4] as u64;
+					let copy5 = self[byte_offset_copy + 4] as u64;
 					// copy5 <<= 0;
 	
 					// Logical OR these five to get the original five bytes
@@ -1515,25 +2707,25 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy6 as u64);
 				} else if bit_offset_copy + length <= 48 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 40;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 32;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let mut copy3 = self[byte_offset_copy + 2] as u64;
 					copy3 <<= 24;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u64;
+					let mut copy4 = self[byte_offset_copy + 3] as u64;
 					copy4 <<= 16;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as u64;
+					let mut copy5 = self[byte_offset_copy + 4] as u64;
 					copy5 <<= 8;
 	
-					let copy6 = self[byte_offset_copy as usize + 5] as u64;
+					let copy6 = self[byte_offset_copy + 5] as u64;
 					// copy6 <<= 0;
 	
 					// Logical OR these six to get the original six bytes
@@ -1548,28 +2740,28 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy7 as u64);
 				} else if bit_offset_copy + length <= 56 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 48;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 40;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let mut copy3 = self[byte_offset_copy + 2] as u64;
 					copy3 <<= 32;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u64;
+					let mut copy4 = self[byte_offset_copy + 3] as u64;
 					copy4 <<= 24;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as u64;
+					let mut copy5 = self[byte_offset_copy + 4] as u64;
 					copy5 <<= 16;
 	
-					let mut copy6 = self[byte_offset_copy as usize + 5] as u64;
+					let mut copy6 = self[byte_offset_copy + 5] as u64;
 					copy6 <<= 8;
 	
-					let copy7 = self[byte_offset_copy as usize + 6] as u64;
+					let copy7 = self[byte_offset_copy + 6] as u64;
 					// copy7 <<= 0;
 	
 					// Logical OR these seven to get the original seven bytes
@@ -1584,31 +2776,31 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy8 as u64);
 				} else if bit_offset_copy + length <= 64 {
-					let mut copy1 = self[byte_offset_copy as usize] as u64;
+					let mut copy1 = self[byte_offset_copy] as u64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 56;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u64;
+					let mut copy2 = self[byte_offset_copy + 1] as u64;
 					copy2 <<= 48;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u64;
+					let mut copy3 = self[byte_offset_copy + 2] as u64;
 					copy3 <<= 40;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u64;
+					let mut copy4 = self[byte_offset_copy + 3] as u64;
 					copy4 <<= 32;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as u64;
+					let mut copy5 = self[byte_offset_copy + 4] as u64;
 					copy5 <<= 24;
 	
-					let mut copy6 = self[byte_offset_copy as usize + 5] as u64;
+					let mut copy6 = self[byte_offset_copy + 5] as u64;
 					copy6 <<= 16;
 	
-					let mut copy7 = self[byte_offset_copy as usize + 6] as u64;
+					let mut copy7 = self[byte_offset_copy + 6] as u64;
 					copy7 <<= 8;
 	
-					let copy8 = self[byte_offset_copy as usize + 7] as u64;
+					let copy8 = self[byte_offset_copy + 7] as u64;
 					// copy8 <<= 0;
 	
 					// Logical OR these eight to get the original eight bytes
@@ -1623,34 +2815,34 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy9 as u64);
 				} else {
-					let mut copy1 = self[byte_offset_copy as usize] as u128;
+					let mut copy1 = self[byte_offset_copy] as u128;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 64;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u128;
+					let mut copy2 = self[byte_offset_copy + 1] as u128;
 					copy2 <<= 56;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u128;
+					let mut copy3 = self[byte_offset_copy + 2] as u128;
 					copy3 <<= 48;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u128;
+					let mut copy4 = self[byte_offset_copy + 3] as u128;
 					copy4 <<= 40;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as u128;
+					let mut copy5 = self[byte_offset_copy + 4] as u128;
 					copy5 <<= 32;
 	
-					let mut copy6 = self[byte_offset_copy as usize + 5] as u128;
+					let mut copy6 = self[byte_offset_copy + 5] as u128;
 					copy6 <<= 24;
 	
-					let mut copy7 = self[byte_offset_copy as usize + 6] as u128;
+					let mut copy7 = self[byte_offset_copy + 6] as u128;
 					copy7 <<= 16;
 	
-					let mut copy8 = self[byte_offset_copy as usize + 7] as u128;
+					let mut copy8 = self[byte_offset_copy + 7] as u128;
 					copy8 <<= 8;
 	
-					let copy9 = self[byte_offset_copy as usize + 8] as u128;
+					let copy9 = self[byte_offset_copy + 8] as u128;
 					// copy9 <<= 0;
 	
 					// Logical OR these two to get the original 3 bytes
@@ -1673,21 +2865,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 		}
 	}
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
 	fn get_i64(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i64> {
 		if length == 0 { return Err(s!(LEN_ZERO)); };
 	
 		if length <= 64 {
-			if self.len() as u32 * 8 >= byte_offset * 8 + bit_offset + length { // Ensure that we stay within the vector
+			if is_within(self.len(), byte_offset, bit_offset, length) { // Ensure that we stay within the vector
 				// if the bit offset is > 7 increase the byte offset as needed and reduce the bit offset until bit offset is <= 7
-				let mut byte_offset_copy = byte_offset;
+				let mut byte_offset_copy = byte_offset as usize;
 				let mut bit_offset_copy = bit_offset;
 	
-				byte_offset_copy += bit_offset_copy / 8;			// Integer division!
+				byte_offset_copy += bit_offset_copy as usize / 8;			// Integer division!
 				bit_offset_copy -= (bit_offset_copy / 8) * 8;
 	
 				if bit_offset_copy + length <= 8 {
 					// Don't touch the original
-					let copy1 = self[byte_offset_copy as usize] as i8;
+					let copy1 = self[byte_offset_copy] as i8;
 	
 					// Expand to i64
 					let mut copy2 = copy1 as i64;
@@ -1701,13 +2894,13 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy2);
 				} else if bit_offset_copy + length <= 16 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 8;
 	
-					let copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let copy2 = self[byte_offset_copy + 1] as i64;
 					// copy2 <<= 0;
 	
 					// Logical OR these two to get the original 2 bytes
@@ -1722,16 +2915,16 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy3);
 				} else if bit_offset_copy + length <= 24 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 16;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 8;
 	
-					let copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let copy3 = self[byte_offset_copy + 2] as i64;
 					// copy3 <<= 0;
 	
 					// Logical OR these three to get the original three bytes
@@ -1746,19 +2939,19 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy4 as i64);
 				} else if bit_offset_copy + length <= 32 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 24;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 16;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let mut copy3 = self[byte_offset_copy + 2] as i64;
 					copy3 <<= 8;
 	
-					let copy4 = self[byte_offset_copy as usize + 3] as i64;
+					let copy4 = self[byte_offset_copy + 3] as i64;
 					// copy4 <<= 0;
 	
 					// Logical OR these four to get the original four bytes
@@ -1773,22 +2966,22 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy5 as i64);
 				} else if bit_offset_copy + length <= 40 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 32;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 24;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let mut copy3 = self[byte_offset_copy + 2] as i64;
 					copy3 <<= 16;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as i64;
+					let mut copy4 = self[byte_offset_copy + 3] as i64;
 					copy4 <<= 8;
 	
-					let copy5 = self[byte_offset_copy as usize + 4] as i64;
+					let copy5 = self[byte_offset_copy + 4] as i64;
 					// copy5 <<= 0;
 	
 					// Logical OR these five to get the original five bytes
@@ -1803,25 +2996,25 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy6 as i64);
 				} else if bit_offset_copy + length <= 48 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 40;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 32;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let mut copy3 = self[byte_offset_copy + 2] as i64;
 					copy3 <<= 24;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as i64;
+					let mut copy4 = self[byte_offset_copy + 3] as i64;
 					copy4 <<= 16;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as i64;
+					let mut copy5 = self[byte_offset_copy + 4] as i64;
 					copy5 <<= 8;
 	
-					let copy6 = self[byte_offset_copy as usize + 5] as i64;
+					let copy6 = self[byte_offset_copy + 5] as i64;
 					// copy6 <<= 0;
 	
 					// Logical OR these six to get the original six bytes
@@ -1836,28 +3029,28 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy7 as i64);
 				} else if bit_offset_copy + length <= 56 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 48;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 40;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let mut copy3 = self[byte_offset_copy + 2] as i64;
 					copy3 <<= 32;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as i64;
+					let mut copy4 = self[byte_offset_copy + 3] as i64;
 					copy4 <<= 24;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as i64;
+					let mut copy5 = self[byte_offset_copy + 4] as i64;
 					copy5 <<= 16;
 	
-					let mut copy6 = self[byte_offset_copy as usize + 5] as i64;
+					let mut copy6 = self[byte_offset_copy + 5] as i64;
 					copy6 <<= 8;
 	
-					let copy7 = self[byte_offset_copy as usize + 6] as i64;
+					let copy7 = self[byte_offset_copy + 6] as i64;
 					// copy7 <<= 0;
 	
 					// Logical OR these seven to get the original seven bytes
@@ -1872,31 +3065,31 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy8 as i64);
 				} else if bit_offset_copy + length <= 64 {
-					let mut copy1 = self[byte_offset_copy as usize] as i64;
+					let mut copy1 = self[byte_offset_copy] as i64;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 56;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as i64;
+					let mut copy2 = self[byte_offset_copy + 1] as i64;
 					copy2 <<= 48;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as i64;
+					let mut copy3 = self[byte_offset_copy + 2] as i64;
 					copy3 <<= 40;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as i64;
+					let mut copy4 = self[byte_offset_copy + 3] as i64;
 					copy4 <<= 32;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as i64;
+					let mut copy5 = self[byte_offset_copy + 4] as i64;
 					copy5 <<= 24;
 	
-					let mut copy6 = self[byte_offset_copy as usize + 5] as i64;
+					let mut copy6 = self[byte_offset_copy + 5] as i64;
 					copy6 <<= 16;
 	
-					let mut copy7 = self[byte_offset_copy as usize + 6] as i64;
+					let mut copy7 = self[byte_offset_copy + 6] as i64;
 					copy7 <<= 8;
 	
-					let copy8 = self[byte_offset_copy as usize + 7] as i64;
+					let copy8 = self[byte_offset_copy + 7] as i64;
 					// copy8 <<= 0;
 	
 					// Logical OR these eight to get the original eight bytes
@@ -1911,34 +3104,34 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 	
 					return Ok(copy9 as i64);
 				} else {
-					let mut copy1 = self[byte_offset_copy as usize] as u128;
+					let mut copy1 = self[byte_offset_copy] as u128;
 	
 					// This is the most significant byte. So move it to the left
 					// NOTE: The byte order should be OK for both big and little endian
 					copy1 <<= 64;
 	
-					let mut copy2 = self[byte_offset_copy as usize + 1] as u128;
+					let mut copy2 = self[byte_offset_copy + 1] as u128;
 					copy2 <<= 56;
 	
-					let mut copy3 = self[byte_offset_copy as usize + 2] as u128;
+					let mut copy3 = self[byte_offset_copy + 2] as u128;
 					copy3 <<= 48;
 	
-					let mut copy4 = self[byte_offset_copy as usize + 3] as u128;
+					let mut copy4 = self[byte_offset_copy + 3] as u128;
 					copy4 <<= 40;
 	
-					let mut copy5 = self[byte_offset_copy as usize + 4] as u128;
+					let mut copy5 = self[byte_offset_copy + 4] as u128;
 					copy5 <<= 32;
 	
-					let mut copy6 = self[byte_offset_copy as usize + 5] as u128;
+					let mut copy6 = self[byte_offset_copy + 5] as u128;
 					copy6 <<= 24;
 	
-					let mut copy7 = self[byte_offset_copy as usize + 6] as u128;
+					let mut copy7 = self[byte_offset_copy + 6] as u128;
 					copy7 <<= 16;
 	
-					let mut copy8 = self[byte_offset_copy as usize + 7] as u128;
+					let mut copy8 = self[byte_offset_copy + 7] as u128;
 					copy8 <<= 8;
 	
-					let copy9 = self[byte_offset_copy as usize + 8] as u128;
+					let copy9 = self[byte_offset_copy + 8] as u128;
 					// copy9 <<= 0;
 	
 					// Logical OR these two to get the original 3 bytes
@@ -1960,8 +3153,220 @@ impl ExtractBitsFromVecU8 for Vec<u8> {
 			return Err(s!(OUT_OF_RANGE_MSG))
 		}
 	}
+
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), ret, err))]
+	fn get_u128(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u128> {
+		if length == 0 { return Err(s!(LEN_ZERO)); };
+		if length > 128 { return Err(s!(LEN_TOO_BIG_MSG) + "u128"); };
+		if !is_within(self.len(), byte_offset, bit_offset, length) { return Err(s!(OUT_OF_RANGE_MSG)); };
+
+		let start = byte_offset as usize * 8 + bit_offset as usize;
+		if length <= 64 {
+			return Ok(read_bits_from_slice(self, start, length) as u128);
+		}
+		let high = read_bits_from_slice(self, start, length - 64) as u128;
+		let low = read_bits_from_slice(self, start + (length - 64) as usize, 64) as u128;
+		Ok((high << 64) | low)
+	}
+
+	fn get_fields(&self, fields: &[(u32, u32, u32)]) -> Result<Vec<u64>> {
+		for &(byte_offset, bit_offset, length) in fields {
+			check_field(self.len(), byte_offset, bit_offset, length)?;
+		}
+		Ok(fields.iter()
+			.map(|&(byte_offset, bit_offset, length)| read_bits_from_slice(self, byte_offset as usize * 8 + bit_offset as usize, length))
+			.collect())
+	}
+
+	fn get_strided_bools(&self, byte_offset: u32, bit_offset: u32, stride: u32, count: usize) -> Result<Vec<bool>> {
+		if stride == 0 {
+			return Err(s!(STRIDE_ZERO_MSG));
+		}
+		let start = byte_offset as u64 * 8 + bit_offset as u64;
+		if let Some(last) = strided_last(start, stride, count) {
+			if last >= (self.len() as u64).saturating_mul(8) {
+				return Err(s!(OUT_OF_RANGE_MSG));
+			}
+		}
+		Ok((0..count)
+			.map(|i| read_bits_from_slice(self, start as usize + i * stride as usize, 1) == 1)
+			.collect())
+	}
+
+	fn get_bytes<const N: usize>(&self, byte_offset: u32, bit_offset: u32) -> Result<[u8; N]> {
+		if N == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		let start = byte_offset as u64 * 8 + bit_offset as u64;
+		if start + N as u64 * 8 > (self.len() as u64).saturating_mul(8) {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+
+		let start = start as usize;
+		let mut result = [0u8; N];
+		if start.is_multiple_of(8) {
+			result.copy_from_slice(&self[start / 8..start / 8 + N]);
+		} else {
+			for (i, byte) in result.iter_mut().enumerate() {
+				*byte = read_bits_from_slice(self, start + i * 8, 8) as u8;
+			}
+		}
+		Ok(result)
+	}
+
+	fn get_str(&self, byte_offset: u32, bit_offset: u32, n_bytes: u32) -> Result<std::borrow::Cow<'_, str>> {
+		let start = byte_offset as u64 * 8 + bit_offset as u64;
+		if start + n_bytes as u64 * 8 > (self.len() as u64).saturating_mul(8) {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+
+		let first = (start / 8) as usize;
+		let shift = (start % 8) as u32;
+		let n = n_bytes as usize;
+		if shift == 0 {
+			return std::str::from_utf8(&self[first..first + n])
+				.map(std::borrow::Cow::Borrowed)
+				.map_err(|e| s!(INVALID_UTF8_MSG) + &e.to_string());
+		}
+
+		// Since the string is not aligned, the byte after it exists
+		let bytes = self[first..=first + n]
+			.windows(2)
+			.map(|pair| pair[0] << shift | pair[1] >> (8 - shift))
+			.collect();
+		String::from_utf8(bytes)
+			.map(std::borrow::Cow::Owned)
+			.map_err(|e| s!(INVALID_UTF8_MSG) + &e.utf8_error().to_string())
+	}
+}
+
+// Vectors use the implementation for slices
+impl ExtractBitsFromVecU8 for Vec<u8> {
+	#[inline]
+	fn get_u8(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u8> {
+		self.as_slice().get_u8(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i8(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i8> {
+		self.as_slice().get_i8(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u16> {
+		self.as_slice().get_u16(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i16> {
+		self.as_slice().get_i16(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u32> {
+		self.as_slice().get_u32(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i32> {
+		self.as_slice().get_i32(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u64> {
+		self.as_slice().get_u64(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i64> {
+		self.as_slice().get_i64(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u128> {
+		self.as_slice().get_u128(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_fields(&self, fields: &[(u32, u32, u32)]) -> Result<Vec<u64>> {
+		self.as_slice().get_fields(fields)
+	}
+
+	#[inline]
+	fn get_strided_bools(&self, byte_offset: u32, bit_offset: u32, stride: u32, count: usize) -> Result<Vec<bool>> {
+		self.as_slice().get_strided_bools(byte_offset, bit_offset, stride, count)
+	}
+
+	#[inline]
+	fn get_bytes<const N: usize>(&self, byte_offset: u32, bit_offset: u32) -> Result<[u8; N]> {
+		self.as_slice().get_bytes(byte_offset, bit_offset)
+	}
+
+	#[inline]
+	fn get_str(&self, byte_offset: u32, bit_offset: u32, n_bytes: u32) -> Result<std::borrow::Cow<'_, str>> {
+		self.as_slice().get_str(byte_offset, bit_offset, n_bytes)
+	}
+}
+
+/// A type, which bit fields can be extracted as. It is implemented for all integer types up to
+/// 128 bits and selects the matching get_* function for the generic functions
+/// ExtractBitsFromIntegralTypes::get and ExtractBitsFromVecU8::get_at.
+///
+/// ```rust
+/// use bitlab::*;
+/// // A generic helper, which does not need to know the type of the field
+/// fn first_nibble<T: BitTarget>(v: &[u8]) -> T {
+///     v.get_at(0, 0, 4).unwrap()
+/// }
+/// assert_eq!(first_nibble::<u64>(&[0xA5]), 10);
+/// assert_eq!(first_nibble::<i32>(&[0xA5]), -6);
+/// assert_eq!(0xA5u8.get::<i16>(4, 4).unwrap(), 5);
+/// ```
+pub trait BitTarget: std::marker::Sized {
+	/// Extracts length bits starting at bit_offset from an integer
+	fn extract_from<S: ExtractBitsFromIntegralTypes>(source: S, bit_offset: u32, length: u32) -> Result<Self>;
+
+	/// Extracts length bits starting at byte_offset and bit_offset from a Vec<u8> or a byte slice
+	fn extract_from_bytes<S: ExtractBitsFromVecU8 + ?Sized>(source: &S, byte_offset: u32, bit_offset: u32, length: u32) -> Result<Self>;
+}
+
+// The first parameter is the target type and the second the name of the matching get function
+macro_rules! def_bit_target {
+	( $( $t:ty, $get:ident );* ) => { $(
+		impl BitTarget for $t {
+			#[inline]
+			fn extract_from<S: ExtractBitsFromIntegralTypes>(source: S, bit_offset: u32, length: u32) -> Result<Self> {
+				source.$get(bit_offset, length)
+			}
+
+			#[inline]
+			fn extract_from_bytes<S: ExtractBitsFromVecU8 + ?Sized>(source: &S, byte_offset: u32, bit_offset: u32, length: u32) -> Result<Self> {
+				source.$get(byte_offset, bit_offset, length)
+			}
+		}
+	)* }
 }
 
+def_bit_target!(u8, get_u8; i8, get_i8; u16, get_u16; i16, get_i16; u32, get_u32; i32, get_i32;
+	u64, get_u64; i64, get_i64; u128, get_u128; i128, get_i128);
+
+/// A value, which can be inserted into bits. It is implemented for all integer types up to
+/// 128 bits and stands for the bounds of the generic setters, e.g. ChainedBits::set_next.
+pub trait BitValue: std::marker::Sized + SignedInfo + SingleBits + Copy + std::string::ToString
+	+ num::cast::AsPrimitive<u8> + num::cast::AsPrimitive<i8>
+	+ num::cast::AsPrimitive<u16> + num::cast::AsPrimitive<i16>
+	+ num::cast::AsPrimitive<u32> + num::cast::AsPrimitive<i32>
+	+ num::cast::AsPrimitive<u64> + num::cast::AsPrimitive<i64>
+	+ num::cast::AsPrimitive<u128> + num::cast::AsPrimitive<i128> {}
+
+impl<T> BitValue for T
+	where T: std::marker::Sized + SignedInfo + SingleBits + Copy + std::string::ToString,
+	T: num::cast::AsPrimitive<u8> + num::cast::AsPrimitive<i8>,
+	T: num::cast::AsPrimitive<u16> + num::cast::AsPrimitive<i16>,
+	T: num::cast::AsPrimitive<u32> + num::cast::AsPrimitive<i32>,
+	T: num::cast::AsPrimitive<u64> + num::cast::AsPrimitive<i64>,
+	T: num::cast::AsPrimitive<u128> + num::cast::AsPrimitive<i128> {}
+
 /// Defines a set of functions to get, set and clear single bits
 pub trait SingleBits {
 	/// Sets a single bit and returns a Result object, which contains the modified variable
@@ -1971,22 +3376,172 @@ pub trait SingleBits {
 	/// - **bit_offset** (u32) the offset of the bit to be set. Zero is the **MOST** significant bit.
 	fn set_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized;
 
-	/// Tests a single bit and returns true or false in a Result object
-	///
-	/// On error, the Result object contains an error message.
-	/// This may happen if the bit_offset is larger than the data source (bit_offset > variable size)
+	/// Tests a single bit and returns true or false in a Result object
+	///
+	/// On error, the Result object contains an error message.
+	/// This may happen if the bit_offset is larger than the data source (bit_offset > variable size)
+	///
+	/// Parameters:
+	///
+	/// - **bit_offset** (u32) the offset of the bit to be set. Zero is the **MOST** significant bit.
+	fn get_bit(self, bit_offset: u32) -> Result<bool>;
+
+	/// Clears a single bit and then returns a Result Object, which contains the modified variable
+	///
+	/// Parameters:
+	///
+	/// - **bit_offset** (u32) the offset of the bit to be set. Zero is the **MOST** significant bit.
+	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized;
+
+	/// Inverts a single bit and then returns a Result Object, which contains the modified variable
+	///
+	/// Parameters:
+	///
+	/// - **bit_offset** (u32) the offset of the bit to be inverted. Zero is the **MOST** significant bit.
+	fn toggle_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized + Copy {
+		if self.get_bit(bit_offset)? {
+			self.clear_bit(bit_offset)
+		} else {
+			self.set_bit(bit_offset)
+		}
+	}
+
+	/// Sets the bit if value is true or clears it otherwise and then returns a Result Object,
+	/// which contains the modified variable
 	///
 	/// Parameters:
 	///
-	/// - **bit_offset** (u32) the offset of the bit to be set. Zero is the **MOST** significant bit.
-	fn get_bit(self, bit_offset: u32) -> Result<bool>;
+	/// - **bit_offset** (u32) the offset of the bit to be assigned. Zero is the **MOST** significant bit.
+	/// - **value** (bool) the new state of the bit
+	fn assign_bit(self, bit_offset: u32, value: bool) -> Result<Self> where Self: std::marker::Sized {
+		if value {
+			self.set_bit(bit_offset)
+		} else {
+			self.clear_bit(bit_offset)
+		}
+	}
 
-	/// Clears a single bit and then returns a Result Object, which contains the modified variable
+	/// Reverses the order of length bits starting at bit_offset and returns a Result object,
+	/// which contains the modified variable
 	///
 	/// Parameters:
 	///
-	/// - **bit_offset** (u32) the offset of the bit to be set. Zero is the **MOST** significant bit.
-	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized;
+	/// - **bit_offset** (u32) the offset of the first bit of the range. Zero is the **MOST** significant bit.
+	/// - **length** (u32) the number of bits to be reversed
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let a: u8 = 0b1110_0100;
+	/// assert_eq!(a.reverse_bits_in_range(2, 5).unwrap(), 0b1101_0010);
+	/// ```
+	fn reverse_bits_in_range(self, bit_offset: u32, length: u32) -> Result<Self> where Self: std::marker::Sized + Copy {
+		if length == 0 {
+			return Ok(self);
+		}
+		// Check the range, before any bit is accessed
+		match bit_offset.checked_add(length - 1) {
+			Some(last) => { self.get_bit(last)?; },
+			None => return Err(s!(OUT_OF_RANGE_MSG)),
+		}
+
+		let mut result = self;
+		for i in 0..length {
+			let bit = self.get_bit(bit_offset + length - 1 - i)?;
+			result = result.assign_bit(bit_offset + i, bit)?;
+		}
+		Ok(result)
+	}
+
+	/// Rotates length bits starting at bit_offset by amount bits towards the most significant bit
+	/// and returns a Result object, which contains the modified variable. The bits outside of the
+	/// range are not modified.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let a: u16 = 0b0000_1100_0100_0000;
+	/// assert_eq!(a.rotate_bits_left(4, 8, 3).unwrap(), 0b0000_0010_0110_0000);
+	/// assert_eq!(a.rotate_bits_right(4, 8, 5).unwrap(), 0b0000_0010_0110_0000);
+	/// ```
+	fn rotate_bits_left(self, bit_offset: u32, length: u32, amount: u32) -> Result<Self> where Self: std::marker::Sized + Copy {
+		if length == 0 {
+			return Ok(self);
+		}
+		match bit_offset.checked_add(length - 1) {
+			Some(last) => { self.get_bit(last)?; },
+			None => return Err(s!(OUT_OF_RANGE_MSG)),
+		}
+
+		let amount = amount % length;
+		let mut result = self;
+		for i in 0..length {
+			let bit = self.get_bit(bit_offset + (i + amount) % length)?;
+			result = result.assign_bit(bit_offset + i, bit)?;
+		}
+		Ok(result)
+	}
+
+	/// Rotates length bits starting at bit_offset by amount bits towards the least significant bit.
+	/// See rotate_bits_left.
+	fn rotate_bits_right(self, bit_offset: u32, length: u32, amount: u32) -> Result<Self> where Self: std::marker::Sized + Copy {
+		if length == 0 {
+			return Ok(self);
+		}
+		self.rotate_bits_left(bit_offset, length, length - amount % length)
+	}
+}
+
+// Reverses a range of bits with the operations on the whole word. The parameter is the unsigned
+// type of the same size.
+macro_rules! def_reverse_bits_fn {
+	( $u:ty ) => {
+		fn reverse_bits_in_range(self, bit_offset: u32, length: u32) -> Result<Self> where Self: std::marker::Sized + Copy {
+			if length == 0 {
+				return Ok(self);
+			}
+			let bits = <$u>::BITS;
+			match bit_offset.checked_add(length) {
+				Some(end) if end <= bits => {},
+				_ => return Err(s!(OUT_OF_RANGE_MSG)),
+			}
+			let value = self as $u;
+			let mask = (<$u>::MAX >> (bits - length)) << (bits - bit_offset - length);
+			// Reversing the whole word moves the range to the mirrored offset, from where it is shifted back
+			let mirrored = bits - bit_offset - length;
+			let reversed = if mirrored > bit_offset {
+				value.reverse_bits() << (mirrored - bit_offset)
+			} else {
+				value.reverse_bits() >> (bit_offset - mirrored)
+			};
+			Ok(((value & !mask) | (reversed & mask)) as Self)
+		}
+	}
+}
+
+// Rotates a range of bits with the operations on the whole word. The parameter is the unsigned
+// type of the same size.
+macro_rules! def_rotate_bits_fn {
+	( $u:ty ) => {
+		fn rotate_bits_left(self, bit_offset: u32, length: u32, amount: u32) -> Result<Self> where Self: std::marker::Sized + Copy {
+			if length == 0 {
+				return Ok(self);
+			}
+			let bits = <$u>::BITS;
+			match bit_offset.checked_add(length) {
+				Some(end) if end <= bits => {},
+				_ => return Err(s!(OUT_OF_RANGE_MSG)),
+			}
+			let amount = amount % length;
+			if amount == 0 {
+				return Ok(self);
+			}
+			let value = self as $u;
+			let shift = bits - bit_offset - length;
+			let field_mask = <$u>::MAX >> (bits - length);
+			let field = (value >> shift) & field_mask;
+			let rotated = ((field << amount) | (field >> (length - amount))) & field_mask;
+			Ok(((value & !(field_mask << shift)) | (rotated << shift)) as Self)
+		}
+	}
 }
 
 impl SingleBits for u8 {
@@ -2035,6 +3590,9 @@ impl SingleBits for u8 {
 
 		Ok(copy)
 	}
+
+	def_reverse_bits_fn!(u8);
+	def_rotate_bits_fn!(u8);
 }
 
 impl SingleBits for i8 {
@@ -2083,6 +3641,9 @@ impl SingleBits for i8 {
 
 		Ok(copy as i8)
 	}
+
+	def_reverse_bits_fn!(u8);
+	def_rotate_bits_fn!(u8);
 }
 
 impl SingleBits for u16 {
@@ -2131,6 +3692,9 @@ impl SingleBits for u16 {
 
 		Ok(copy)
 	}
+
+	def_reverse_bits_fn!(u16);
+	def_rotate_bits_fn!(u16);
 }
 
 impl SingleBits for i16 {
@@ -2179,6 +3743,9 @@ impl SingleBits for i16 {
 
 		Ok(copy as i16)
 	}
+
+	def_reverse_bits_fn!(u16);
+	def_rotate_bits_fn!(u16);
 }
 
 impl SingleBits for u32 {
@@ -2227,6 +3794,9 @@ impl SingleBits for u32 {
 
 		Ok(copy)
 	}
+
+	def_reverse_bits_fn!(u32);
+	def_rotate_bits_fn!(u32);
 }
 
 impl SingleBits for i32 {
@@ -2275,6 +3845,9 @@ impl SingleBits for i32 {
 
 		Ok(copy as i32)
 	}
+
+	def_reverse_bits_fn!(u32);
+	def_rotate_bits_fn!(u32);
 }
 
 impl SingleBits for u64 {
@@ -2323,6 +3896,9 @@ impl SingleBits for u64 {
 
 		Ok(copy)
 	}
+
+	def_reverse_bits_fn!(u64);
+	def_rotate_bits_fn!(u64);
 }
 
 impl SingleBits for i64 {
@@ -2361,7 +3937,7 @@ impl SingleBits for i64 {
 	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
 		check_max_bit_offset!(bit_offset);
 
-		let a : u64 = 0b0111_1111_1111_1111_1111_1111_1111_1111; // Only the most significant bit is clear.
+		let a : u64 = 0b0111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111; // Only the most significant bit is clear.
 
 		// Shift it to the right according to the desired offset
 		let a = a.rotate_right(bit_offset);
@@ -2371,6 +3947,100 @@ impl SingleBits for i64 {
 
 		Ok(copy as i64)
 	}
+
+	def_reverse_bits_fn!(u64);
+	def_rotate_bits_fn!(u64);
+}
+
+impl SingleBits for u128 {
+	fn set_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		check_max_bit_offset!(bit_offset);
+
+		// Only the most significant bit is set. Shift it to the right according to the desired offset
+		let a : u128 = (1 << 127) >> bit_offset;
+
+		Ok(self | a)
+	}
+
+	fn get_bit(self, bit_offset: u32) -> Result<bool> {
+		check_max_bit_offset!(bit_offset);
+
+		let a : u128 = (1 << 127) >> bit_offset;
+
+		Ok(self & a > 0)
+	}
+
+	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		check_max_bit_offset!(bit_offset);
+
+		let a : u128 = (1 << 127) >> bit_offset;
+
+		Ok(self & !a)
+	}
+
+	def_reverse_bits_fn!(u128);
+	def_rotate_bits_fn!(u128);
+}
+
+impl SingleBits for i128 {
+	#[inline]
+	fn set_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		Ok((self as u128).set_bit(bit_offset)? as i128)
+	}
+
+	#[inline]
+	fn get_bit(self, bit_offset: u32) -> Result<bool> {
+		(self as u128).get_bit(bit_offset)
+	}
+
+	#[inline]
+	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		Ok((self as u128).clear_bit(bit_offset)? as i128)
+	}
+
+	def_reverse_bits_fn!(u128);
+	def_rotate_bits_fn!(u128);
+}
+
+// usize and isize use the implementation for the unsigned type of the same size
+impl SingleBits for usize {
+	#[inline]
+	fn set_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		Ok((self as PointerSized).set_bit(bit_offset)? as usize)
+	}
+
+	#[inline]
+	fn get_bit(self, bit_offset: u32) -> Result<bool> {
+		(self as PointerSized).get_bit(bit_offset)
+	}
+
+	#[inline]
+	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		Ok((self as PointerSized).clear_bit(bit_offset)? as usize)
+	}
+
+	def_reverse_bits_fn!(usize);
+	def_rotate_bits_fn!(usize);
+}
+
+impl SingleBits for isize {
+	#[inline]
+	fn set_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		Ok((self as PointerSized).set_bit(bit_offset)? as isize)
+	}
+
+	#[inline]
+	fn get_bit(self, bit_offset: u32) -> Result<bool> {
+		(self as PointerSized).get_bit(bit_offset)
+	}
+
+	#[inline]
+	fn clear_bit(self, bit_offset: u32) -> Result<Self> where Self: std::marker::Sized {
+		Ok((self as PointerSized).clear_bit(bit_offset)? as isize)
+	}
+
+	def_reverse_bits_fn!(usize);
+	def_rotate_bits_fn!(usize);
 }
 
 /// Provides a single function to insert a sized integer into an other sized integer type
@@ -2381,24 +4051,47 @@ pub trait InsertIntoSizedIntegerTypes {
 	/// - **bit offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit  
 	/// - **length** (u32) the number of bits to be extracted (at the least significant side).
 	/// - **value** (Any sized integer type) the value to be inserted.
+	///
+	/// Returns an error if the value requires more than length bits. Use set_truncate to mask it instead.
 	fn set<T>(self, bit_offset: u32, length: u32, value: T) -> Result<Self>
 		where Self: std::marker::Sized, T: std::marker::Sized, T: SignedInfo,
 		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
 		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
 		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
 		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+		T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
 		T : std::string::ToString;
+
+	/// Same as set, but the value is masked to its length least significant bits instead of being
+	/// rejected if it requires more bits. Negative values are truncated in two's complement.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// assert!(0u16.set(4, 8, 0x1FFu16).is_err());
+	/// assert_eq!(0u16.set_truncate(4, 8, 0x1FFu16).unwrap(), 0x0FF0);
+	/// assert_eq!(0u8.set_truncate(0, 4, -1i32).unwrap(), 0xF0);
+	/// ```
+	fn set_truncate<T>(self, bit_offset: u32, length: u32, value: T) -> Result<Self>
+		where Self: std::marker::Sized, T: num::cast::AsPrimitive<u128> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		let bits: u128 = value.as_();
+		self.set(bit_offset, length, bits & (u128::MAX >> 128u32.saturating_sub(length)))
+	}
 }
 
 // The first parameter ($t) is the variable type to be inserted ($t)
 macro_rules! def_set_fn {
 	($t:ty) => (
+		#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(value), fields(value = %value.to_string(), ty = std::any::type_name::<T>()), ret, err))]
 		fn set<T>(self, bit_offset: u32, length: u32, value: T) -> Result<Self>
 		where Self: std::marker::Sized, T: std::marker::Sized, T: SignedInfo,
 		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
 		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
 		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
 		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+		T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
 		T : std::string::ToString {
 			// Range checks
 			if length > std::mem::size_of::<Self>() as u32 * 8 {
@@ -2407,133 +4100,731 @@ macro_rules! def_set_fn {
 
 			check_range!(bit_offset, length);
 
-			if value.is_signed() {
-				let n = n_required_bits_for_a_signed_int(value.as_()); // value.as_() is type casting to u32 in this case
-				if n > length {
-					return Err(format!("Failed to insert {} as a {} bit signed integer variable, since it requires at least {} bits.",
-						&value.to_string(), &length.to_string(), &n.to_string()))
-				}
-			} else {
-				let n = n_required_bits_for_an_unsigned_int(value.as_()); // value.as_() is type casting to u32 in this case
-				if n > length {
-					return Err(format!("Failed to insert {} as a {} bit unsigned integer variable, since it requires at least {} bits.",
-						&value.to_string(), &length.to_string(), &n.to_string()))
-				}
+			check_value_fits(value, length)?;
+
+			// Clear the target range with a mask and OR in the shifted value.
+			// The calculation is done in 128 bits and truncated to the size of Self.
+			let shift = std::mem::size_of::<Self>() as u32 * 8 - (bit_offset + length);
+			let mask = (u128::MAX >> (128 - length)) << shift;
+			let bits: u128 = value.as_();
+			Ok((((self as u128) & !mask) | ((bits << shift) & mask)) as Self)
+		}
+	)
+}
+
+impl InsertIntoSizedIntegerTypes for u8  { def_set_fn!(u8); }
+impl InsertIntoSizedIntegerTypes for i8  { def_set_fn!(i8); }
+impl InsertIntoSizedIntegerTypes for u16 { def_set_fn!(u8); }
+impl InsertIntoSizedIntegerTypes for i16 { def_set_fn!(i8); }
+impl InsertIntoSizedIntegerTypes for u32 { def_set_fn!(u8); }
+impl InsertIntoSizedIntegerTypes for i32 { def_set_fn!(i8); }
+impl InsertIntoSizedIntegerTypes for u64 { def_set_fn!(u8); }
+impl InsertIntoSizedIntegerTypes for i64 { def_set_fn!(i8); }
+impl InsertIntoSizedIntegerTypes for u128 { def_set_fn!(u8); }
+impl InsertIntoSizedIntegerTypes for i128 { def_set_fn!(i8); }
+impl InsertIntoSizedIntegerTypes for usize { def_set_fn!(u8); }
+impl InsertIntoSizedIntegerTypes for isize { def_set_fn!(i8); }
+
+/// Accesses the components of an IEEE 754 floating point number: the sign bit, the biased
+/// exponent and the mantissa (the fraction without the hidden bit)
+///
+/// ```rust
+/// use bitlab::*;
+/// let x = -1.5f32;
+/// assert!(x.sign());
+/// assert_eq!(x.exponent_bits(), 127);
+/// assert_eq!(x.mantissa_bits(), 1 << 22);
+/// assert_eq!(x.set_exponent_bits(128).unwrap(), -3.0);
+/// ```
+pub trait FloatComponents {
+	/// Returns true if the sign bit is set
+	fn sign(self) -> bool;
+
+	/// Returns the biased exponent (8 bits for f32, 11 bits for f64)
+	fn exponent_bits(self) -> u32;
+
+	/// Returns the mantissa (23 bits for f32, 52 bits for f64)
+	fn mantissa_bits(self) -> u64;
+
+	/// Returns the number with the sign bit set if negative is true or cleared otherwise
+	fn set_sign(self, negative: bool) -> Self;
+
+	/// Returns the number with a new biased exponent. Returns an error if it does not fit.
+	fn set_exponent_bits(self, exponent: u32) -> Result<Self> where Self: std::marker::Sized;
+
+	/// Returns the number with a new mantissa. Returns an error if it does not fit.
+	fn set_mantissa_bits(self, mantissa: u64) -> Result<Self> where Self: std::marker::Sized;
+}
+
+// The parameters are the float type, the name of the getter for its bits and the number of
+// exponent and mantissa bits. The components are read and written with the integer traits.
+macro_rules! def_float_components {
+	( $t:ty, $get:ident, $exp_bits:expr, $mant_bits:expr ) => {
+		impl FloatComponents for $t {
+			fn sign(self) -> bool {
+				self.is_sign_negative()
+			}
+
+			fn exponent_bits(self) -> u32 {
+				// Cannot fail, since the range is within the type
+				self.to_bits().$get(1, $exp_bits).unwrap_or(0) as u32
+			}
+
+			fn mantissa_bits(self) -> u64 {
+				self.to_bits().$get(1 + $exp_bits, $mant_bits).unwrap_or(0) as u64
+			}
+
+			fn set_sign(self, negative: bool) -> Self {
+				<$t>::from_bits(self.to_bits().assign_bit(0, negative).unwrap_or(self.to_bits()))
+			}
+
+			fn set_exponent_bits(self, exponent: u32) -> Result<Self> {
+				Ok(<$t>::from_bits(self.to_bits().set(1, $exp_bits, exponent)?))
+			}
+
+			fn set_mantissa_bits(self, mantissa: u64) -> Result<Self> {
+				Ok(<$t>::from_bits(self.to_bits().set(1 + $exp_bits, $mant_bits, mantissa)?))
 			}
+		}
+	}
+}
+
+def_float_components!(f32, get_u32, 8, 23);
+def_float_components!(f64, get_u64, 11, 52);
+
+/// Defines a functions, which inserts a range of bits into a Vec<u8> or a mutable byte slice
+/// **Important:** the contents of the vector are assumed to be **big endian** (network order)
+///
+/// Since the trait is implemented for [u8], bit fields can be patched in place, e.g. in a stack buffer:
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut frame = [0u8; 4];
+/// frame.set(0, 4, 8, 0xABu8).unwrap();
+/// frame[2..].set(0, 0, 16, -2i16).unwrap();
+/// assert_eq!(frame, [0x0A, 0xB0, 0xFF, 0xFE]);
+/// ```
+pub trait InsertBitsIntoVecU8 {
+	/// inserts a range of bits into a Vec<u8>
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the number of bits to skip. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be inserted.
+	/// - **value** (u32) the value to be inserted.
+	///
+	/// Returns an error if the value requires more than length bits. Use set_truncate to mask it instead.
+	fn set<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: std::marker::Sized, T: SignedInfo,
+		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
+		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
+		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
+		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+		T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
+		T : std::string::ToString, T: SingleBits + Copy;
+
+	/// Inserts value as a packed BCD number with the given number of decimal digits (at most 19).
+	/// See ExtractBitsFromVecU8::get_bcd.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0x00, 0x00, 0x00 };
+	/// v.set_bcd(0, 3, 4, 2359).unwrap();
+	/// assert_eq!(v, vec!{ 0b0000_0100, 0b0110_1011, 0b0010_0000 });
+	/// ```
+	fn set_bcd(&mut self, byte_offset: u32, bit_offset: u32, digits: u32, value: u64) -> Result<()> {
+		if digits > 19 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+
+		let mut packed: u128 = 0;
+		let mut rest = value;
+		for i in 0..digits {
+			packed |= ((rest % 10) as u128) << (i * 4);
+			rest /= 10;
+		}
+		if rest != 0 {
+			return Err(format!("Failed to insert {} as a BCD number with {} digits.", value, digits));
+		}
+		self.set(byte_offset, bit_offset, digits * 4, packed)
+	}
+
+	/// Inserts value as a fixed point number in the Q format, rounded to the nearest multiple of
+	/// 2^-frac_bits. Returns an error if the rounded value does not fit.
+	/// See ExtractBitsFromVecU8::get_fixed_point.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0x30, 0x00 };
+	/// v.set_fixed_point(0, 4, 8, 4, true, -10.25).unwrap();
+	/// assert_eq!(v, vec!{ 0x3F, 0x5C });
+	/// ```
+	fn set_fixed_point(&mut self, byte_offset: u32, bit_offset: u32, int_bits: u32, frac_bits: u32, signed: bool, value: f64) -> Result<()> {
+		let length = int_bits.checked_add(frac_bits).ok_or_else(|| s!(LEN_TOO_BIG_MSG) + "u64")?;
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+
+		let raw = (value * 2f64.powi(frac_bits as i32)).round();
+		let (min, max) = if signed {
+			(-(2f64.powi(length as i32 - 1)), 2f64.powi(length as i32 - 1))
+		} else {
+			(0.0, 2f64.powi(length as i32))
+		};
+		// The upper bound is exclusive. NaN fails both comparisons.
+		if !(raw >= min && raw < max) {
+			return Err(format!("Failed to insert {} as a fixed point number with {} integer and {} fractional bits.",
+				value, int_bits, frac_bits));
+		}
+
+		if signed {
+			self.set(byte_offset, bit_offset, length, raw as i64)
+		} else {
+			self.set(byte_offset, bit_offset, length, raw as u64)
+		}
+	}
+
+	/// Inserts several values into fields of at most 64 bits each, which are given as tuples of
+	/// (byte_offset, bit_offset, length, value). The lengths and values of all fields are checked before
+	/// any of them is inserted. Vectors and byte slices check the ranges in advance as well, so that nothing
+	/// is modified on error. The default implementation can only check the range of a field when it is
+	/// inserted, so the fields before a field out of range have already been inserted on error.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0; 4 };
+	/// v.set_fields(&[(0, 0, 4, 4), (0, 4, 4, 5), (2, 0, 16, 1500)]).unwrap();
+	/// assert_eq!(v, vec!{ 0x45, 0x00, 0x05, 0xDC });
+	/// ```
+	fn set_fields(&mut self, fields: &[(u32, u32, u32, u64)]) -> Result<()> {
+		for &(_, _, length, value) in fields {
+			if length == 0 { return Err(s!(LEN_ZERO)); }
+			if length > 64 { return Err(s!(LEN_TOO_BIG_MSG) + "u64"); }
+			check_value_fits(value, length)?;
+		}
+		for &(byte_offset, bit_offset, length, value) in fields {
+			self.set(byte_offset, bit_offset, length, value)?;
+		}
+		Ok(())
+	}
+
+	/// Inserts all bytes of data at an arbitrary bit offset. Nothing is modified if the
+	/// bytes exceed the vector.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // A 4 bit header followed by the payload
+	/// let mut frame: Vec<u8> = vec!{ 0xA0, 0x00, 0x00 };
+	/// frame.set_bytes(0, 4, &[0x12, 0x34]).unwrap();
+	/// assert_eq!(frame, vec!{ 0xA1, 0x23, 0x40 });
+	/// ```
+	fn set_bytes(&mut self, byte_offset: u32, bit_offset: u32, data: &[u8]) -> Result<()> {
+		let last = byte_offset as u64 * 8 + bit_offset as u64 + data.len() as u64 * 8;
+		if !data.is_empty() && last / 8 > u32::MAX as u64 {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		// Check the last byte before anything is modified
+		if let Some(&byte) = data.last() {
+			self.set((last / 8) as u32 - 1, (last % 8) as u32, 8, byte)?;
+		}
+		for (i, &byte) in data.iter().enumerate() {
+			self.set(byte_offset + i as u32, bit_offset, 8, byte)?;
+		}
+		Ok(())
+	}
+
+	/// Inserts a UUID of 128 bits at an arbitrary bit offset. See ExtractBitsFromVecU8::get_uuid.
+	#[cfg(feature = "uuid")]
+	fn set_uuid(&mut self, byte_offset: u32, bit_offset: u32, value: uuid::Uuid) -> Result<()> {
+		self.set(byte_offset, bit_offset, 128, value.as_u128())
+	}
+
+	/// Same as set, but the value is masked to its length least significant bits instead of being
+	/// rejected if it requires more bits. Negative values are truncated in two's complement, so
+	/// that e.g. -1 writes a field of all ones. Unlike set, the length may exceed the size of T.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0x00, 0x00 };
+	/// assert!(v.set(0, 4, 8, 0x1FFu16).is_err());
+	/// v.set_truncate(0, 4, 8, 0x1FFu16).unwrap();
+	/// assert_eq!(v, vec!{ 0x0F, 0xF0 });
+	/// v.set_truncate(0, 0, 16, -1i8).unwrap();
+	/// assert_eq!(v, vec!{ 0xFF, 0xFF });
+	/// ```
+	fn set_truncate<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: num::cast::AsPrimitive<u128> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		let bits: u128 = value.as_();
+		self.set(byte_offset, bit_offset, length, bits & (u128::MAX >> 128u32.saturating_sub(length)))
+	}
+
+	/// Inserts a signed value into a field of length bits (at most 64), which is encoded as selected by
+	/// the policy, e.g. in sign-magnitude. Returns an error if the value can not be represented in the field.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0x00 };
+	/// v.set_signed(0, 0, 4, -3, SignednessPolicy::SignMagnitude).unwrap();
+	/// v.set_signed(0, 4, 4, -3, SignednessPolicy::TwosComplement).unwrap();
+	/// assert_eq!(v, vec!{ 0b1011_1101 });
+	/// ```
+	fn set_signed(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: i64, policy: SignednessPolicy) -> Result<()> {
+		self.set(byte_offset, bit_offset, length, policy.encode(value, length)?)
+	}
+}
+
+impl InsertBitsIntoVecU8 for [u8] {
+	#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self, value), fields(value = %value.to_string(), ty = std::any::type_name::<T>()), ret, err))]
+	fn set<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: std::marker::Sized, T: SignedInfo,
+		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
+		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
+		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
+		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+		T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
+		T : std::string::ToString, T: SingleBits + Copy {
+
+		// Range checks
+		if length == 0 { return Err(s!(LEN_ZERO)); };
+
+		if length > std::mem::size_of::<T>() as u32 * 8 {
+			return Err(s!(LEN_TOO_BIG_MSG) + std::any::type_name::<T>());
+		}
+
+		if !is_within(self.len(), byte_offset, bit_offset, length) {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+
+		check_value_fits(value, length)?;
+
+		// Write whole bytes through masks. Fields longer than 64 bits are written in two parts.
+		let position = byte_offset as usize * 8 + bit_offset as usize;
+		let bits: u128 = value.as_();
+		if length > 64 {
+			write_bits_to_slice(self, position, length - 64, (bits >> 64) as u64);
+			write_bits_to_slice(self, position + (length - 64) as usize, 64, bits as u64);
+		} else {
+			write_bits_to_slice(self, position, length, bits as u64);
+		}
+
+		Ok(())
+	}
+
+	fn set_fields(&mut self, fields: &[(u32, u32, u32, u64)]) -> Result<()> {
+		for &(byte_offset, bit_offset, length, value) in fields {
+			check_field(self.len(), byte_offset, bit_offset, length)?;
+			check_value_fits(value, length)?;
+		}
+		for &(byte_offset, bit_offset, length, value) in fields {
+			write_bits_to_slice(self, byte_offset as usize * 8 + bit_offset as usize, length, value);
+		}
+		Ok(())
+	}
+
+	fn set_bytes(&mut self, byte_offset: u32, bit_offset: u32, data: &[u8]) -> Result<()> {
+		bulk::copy_bits(data, 0, self, byte_offset as usize * 8 + bit_offset as usize, data.len() * 8)
+	}
+}
+
+// Vectors use the implementation for slices
+impl InsertBitsIntoVecU8 for Vec<u8> {
+	#[inline]
+	fn set<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: std::marker::Sized, T: SignedInfo,
+		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
+		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
+		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
+		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
+		T: num::cast::AsPrimitive<u128>, T: num::cast::AsPrimitive<i128>,
+		T : std::string::ToString, T: SingleBits + Copy {
+		self.as_mut_slice().set(byte_offset, bit_offset, length, value)
+	}
+
+	#[inline]
+	fn set_fields(&mut self, fields: &[(u32, u32, u32, u64)]) -> Result<()> {
+		self.as_mut_slice().set_fields(fields)
+	}
 
-			let mut result = self;
+	#[inline]
+	fn set_bytes(&mut self, byte_offset: u32, bit_offset: u32, data: &[u8]) -> Result<()> {
+		self.as_mut_slice().set_bytes(byte_offset, bit_offset, data)
+	}
+}
 
-			// makes sure that value_copy has the same size by type casting to Self
-			let mut value_copy : Self = value.as_();
-			let shift = std::mem::size_of_val(&value_copy) as u8 * 8 - (bit_offset + length) as u8;
-			value_copy <<= shift;
+// Returns the index of the byte and the mask of a single bit in a vector of len bytes
+fn locate_bit(len: usize, byte_offset: u32, bit_offset: u32) -> Result<(usize, u8)> {
+	if !is_within(len, byte_offset, bit_offset, 1) {
+		return Err(s!(OUT_OF_RANGE_MSG));
+	}
+	Ok((byte_offset as usize + bit_offset as usize / 8, 0x80 >> (bit_offset % 8)))
+}
 
-			for i in bit_offset .. bit_offset + length {
-				if value_copy.get_bit(i as u32)? {
-					result = result.set_bit(i as u32)?;
-				} else {
-					result = result.clear_bit(i as u32)?;
-				}
-			}
-			Ok(result)
-		}
-	)
+// Returns the absolute positions of the first bit and the bit after a range of length bits.
+// Returns an error if the range cannot be addressed by u32 byte offsets.
+fn bit_range(byte_offset: u32, bit_offset: u32, length: u32) -> Result<(u64, u64)> {
+	let start = byte_offset as u64 * 8 + bit_offset as u64;
+	let end = start + length as u64;
+	if end > (u32::MAX as u64 + 1) * 8 {
+		return Err(s!(OUT_OF_RANGE_MSG));
+	}
+	Ok((start, end))
 }
 
-impl InsertIntoSizedIntegerTypes for u8  { def_set_fn!(u8); }
-impl InsertIntoSizedIntegerTypes for i8  { def_set_fn!(i8); }
-impl InsertIntoSizedIntegerTypes for u16 { def_set_fn!(u8); }
-impl InsertIntoSizedIntegerTypes for i16 { def_set_fn!(i8); }
-impl InsertIntoSizedIntegerTypes for u32 { def_set_fn!(u8); }
-impl InsertIntoSizedIntegerTypes for i32 { def_set_fn!(i8); }
-impl InsertIntoSizedIntegerTypes for u64 { def_set_fn!(u8); }
-impl InsertIntoSizedIntegerTypes for i64 { def_set_fn!(i8); }
+/// Defines a set of functions to get, set and clear single bits in a Vec<u8> or a byte slice
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut v: Vec<u8> = vec!{ 0x00, 0x01 };
+/// assert_eq!(v.get_bit(0, 15).unwrap(), true);
+/// v.set_bit(1, 0).unwrap();
+/// v.clear_bit(0, 15).unwrap();
+/// assert_eq!(v, vec!{ 0x00, 0x80 });
+/// ```
+pub trait SingleBitsInVecU8 {
+	/// Tests a single bit and returns true or false in a Result object
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the offset of the bit to be tested. Zero is the **MOST** significant bit.
+	fn get_bit(&self, byte_offset: u32, bit_offset: u32) -> Result<bool>;
 
-/// Defines a functions, which inserts a range of bits into a Vec<u8>
-/// **Important:** the contents of the vector are assumed to be **big endian** (network order)
-pub trait InsertBitsIntoVecU8 {
-	/// inserts a range of bits into a Vec<u8>
+	/// Sets a single bit
 	///
 	/// Parameters:
 	///
 	/// - **byte_offset** (u32) the number of bytes to skip
-	/// - **bit_offset** (u32) the number of bits to skip. Zero is the most significant bit
-	/// - **length** (u32) the number of bits to be inserted.
-	/// - **value** (u32) the value to be inserted.
-	fn set<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
-		where Self: std::marker::Sized, T: std::marker::Sized, T: SignedInfo,
-		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
-		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
-		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
-		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
-		T : std::string::ToString, T: SingleBits + Copy;
-}
+	/// - **bit_offset** (u32) the offset of the bit to be set. Zero is the **MOST** significant bit.
+	fn set_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()>;
 
-impl InsertBitsIntoVecU8 for Vec<u8> {
-	fn set<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
-		where Self: std::marker::Sized, T: std::marker::Sized, T: SignedInfo,
-		T: num::cast::AsPrimitive<u8>, T: num::cast::AsPrimitive<i8>,
-		T: num::cast::AsPrimitive<u16>, T: num::cast::AsPrimitive<i16>,
-		T: num::cast::AsPrimitive<u32>, T: num::cast::AsPrimitive<i32>,
-		T: num::cast::AsPrimitive<u64>, T: num::cast::AsPrimitive<i64>,
-		T : std::string::ToString, T: SingleBits + Copy {
+	/// Clears a single bit
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the offset of the bit to be cleared. Zero is the **MOST** significant bit.
+	fn clear_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()>;
 
-		// Range checks
-		if length == 0 { return Err(s!(LEN_ZERO)); };
+	/// Inverts a single bit
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the offset of the bit to be inverted. Zero is the **MOST** significant bit.
+	fn toggle_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()>;
 
-		if byte_offset * 8 + bit_offset + length > self.len() as u32 * 8 {
-			return Err(s!(OUT_OF_RANGE_MSG));
+	/// Sets the bit if value is true or clears it otherwise
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the offset of the bit to be assigned. Zero is the **MOST** significant bit.
+	/// - **value** (bool) the new state of the bit
+	fn assign_bit(&mut self, byte_offset: u32, bit_offset: u32, value: bool) -> Result<()> {
+		if value {
+			self.set_bit(byte_offset, bit_offset)
+		} else {
+			self.clear_bit(byte_offset, bit_offset)
 		}
+	}
 
-		if value.is_signed() {
-			let n = n_required_bits_for_a_signed_int(value.as_()); // value.as_() is type casting to u32 in this case
-			if n > length {
-				return Err(format!("Failed to insert {} as a {} bit signed integer variable, since it requires at least {} bits.",
-					&value.to_string(), &length.to_string(), &n.to_string()))
-			}
-		} else {
-			let n = n_required_bits_for_an_unsigned_int(value.as_()); // value.as_() is type casting to u32 in this case
-			if n > length {
-				return Err(format!("Failed to insert {} as a {} bit unsigned integer variable, since it requires at least {} bits.",
-					&value.to_string(), &length.to_string(), &n.to_string()))
-			}
+	/// Sets length bits starting at the given offsets if bit is true or clears them otherwise.
+	/// Returns an error and modifies nothing, if length is zero or the range exceeds the vector.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0x00, 0x00, 0xFF };
+	/// v.fill_bits(0, 5, 8, true).unwrap();
+	/// v.clear_bits(2, 2, 4).unwrap();
+	/// assert_eq!(v, vec!{ 0x07, 0xF8, 0xC3 });
+	/// ```
+	fn fill_bits(&mut self, byte_offset: u32, bit_offset: u32, length: u32, bit: bool) -> Result<()>;
+
+	/// Reverses the order of length bits starting at the given offsets, so that the first bit
+	/// becomes the last one. Returns an error and modifies nothing, if the range exceeds the vector.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0b1100_0001, 0b0100_0000 };
+	/// v.reverse_bits_in_range(0, 2, 8).unwrap();
+	/// assert_eq!(v, vec!{ 0b1110_1000, 0b0000_0000 });
+	/// ```
+	fn reverse_bits_in_range(&mut self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<()>;
+
+	/// Rotates length bits starting at the given offsets by amount bits towards the first bit.
+	/// Returns an error and modifies nothing, if the range exceeds the vector.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let mut v: Vec<u8> = vec!{ 0b0000_1100, 0b0100_0000 };
+	/// v.rotate_bits_left(0, 4, 8, 3).unwrap();
+	/// assert_eq!(v, vec!{ 0b0000_0010, 0b0110_0000 });
+	/// v.rotate_bits_right(0, 4, 8, 3).unwrap();
+	/// assert_eq!(v, vec!{ 0b0000_1100, 0b0100_0000 });
+	/// ```
+	fn rotate_bits_left(&mut self, byte_offset: u32, bit_offset: u32, length: u32, amount: u32) -> Result<()>;
+
+	/// Rotates length bits starting at the given offsets by amount bits towards the last bit.
+	/// See rotate_bits_left.
+	fn rotate_bits_right(&mut self, byte_offset: u32, bit_offset: u32, length: u32, amount: u32) -> Result<()> {
+		if length == 0 {
+			return self.rotate_bits_left(byte_offset, bit_offset, 0, 0);
 		}
+		self.rotate_bits_left(byte_offset, bit_offset, length, length - amount % length)
+	}
 
-		let first_relevant_byte_index = byte_offset + bit_offset / 8;
-		let last_relevant_byte_index  = byte_offset + (bit_offset + length - 1) / 8;
-		// For each relevant byte in the vector
-		// 1. Make a copy of a byte
-		// 2. For each relevant bit in the copy, set or clear the relevant bits (bit by bit)
-		// 3. Replace the oríginal byte in the vector with the modified copy
-		let mut bit_counter = length;
-		let mut read_bit_index = std::mem::size_of::<T>() as u32 * 8 - length;
-		let mut write_bit_index = bit_offset % 8;
+	/// Sets length bits starting at the given offsets. See fill_bits.
+	fn set_bits(&mut self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<()> {
+		self.fill_bits(byte_offset, bit_offset, length, true)
+	}
 
-		for byte_index in first_relevant_byte_index .. last_relevant_byte_index + 1 {
-			let mut copy = self[byte_index as usize];	// Step 1
+	/// Clears length bits starting at the given offsets. See fill_bits.
+	fn clear_bits(&mut self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<()> {
+		self.fill_bits(byte_offset, bit_offset, length, false)
+	}
+}
 
-			while bit_counter > 0 {	// Step 2
-				if value.get_bit(read_bit_index)? {
-					copy = copy.set_bit(write_bit_index)?;
-				} else {
-					copy = copy.clear_bit(write_bit_index)?;
-				}
-				read_bit_index += 1;
-				write_bit_index += 1;
-				bit_counter -= 1;
-				if write_bit_index % 8 == 0 {
-					write_bit_index = 0;
-					break;
-				}
+impl SingleBitsInVecU8 for [u8] {
+	fn get_bit(&self, byte_offset: u32, bit_offset: u32) -> Result<bool> {
+		let (index, mask) = locate_bit(self.len(), byte_offset, bit_offset)?;
+		Ok(self[index] & mask != 0)
+	}
+
+	fn set_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()> {
+		let (index, mask) = locate_bit(self.len(), byte_offset, bit_offset)?;
+		self[index] |= mask;
+		Ok(())
+	}
+
+	fn clear_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()> {
+		let (index, mask) = locate_bit(self.len(), byte_offset, bit_offset)?;
+		self[index] &= !mask;
+		Ok(())
+	}
+
+	fn toggle_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()> {
+		let (index, mask) = locate_bit(self.len(), byte_offset, bit_offset)?;
+		self[index] ^= mask;
+		Ok(())
+	}
+
+	fn fill_bits(&mut self, byte_offset: u32, bit_offset: u32, length: u32, bit: bool) -> Result<()> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		if !is_within(self.len(), byte_offset, bit_offset, length) {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+
+		let start = byte_offset as usize * 8 + bit_offset as usize;
+		let end = start + length as usize;
+		let value = if bit { 0xFF } else { 0x00 };
+		let mut position = start;
+		while position < end {
+			let index = position / 8;
+			if position.is_multiple_of(8) && end - position >= 8 {
+				// Fill all whole bytes at once
+				let n = (end - position) / 8;
+				self[index..index + n].fill(value);
+				position += n * 8;
+			} else {
+				// Mask the bits of a partial byte
+				let n = std::cmp::min(8 - position % 8, end - position);
+				let mask = (0xFFu8 >> (position % 8)) & (0xFFu8 << (8 - position % 8 - n));
+				self[index] = (self[index] & !mask) | (value & mask);
+				position += n;
 			}
+		}
+		Ok(())
+	}
 
-			self[byte_index as usize] = copy;	// Step 3
+	fn reverse_bits_in_range(&mut self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<()> {
+		bit_range(byte_offset, bit_offset, length)?;
+		if length == 0 {
+			return Ok(());
 		}
+		let start = range_start(self.len(), byte_offset, bit_offset, length as usize)?;
 
+		// Swap up to 64 reversed bits from both ends at once, until they meet in the middle
+		let (mut front, mut back) = (start, start + length as usize);
+		while back - front >= 2 {
+			let n = std::cmp::min(64, (back - front) / 2) as u32;
+			let a = read_bits_from_slice(self, front, n);
+			let b = read_bits_from_slice(self, back - n as usize, n);
+			write_bits_to_slice(self, front, n, b.reverse_bits() >> (64 - n));
+			write_bits_to_slice(self, back - n as usize, n, a.reverse_bits() >> (64 - n));
+			front += n as usize;
+			back -= n as usize;
+		}
 		Ok(())
 	}
+
+	fn rotate_bits_left(&mut self, byte_offset: u32, bit_offset: u32, length: u32, amount: u32) -> Result<()> {
+		bit_range(byte_offset, bit_offset, length)?;
+		if length == 0 {
+			return Ok(());
+		}
+		let start = range_start(self.len(), byte_offset, bit_offset, length as usize)?;
+
+		// Copy both parts of the range from a copy of its bytes to their new positions
+		let copy = self[start / 8..(start + length as usize).div_ceil(8)].to_vec();
+		let (offset, length, amount) = (start % 8, length as usize, (amount % length) as usize);
+		bulk::copy_bits(&copy, offset + amount, self, start, length - amount)?;
+		bulk::copy_bits(&copy, offset, self, start + length - amount, amount)
+	}
+}
+
+// Vectors use the implementation for slices
+impl SingleBitsInVecU8 for Vec<u8> {
+	#[inline]
+	fn get_bit(&self, byte_offset: u32, bit_offset: u32) -> Result<bool> {
+		self.as_slice().get_bit(byte_offset, bit_offset)
+	}
+
+	#[inline]
+	fn set_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()> {
+		self.as_mut_slice().set_bit(byte_offset, bit_offset)
+	}
+
+	#[inline]
+	fn clear_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()> {
+		self.as_mut_slice().clear_bit(byte_offset, bit_offset)
+	}
+
+	#[inline]
+	fn toggle_bit(&mut self, byte_offset: u32, bit_offset: u32) -> Result<()> {
+		self.as_mut_slice().toggle_bit(byte_offset, bit_offset)
+	}
+
+	#[inline]
+	fn fill_bits(&mut self, byte_offset: u32, bit_offset: u32, length: u32, bit: bool) -> Result<()> {
+		self.as_mut_slice().fill_bits(byte_offset, bit_offset, length, bit)
+	}
+
+	#[inline]
+	fn reverse_bits_in_range(&mut self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<()> {
+		self.as_mut_slice().reverse_bits_in_range(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn rotate_bits_left(&mut self, byte_offset: u32, bit_offset: u32, length: u32, amount: u32) -> Result<()> {
+		self.as_mut_slice().rotate_bits_left(byte_offset, bit_offset, length, amount)
+	}
 }
 
+mod bitslice;
+pub use bitslice::{BitSlice, BitSliceMut};
+
+mod buffer;
+pub use buffer::BitBuffer;
+
+mod field;
+pub use field::{BitField, Field, FieldIter};
+
+mod frame;
+pub use frame::FrameBuilder;
+
+mod layout;
+pub use layout::{Layout, LayoutField};
+
+mod matrix;
+pub use matrix::BitMatrix;
+
+/// Derives from_bytes(), to_bytes() and BIT_LEN for a struct with packed bit fields.
+/// Requires the feature **derive**.
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use bitlab::BitLayout;
+///
+/// #[derive(BitLayout, Debug, PartialEq)]
+/// struct Ipv4Start {
+///     #[bits(4)] version: u8,
+///     #[bits(4)] ihl: u8,
+///     #[bits(6)] dscp: u8,
+///     #[bits(2)] ecn: u8,
+///     #[bits(16)] total_length: u16,
+/// }
+///
+/// let header = Ipv4Start::from_bytes(&[0x45, 0x00, 0x05, 0xDC]).unwrap();
+/// assert_eq!(header, Ipv4Start { version: 4, ihl: 5, dscp: 0, ecn: 0, total_length: 1500 });
+/// assert_eq!(header.to_bytes().unwrap(), vec!{ 0x45, 0x00, 0x05, 0xDC });
+/// assert_eq!(Ipv4Start::BIT_LEN, 32);
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+pub use bitlab_derive::BitLayout;
+
+mod reader;
+pub use reader::BitReader;
+
+mod source;
+pub use source::BitSource;
+
+mod value;
+pub use value::{Value, ValueKind, DynamicBits};
+
+mod writer;
+pub use writer::BitWriter;
+
+mod sink;
+pub use sink::BitSink;
+
+mod signedness;
+pub use signedness::SignednessPolicy;
+
+pub mod bulk;
+pub mod checksum;
+pub mod crc;
+pub mod ct;
+pub mod dump;
+pub mod explain;
+pub mod fec;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod framing;
+pub mod from_end;
+pub use from_end::FromEnd;
+pub mod interleave;
+pub mod linecode;
+pub mod lsb0;
+pub use lsb0::Lsb0Bits;
+#[cfg(feature = "mmap")]
+pub mod mapped;
+pub mod minifloat;
+pub mod morton;
+#[cfg(feature = "nom")]
+pub mod nom_parsers;
+pub mod offset64;
+pub use offset64::LargeOffsets;
+#[cfg(feature = "serde")]
+pub mod packing;
+pub mod padded;
+pub use padded::ZeroPadded;
+pub mod pext;
+pub use pext::MaskedBits;
+pub mod parity;
+pub mod posit;
+pub mod position;
+pub use position::{BitPos, ChainedBits};
+pub mod scan;
+pub use scan::BitScan;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod stuffing;
+#[cfg(any(feature = "ffi", feature = "wasm"))]
+mod slice;
+pub mod text;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 /////////////////////////////////////////////////////////////////////
 //                                                                 //
 //                          UNIT TESTS                             //
@@ -2578,6 +4869,26 @@ mod tests {
 		assert_eq!(n_required_bits_for_a_signed_int(-129), 9);
 	}
 
+	#[test]
+	fn test_bit_length() {
+		assert_eq!(0u8.bit_length(), 1);
+		assert_eq!(0x80u8.bit_length(), 8);
+		assert_eq!(0x1FFu16.bit_length(), 9);
+		assert_eq!(u32::MAX.bit_length(), 32);
+		assert_eq!(i8::MIN.bit_length(), 8);
+		assert_eq!(i8::MAX.bit_length(), 8);
+		assert_eq!((-129i16).bit_length(), 9);
+		assert_eq!(i64::MIN.bit_length(), 64);
+		assert_eq!(i128::MIN.bit_length(), 128);
+		assert_eq!((1u128 << 100).bit_length(), 101);
+
+		// The floating point implementation failed near 2^53
+		assert_eq!(n_required_bits_for_an_unsigned_int((1 << 53) - 1), 53);
+		assert_eq!(n_required_bits_for_an_unsigned_int(u64::MAX), 64);
+		assert_eq!(n_required_bits_for_a_signed_int(-(1 << 53) - 1), 55);
+		assert_eq!(n_required_bits_for_a_signed_int(i64::MIN), 64);
+	}
+
 	#[test]
 	fn range_checks_for_integrals() {
 		//
@@ -3678,6 +5989,10 @@ mod tests {
 
 		// Clear the same bit again
 		assert_eq!(a.clear_bit(b).unwrap(), 5);
+
+		// Clearing a bit keeps the other 63 bits
+		assert_eq!((-1i64).clear_bit(0).unwrap(), i64::MAX);
+		assert_eq!((-1i64).clear_bit(40).unwrap(), !(1 << 23));
 	}
 
 	#[test]
@@ -4676,4 +6991,719 @@ mod tests {
 			Err(e) => assert_eq!(e, s!("Failed to insert 3 as a 1 bit unsigned integer variable, since it requires at least 2 bits.")),
 		}
 	}
+
+	#[test]
+	fn test_little_endian() {
+		// The width and height of a BMP file at byte offset 18 and 22
+		let v: Vec<u8> = vec!{ 0x80, 0x02, 0x00, 0x00, 0xE0, 0xFE, 0xFF, 0xFF };
+		assert_eq!(v.get_u32_le(0, 0, 32).unwrap(), 640);
+		assert_eq!(v.get_i32_le(4, 0, 32).unwrap(), -288);
+		assert_eq!(v.get_u16_le(0, 0, 16).unwrap(), 0x0280);
+		assert_eq!(v.get_i16_le(0, 0, 16).unwrap(), 0x0280);
+		assert_eq!(v.get_u64_le(0, 0, 64).unwrap(), 0xFFFF_FEE0_0000_0280);
+		assert_eq!(v.get_i64_le(0, 0, 64).unwrap(), 0xFFFF_FEE0_0000_0280u64 as i64);
+
+		// Unaligned 24 bit field: 0x02_0000_0 | 0x0E --> bytes 0x20 0x00 0x0E
+		assert_eq!(v.get_u32_le(1, 4, 24).unwrap(), 0x0E_0020);
+		assert_eq!(v.get_i32_le(1, 4, 24).unwrap(), 0x0E_0020);
+		assert_eq!(v.get_i16_le(4, 8, 16).unwrap(), -2);
+
+		assert_eq!(v.get_u16_le(0, 0, 12).unwrap_err(), LITTLE_ENDIAN_LEN_MSG);
+		assert_eq!(v.get_u16_le(0, 0, 24).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u16");
+		assert_eq!(v.get_u64_le(1, 0, 64).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_slices() {
+		let mut buffer = [0x48u8, 0x61, 0x6C, 0x6C, 0x6F];
+		{
+			let frame: &mut [u8] = &mut buffer[1..4];
+			assert_eq!(frame.get_u16(0, 7, 3).unwrap(), 5);
+			frame.set(0, 20, 4, 0xFu8).unwrap();
+			assert_eq!(frame.set(2, 1, 8, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+			assert_eq!(frame.get_dyn(0, 0, 24, ValueKind::Bytes).unwrap(), Value::Bytes(vec!{ 0x61, 0x6C, 0x6F }));
+		}
+		assert_eq!(buffer, [0x48, 0x61, 0x6C, 0x6F, 0x6F]);
+	}
+
+	#[test]
+	fn test_nonzero_outputs() {
+		let v: Vec<u8> = vec!{ 0x48, 0x61, 0x00, 0x00, 0x6F };
+		assert_eq!(v.get_nonzero_u8(1, 7, 3).unwrap().get(), 4);
+		assert_eq!(v.get_nonzero_u16(0, 0, 16).unwrap().get(), 0x4861);
+		assert_eq!(v.get_nonzero_u32(2, 0, 24).unwrap().get(), 0x6F);
+		assert_eq!(v.get_nonzero_u64(0, 0, 40).unwrap().get(), 0x48_6100_006F);
+
+		assert_eq!(v.get_nonzero_u8(2, 0, 8).unwrap_err(), ZERO_VALUE_MSG);
+		assert_eq!(v.get_nonzero_u32(2, 0, 16).unwrap_err(), ZERO_VALUE_MSG);
+		assert_eq!(v.get_nonzero_u16(4, 0, 16).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn no_panics_on_arbitrary_parameters() {
+		// The checks must reject all invalid parameters with an error, even if the
+		// calculation of the end of the range overflows
+		let edges = [0, 1, 7, 8, 9, 31, 32, 33, 63, 64, 65, u32::MAX / 8, u32::MAX - 7, u32::MAX];
+
+		let result = std::panic::catch_unwind(|| {
+			let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F, 0x21, 0xFF, 0x00, 0x80, 0x7F };
+			for &a in edges.iter() {
+				for &b in edges.iter() {
+					let _ = 0xA5u8.get_u8(a, b);
+					let _ = 0xA5u8.get_i64(a, b);
+					let _ = (-2i16).get_u16(a, b);
+					let _ = 0xA5A5_A5A5u32.get_i32(a, b);
+					let _ = (-1i64).get_u64(a, b);
+					let _ = 0x5Au8.set(a, b, 1u8);
+					let _ = 0x5Au64.set(a, b, -1i8);
+					let _ = 0x5Au8.get_bit(a);
+					let _ = 0x5Au64.set_bit(a);
+
+					for &c in edges.iter() {
+						let _ = v.get_u8(a, b, c);
+						let _ = v.get_i16(a, b, c);
+						let _ = v.get_u32(a, b, c);
+						let _ = v.get_i64(a, b, c);
+						let _ = v.set(a, b, c, 1u8);
+						let _ = v.set(a, b, c, -1i64);
+					}
+				}
+			}
+		});
+
+		assert!(result.is_ok());
+	}
+
+	#[test]
+	fn test_set_keeps_surrounding_bits() {
+		assert_eq!((-1i16).set(4, 8, 0u8).unwrap(), 0xF00Fu16 as i16);
+		assert_eq!(0u32.set(8, 8, -1i8).unwrap(), 0x00FF_0000);
+		assert_eq!(0x5555u16.set(0, 16, 0xAAAAu16).unwrap(), 0xAAAA);
+		assert_eq!(u64::MAX.set(63, 1, 0u8).unwrap(), u64::MAX - 1);
+		assert_eq!(0i8.set(0, 2, -2i8).unwrap(), i8::MIN);
+		assert_eq!(0u128.set(1, 127, 1u8).unwrap(), 1);
+	}
+
+	#[test]
+	fn test_toggle_bit() {
+		assert_eq!(0b0000_0101u8.toggle_bit(0).unwrap(), 0b1000_0101);
+		assert_eq!(0b0000_0101u8.toggle_bit(7).unwrap(), 0b0000_0100);
+		assert_eq!((-1i8).toggle_bit(0).unwrap(), i8::MAX);
+		assert_eq!(0x00F0u16.toggle_bit(8).unwrap(), 0x0070);
+		assert_eq!(0i16.toggle_bit(15).unwrap(), 1);
+		assert_eq!(u32::MAX.toggle_bit(31).unwrap(), u32::MAX - 1);
+		assert_eq!(0i32.toggle_bit(0).unwrap(), i32::MIN);
+		assert_eq!(0u64.toggle_bit(63).unwrap(), 1);
+		assert_eq!((-1i64).toggle_bit(0).unwrap(), i64::MAX);
+		assert_eq!(0u128.toggle_bit(0).unwrap(), 1 << 127);
+		assert_eq!((-1i128).toggle_bit(127).unwrap(), -2);
+		// Toggling twice restores the value
+		assert_eq!(0x1234u16.toggle_bit(3).unwrap().toggle_bit(3).unwrap(), 0x1234);
+		assert_eq!(5u8.toggle_bit(8).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_assign_bit() {
+		let flags = [true, false, true, true];
+		let mut a: u8 = 0b0100_0000;
+		for (i, &f) in flags.iter().enumerate() {
+			a = a.assign_bit(i as u32, f).unwrap();
+		}
+		assert_eq!(a, 0b1011_0000);
+		assert_eq!((-1i64).assign_bit(63, false).unwrap(), -2);
+		assert_eq!(0u128.assign_bit(127, true).unwrap(), 1);
+		assert_eq!(0i32.assign_bit(32, true).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_single_bits_in_vectors() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61 };
+		assert!(v.get_bit(0, 1).unwrap());
+		assert!(!v.get_bit(0, 2).unwrap());
+		assert!(v.get_bit(1, 7).unwrap());
+		assert!(v.get_bit(0, 15).unwrap());
+		assert_eq!(v.get_bit(1, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		v.set_bit(0, 0).unwrap();
+		v.clear_bit(0, 9).unwrap();
+		assert_eq!(v, vec!{ 0xC8, 0x21 });
+		assert_eq!(v.set_bit(2, 0).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.clear_bit(0, 16).unwrap_err(), OUT_OF_RANGE_MSG);
+		v.toggle_bit(0, 0).unwrap();
+		v.toggle_bit(1, 3).unwrap();
+		assert_eq!(v, vec!{ 0x48, 0x31 });
+		assert_eq!(v.toggle_bit(1, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+		v.assign_bit(0, 15, false).unwrap();
+		v.assign_bit(1, 0, true).unwrap();
+		assert_eq!(v, vec!{ 0x48, 0xB0 });
+		assert_eq!(v.assign_bit(2, 0, true).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		let mut a = [0u8; 2];
+		a[1..].set_bit(0, 7).unwrap();
+		assert_eq!(a, [0x00, 0x01]);
+		assert!(a[..].get_bit(0, 15).unwrap());
+	}
+
+	#[test]
+	fn test_floats() {
+		// 1.5 as f32 = 0x3FC0_0000 and -0.1 as f64 = 0xBFB9_9999_9999_999A, at the bit offsets 5 and 3
+		let v: Vec<u8> = vec!{ 0b0000_0001, 0b1111_1110, 0x00, 0x00, 0x00 };
+		assert_eq!(v.get_f32(0, 5).unwrap(), 1.5);
+		assert_eq!(v.get_f32(1, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		let mut w: Vec<u8> = vec!{ 0; 9 };
+		w.set(0, 3, 64, (-0.1f64).to_bits()).unwrap();
+		assert_eq!(w[0], 0b0001_0111);
+		assert_eq!(w.get_f64(0, 3).unwrap(), -0.1);
+		assert_eq!(w[..8].get_f64(0, 0).unwrap(), f64::from_bits(0x17F7_3333_3333_3333));
+		assert!(w.get_f64(0, 9).is_err());
+	}
+
+	#[test]
+	fn test_16_bit_floats() {
+		// 1.5, -2.0 and 65504 as half precision floats at bit offset 5
+		let mut v: Vec<u8> = vec!{ 0; 7 };
+		v.set(0, 5, 16, 0x3E00u16).unwrap();
+		v.set(2, 5, 16, 0xC000u16).unwrap();
+		v.set(4, 5, 16, 0x7BFFu16).unwrap();
+		assert_eq!(v.get_f16(0, 5).unwrap(), 1.5);
+		assert_eq!(v.get_f16(2, 5).unwrap(), -2.0);
+		assert_eq!(v[..].get_f16(4, 5).unwrap(), 65504.0);
+		assert_eq!(0x0001u16.to_be_bytes().get_f16(0, 0).unwrap(), 2f32.powi(-24));
+		assert!([0x7Eu8, 0x00].get_f16(0, 0).unwrap().is_nan());
+
+		// The same bits as bfloat16
+		assert_eq!(v.get_bf16(0, 5).unwrap(), 0.125);
+		assert_eq!(v.get_bf16(2, 5).unwrap(), -2.0);
+		assert_eq!([0x7Fu8, 0x80].get_bf16(0, 0).unwrap(), f32::INFINITY);
+		assert!([0xFFu8, 0xC1].get_bf16(0, 0).unwrap().is_nan());
+		assert_eq!([0x00u8, 0x01].get_bf16(0, 0).unwrap(), f32::from_bits(0x0001_0000));
+		assert!(v.get_bf16(5, 1).is_err());
+		assert!(v.get_f16(5, 1).is_err());
+	}
+
+	#[test]
+	fn test_float_components() {
+		assert!(!0.0f64.sign());
+		assert!((-0.0f64).sign());
+		assert_eq!(1.0f64.exponent_bits(), 1023);
+		assert_eq!(0.75f64.mantissa_bits(), 1 << 51);
+		assert_eq!(f32::INFINITY.exponent_bits(), 255);
+		assert_eq!(f32::MIN_POSITIVE.mantissa_bits(), 0);
+		assert_eq!(f32::from_bits(1).mantissa_bits(), 1);
+
+		assert_eq!(2.5f32.set_sign(true), -2.5);
+		assert_eq!((-2.5f64).set_sign(false), 2.5);
+		assert_eq!(1.0f64.set_exponent_bits(1033).unwrap(), 1024.0);
+		assert_eq!(1.0f32.set_mantissa_bits(1 << 21).unwrap(), 1.25);
+		assert!(1.0f32.set_exponent_bits(255).unwrap().is_infinite());
+		assert!(1.0f32.set_exponent_bits(256).is_err());
+		assert!(1.0f64.set_mantissa_bits(1 << 52).is_err());
+	}
+
+	#[test]
+	fn test_extract_from_floats() {
+		// 1.5 = 0x3FC0_0000 and -0.1 = 0xBFB9_9999_9999_999A
+		assert_eq!(1.5f32.get_u16(0, 16).unwrap(), 0x3FC0);
+		assert_eq!(1.5f32.get_u8(1, 8).unwrap(), 127);
+		assert_eq!((-1.5f32).get_i8(0, 2).unwrap(), -2);
+		assert_eq!((-0.1f64).get_u64(0, 64).unwrap(), 0xBFB9_9999_9999_999A);
+		assert_eq!((-0.1f64).get_u16(1, 11).unwrap(), 1019);
+		assert_eq!((-0.1f64).get_i128(60, 4).unwrap(), -6);
+		assert_eq!(1.5f32.get_u8(30, 3).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_pointer_sized_types() {
+		let bits = usize::BITS;
+		let a: usize = 0b101 << (bits - 3);
+		assert_eq!(a.get_u8(0, 3).unwrap(), 0b101);
+		assert_eq!((-1isize).get_i16(bits - 5, 5).unwrap(), -1);
+		assert_eq!(a.get_u64(bits - 3, 4).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(a.type_of(), "usize");
+		assert!((-1isize).is_signed());
+
+		assert!(a.get_bit(2).unwrap());
+		assert_eq!(0usize.set_bit(bits - 1).unwrap(), 1);
+		assert_eq!((-1isize).clear_bit(0).unwrap(), isize::MAX);
+		assert_eq!(1isize.toggle_bit(bits - 1).unwrap(), 0);
+		assert!(a.set_bit(bits).is_err());
+
+		assert_eq!(0usize.set(bits - 8, 8, 0xABu8).unwrap(), 0xAB);
+		assert_eq!(0isize.set(0, bits, -2isize).unwrap(), -2);
+		assert_eq!(0u8.set(4, 4, 9usize).unwrap(), 9);
+		assert_eq!(0usize.set(0, bits + 1, 1u8).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "usize");
+
+		let mut v: Vec<u8> = vec!{ 0; 2 };
+		v.set(0, 3, 10, 1000usize).unwrap();
+		assert_eq!(v.get_u16(0, 3, 10).unwrap(), 1000);
+		assert_eq!(usize::MAX.bit_length(), bits);
+		assert_eq!(isize::MIN.bit_length(), bits);
+	}
+
+	#[test]
+	fn test_generic_get() {
+		let a: u32 = 0xDEAD_BEEF;
+		let b: u8 = a.get(0, 8).unwrap();
+		assert_eq!(b, 0xDE);
+		assert_eq!(a.get::<i64>(28, 4).unwrap(), -1);
+		assert_eq!(a.get::<u128>(0, 32).unwrap(), 0xDEAD_BEEF);
+		assert_eq!(a.get::<u8>(0, 9).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u8");
+		assert_eq!((-2isize).get::<i8>(usize::BITS - 2, 2).unwrap(), -2);
+
+		let v: Vec<u8> = vec!{ 0xDE, 0xAD, 0xBE, 0xEF };
+		assert_eq!(v.get_at::<u32>(0, 0, 32).unwrap(), a);
+		assert_eq!(v[..].get_at::<i16>(1, 4, 8).unwrap(), v.get_i16(1, 4, 8).unwrap());
+		assert_eq!(v.get_at::<u16>(3, 1, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_multiple_fields() {
+		let mut v: Vec<u8> = vec!{ 0; 10 };
+		v.set_fields(&[(0, 0, 3, 5), (0, 3, 13, 0x1ABC), (2, 0, 64, u64::MAX), (9, 7, 1, 1)]).unwrap();
+		assert_eq!(v, vec!{ 0xBA, 0xBC, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF });
+		assert_eq!(v.get_fields(&[(0, 0, 3), (0, 3, 13), (2, 0, 64), (9, 7, 1)]).unwrap(), vec!{ 5, 0x1ABC, u64::MAX, 1 });
+		assert_eq!(v[..].get_fields(&[]).unwrap(), vec!{});
+
+		// Nothing is extracted or modified, if any of the fields is invalid
+		assert_eq!(v.get_fields(&[(0, 0, 3), (9, 7, 2)]).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_fields(&[(0, 0, 65)]).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u64");
+		assert_eq!(v.set_fields(&[(0, 0, 8, 0), (1, 0, 0, 0)]).unwrap_err(), LEN_ZERO);
+		assert!(v.set_fields(&[(0, 0, 8, 0), (1, 0, 4, 16)]).is_err());
+		assert_eq!(v[0], 0xBA);
+	}
+
+	#[test]
+	fn test_reverse_bits_in_range() {
+		assert_eq!(0x0123_4567u32.reverse_bits_in_range(0, 32).unwrap(), 0x0123_4567u32.reverse_bits());
+		assert_eq!((-2i16).reverse_bits_in_range(8, 8).unwrap(), 0xFF7Fu16 as i16);
+		assert_eq!(0b1000_0000u8.reverse_bits_in_range(0, 1).unwrap(), 0b1000_0000);
+		assert_eq!(5u64.reverse_bits_in_range(64, 0).unwrap(), 5);
+		assert!(5u64.reverse_bits_in_range(60, 5).is_err());
+		assert!(5u8.reverse_bits_in_range(1, u32::MAX).is_err());
+
+		let mut v: Vec<u8> = vec!{ 0x12, 0x34, 0x56 };
+		v.reverse_bits_in_range(0, 0, 24).unwrap();
+		assert_eq!(v, vec!{ 0x6A, 0x2C, 0x48 });
+		v[..].reverse_bits_in_range(1, 3, 3).unwrap();
+		assert_eq!(v[1], 0x38);
+		v.reverse_bits_in_range(0, 4, 8).unwrap();
+		assert_eq!(v, vec!{ 0x6C, 0x58, 0x48 });
+
+		// Nothing is modified, if the range exceeds the vector
+		assert_eq!(v.reverse_bits_in_range(2, 1, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(v.reverse_bits_in_range(3, 0, 1).is_err());
+		assert_eq!(v, vec!{ 0x6C, 0x58, 0x48 });
+
+		// Long ranges are reversed word by word. Compare them with the bits of the original.
+		let data: Vec<u8> = (0..41u8).map(|i| i.wrapping_mul(73) ^ 0x5A).collect();
+		for &(bit_offset, length) in &[(3, 150), (0, 328), (13, 129), (7, 2)] {
+			let mut v = data.clone();
+			v.reverse_bits_in_range(0, bit_offset, length).unwrap();
+			for i in 0..length {
+				assert_eq!(v.get_bit(0, bit_offset + i).unwrap(), data.get_bit(0, bit_offset + length - 1 - i).unwrap());
+			}
+			assert_eq!(v[..bit_offset as usize / 8], data[..bit_offset as usize / 8]);
+		}
+
+		let a = 0x0123_4567_89AB_CDEFi64;
+		for bit_offset in 0..64 {
+			for length in 1..=64 - bit_offset {
+				let r = a.reverse_bits_in_range(bit_offset, length).unwrap();
+				for i in 0..64 {
+					let j = if i >= bit_offset && i < bit_offset + length { 2 * bit_offset + length - 1 - i } else { i };
+					assert_eq!(r.get_bit(i).unwrap(), a.get_bit(j).unwrap());
+				}
+			}
+		}
+		assert_eq!(1usize.reverse_bits_in_range(0, usize::BITS).unwrap(), 1usize.reverse_bits());
+	}
+
+	#[test]
+	fn test_rotate_bits() {
+		assert_eq!(0x1234_5678u32.rotate_bits_left(0, 32, 12).unwrap(), 0x1234_5678u32.rotate_left(12));
+		assert_eq!(0x1234_5678u32.rotate_bits_right(0, 32, 36).unwrap(), 0x1234_5678u32.rotate_right(4));
+		assert_eq!(0b0110_1001u8.rotate_bits_left(2, 5, 1).unwrap(), 0b0101_0011);
+		assert_eq!(0b0110_1001u8.rotate_bits_right(2, 5, 4).unwrap(), 0b0101_0011);
+		assert_eq!((-1i8).rotate_bits_left(8, 0, 3).unwrap(), -1);
+		assert!(0u8.rotate_bits_left(4, 5, 1).is_err());
+		assert!(0u8.rotate_bits_right(1, u32::MAX, 1).is_err());
+
+		let mut v: Vec<u8> = vec!{ 0x12, 0x34, 0x56 };
+		v.rotate_bits_left(0, 0, 24, 8).unwrap();
+		assert_eq!(v, vec!{ 0x34, 0x56, 0x12 });
+		v[..].rotate_bits_right(0, 4, 16, 4).unwrap();
+		assert_eq!(v, vec!{ 0x31, 0x45, 0x62 });
+		v.rotate_bits_left(2, 7, 1, 5).unwrap();
+		assert_eq!(v.rotate_bits_left(2, 1, 8, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.rotate_bits_right(3, 0, 0, 1), Ok(()));
+		assert_eq!(v, vec!{ 0x31, 0x45, 0x62 });
+
+		// Long ranges are rotated word by word. Compare them with the bits of the original.
+		let data: Vec<u8> = (0..41u8).map(|i| i.wrapping_mul(73) ^ 0x5A).collect();
+		for &(bit_offset, length, amount) in &[(3, 150, 67), (0, 328, 1), (13, 129, 128), (7, 2, 3), (5, 70, 70)] {
+			let mut v = data.clone();
+			v.rotate_bits_left(0, bit_offset, length, amount).unwrap();
+			for i in 0..length {
+				assert_eq!(v.get_bit(0, bit_offset + i).unwrap(), data.get_bit(0, bit_offset + (i + amount) % length).unwrap());
+			}
+			assert_eq!(v[..bit_offset as usize / 8], data[..bit_offset as usize / 8]);
+		}
+
+		let a = 0x0123_4567_89AB_CDEFi64;
+		for bit_offset in 0..64 {
+			for length in 1..=64 - bit_offset {
+				let r = a.rotate_bits_left(bit_offset, length, 5).unwrap();
+				for i in 0..64 {
+					let j = if i >= bit_offset && i < bit_offset + length { bit_offset + (i - bit_offset + 5) % length } else { i };
+					assert_eq!(r.get_bit(i).unwrap(), a.get_bit(j).unwrap());
+				}
+			}
+		}
+		assert_eq!(1usize.rotate_bits_right(0, usize::BITS, 1).unwrap(), 1usize.rotate_right(1));
+	}
+
+	#[test]
+	fn test_get_strided() {
+		let v: Vec<u8> = vec!{ 0xAA, 0x55, 0xFF };
+		assert_eq!(v.get_strided(0, 0, 2, 8).unwrap(), 0xF0);
+		assert_eq!(v.get_strided(0, 1, 2, 8).unwrap(), 0x0F);
+		assert_eq!(v[..].get_strided(0, 0, 8, 3).unwrap(), 0b101);
+		assert_eq!(v.get_strided(2, 7, 100, 1).unwrap(), 1);
+		assert_eq!(v.get_strided(0, 0, 1, 0).unwrap(), 0);
+		assert_eq!(v.get_strided(0, 0, 0, 8).unwrap_err(), STRIDE_ZERO_MSG);
+		assert_eq!(v.get_strided(0, 1, 12, 3).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(v.get_strided(0, 0, 1, 65).is_err());
+		assert_eq!(v.get_strided_bools(0, 0, 1, 24).unwrap().iter().filter(|&&b| b).count(), 16);
+		assert_eq!(v.get_strided_bools(0, 0, 1, usize::MAX).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_strided_bools(0, 0, u32::MAX, 2).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_strided_bools(u32::MAX, 0, 1, 0).unwrap(), vec!{});
+	}
+
+	#[test]
+	fn test_get_bytes() {
+		let v: Vec<u8> = vec!{ 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD };
+		assert_eq!(v.get_bytes::<6>(0, 0).unwrap(), [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+		assert_eq!(v.get_bytes::<6>(0, 4).unwrap(), [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
+		assert_eq!(v[..].get_bytes::<2>(1, 9).unwrap(), [0x8A, 0xCF]);
+		assert_eq!(v.get_bytes::<1>(6, 0).unwrap(), [0xCD]);
+		assert_eq!(v.get_bytes::<1>(6, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_bytes::<8>(0, 0).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_bytes::<0>(0, 0).unwrap_err(), LEN_ZERO);
+		assert_eq!(v.get_bytes::<2>(u32::MAX, 0).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_network_addresses() {
+		// An Ethernet header shifted by 4 bits, followed by the source address of an IPv4 header
+		let mut v: Vec<u8> = vec!{ 0x0F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF0 };
+		v.extend_from_slice(&[0xC0, 0xA8, 0x01, 0x02]);
+		assert_eq!(v.get_mac(0, 4).unwrap(), [0xFF; 6]);
+		assert_eq!(v.get_ipv4(7, 0).unwrap(), std::net::Ipv4Addr::new(192, 168, 1, 2));
+		assert_eq!(v.get_ipv4(7, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		let mut v: Vec<u8> = vec!{ 0x00; 17 };
+		v[0] = 0x7F;
+		v[1] = 0xC0;
+		v[16] = 0x80;
+		assert_eq!(v.get_ipv6(0, 1).unwrap(), "ff80::1".parse::<std::net::Ipv6Addr>().unwrap());
+		assert!(v[1..].get_ipv6(0, 1).is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "uuid")]
+	fn test_uuid() {
+		let id = uuid::Uuid::from_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210);
+		let mut v: Vec<u8> = vec!{ 0xFF; 17 };
+		v.set_uuid(0, 4, id).unwrap();
+		assert_eq!(v[0], 0xF0);
+		assert_eq!(v[16], 0x0F);
+		assert_eq!(v.get_u128(0, 4, 128).unwrap(), id.as_u128());
+		assert_eq!(v.get_uuid(0, 4).unwrap(), id);
+		assert_eq!(v.set_uuid(0, 9, id).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(v[1..].get_uuid(0, 1).is_err());
+	}
+
+	#[test]
+	fn test_get_str() {
+		let v: Vec<u8> = b"xGr\xC3\xBC\xC3\x9Fe".to_vec();
+		let s = v.get_str(1, 0, 7).unwrap();
+		assert_eq!(s, "Grüße");
+		assert!(matches!(s, std::borrow::Cow::Borrowed(_)));
+		assert_eq!(v.get_str(0, 8, 1).unwrap(), "G");
+		assert_eq!(v.get_str(3, 0, 0).unwrap(), "");
+
+		// The same bytes shifted by 5 bits
+		let mut shifted: Vec<u8> = vec!{ 0x00; 9 };
+		bulk::copy_bits(&v, 0, &mut shifted, 5, 64).unwrap();
+		let s = shifted.get_str(1, 5, 7).unwrap();
+		assert_eq!(s, "Grüße");
+		assert!(matches!(s, std::borrow::Cow::Owned(_)));
+		assert_eq!(shifted.get_str(1, 5, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		// Only the first byte of "ü"
+		assert!(v.get_str(1, 0, 3).unwrap_err().starts_with(INVALID_UTF8_MSG));
+		assert!(shifted.get_str(1, 5, 3).unwrap_err().starts_with(INVALID_UTF8_MSG));
+	}
+
+	#[test]
+	fn test_get_enum() {
+		#[derive(Debug, PartialEq)]
+		enum Kind { Data, Ack }
+
+		impl std::convert::TryFrom<u64> for Kind {
+			type Error = String;
+			fn try_from(value: u64) -> Result<Kind> {
+				match value {
+					0 => Ok(Kind::Data),
+					1 => Ok(Kind::Ack),
+					_ => Err(s!("unknown")),
+				}
+			}
+		}
+
+		let v: Vec<u8> = vec!{ 0x12, 0x34 };
+		assert_eq!(v.get_enum::<Kind>(0, 3, 1).unwrap(), Kind::Ack);
+		assert_eq!(v.get_enum::<Kind>(0, 0, 3).unwrap(), Kind::Data);
+		assert!(v[..].get_enum::<Kind>(0, 4, 4).unwrap_err().starts_with("Invalid discriminant 2 for "));
+		assert_eq!(v.get_enum::<Kind>(1, 7, 2).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		// Any type with TryFrom<u64>
+		assert_eq!(v.get_enum::<u8>(0, 4, 8).unwrap(), 0x23);
+		assert_eq!(v.get_enum::<u8>(0, 0, 16).unwrap_err(), "Invalid discriminant 4660 for u8");
+	}
+
+	#[test]
+	fn test_set_truncate() {
+		assert_eq!(0xFFu8.set_truncate(2, 4, 0b11_0101u8).unwrap(), 0b1101_0111);
+		assert_eq!(0i64.set_truncate(0, 64, u128::MAX).unwrap(), -1);
+		assert_eq!(0u128.set_truncate(0, 128, -1i8).unwrap(), u128::MAX);
+		assert_eq!(0u8.set_truncate(0, 0, 1u8).unwrap_err(), LEN_ZERO);
+		assert_eq!(0u8.set_truncate(5, 4, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		let mut v: Vec<u8> = vec!{ 0x00; 3 };
+		v.set_truncate(0, 4, 12, 0xABCDu16).unwrap();
+		assert_eq!(v, vec!{ 0x0B, 0xCD, 0x00 });
+		// Longer than the type of the value
+		v[..].set_truncate(1, 0, 16, 0xEEu8).unwrap();
+		assert_eq!(v, vec!{ 0x0B, 0x00, 0xEE });
+		v.set_truncate(0, 0, 24, -2i32).unwrap();
+		assert_eq!(v, vec!{ 0xFF, 0xFF, 0xFE });
+		assert_eq!(v.set_truncate(2, 1, 8, 0u8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.set_truncate(0, 0, 0, 0u8).unwrap_err(), LEN_ZERO);
+	}
+
+	#[test]
+	fn test_set_signed() {
+		let mut v: Vec<u8> = vec!{ 0xFF, 0xFF };
+		v.set_signed(0, 2, 10, -5, SignednessPolicy::SignMagnitude).unwrap();
+		assert_eq!(v, vec!{ 0xE0, 0x5F });
+		v[..].set_signed(0, 0, 16, 1000, SignednessPolicy::Reject).unwrap();
+		assert_eq!(v, vec!{ 0x03, 0xE8 });
+		assert!(v.set_signed(0, 0, 3, 4, SignednessPolicy::TwosComplement).is_err());
+		assert!(v.set_signed(1, 4, 8, 1, SignednessPolicy::Reject).is_err());
+		// Nothing is modified on error
+		assert_eq!(v, vec!{ 0x03, 0xE8 });
+	}
+
+	#[test]
+	fn test_sign_magnitude_and_excess_k() {
+		let v: Vec<u8> = vec!{ 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0xFF };
+		// Negative zero
+		assert_eq!(v.get_i8_sign_magnitude(0, 0, 8).unwrap(), 0);
+		assert_eq!(v.get_i64_sign_magnitude(0, 0, 64).unwrap(), -5);
+		assert_eq!(v.get_i16_sign_magnitude(7, 5, 9).unwrap(), -127);
+		assert_eq!(v[..].get_i32_sign_magnitude(8, 0, 8).unwrap(), -127);
+		assert_eq!(v.get_i8_sign_magnitude(8, 7, 1).unwrap(), 0);
+		assert_eq!(v.get_i8_sign_magnitude(0, 0, 9).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "i8");
+		assert_eq!(v.get_i64_sign_magnitude(0, 0, 0).unwrap_err(), LEN_ZERO);
+
+		assert_eq!(v.get_i8_excess_k(8, 0, 8, 128).unwrap(), 127);
+		assert_eq!(v.get_i8_excess_k(8, 0, 8, 127).unwrap_err(), "The value 128 with the bias 127 does not fit into an i8");
+		assert_eq!(v.get_i16_excess_k(0, 0, 4, 7).unwrap(), 1);
+		assert_eq!(v.get_i32_excess_k(0, 4, 4, 7).unwrap(), -7);
+		assert_eq!(v.get_i64_excess_k(0, 0, 64, -1).unwrap_err(), "The value 9223372036854775814 with the bias -1 does not fit into an i64");
+		assert_eq!(v.get_i64_excess_k(0, 0, 64, i64::MAX).unwrap(), 6);
+		assert!(v.get_i64_excess_k(0, 0, 65, 0).is_err());
+	}
+
+	#[test]
+	fn test_set_bytes() {
+		let mut v: Vec<u8> = vec!{ 0xFF; 4 };
+		v.set_bytes(0, 3, &[0x00, 0x00]).unwrap();
+		assert_eq!(v, vec!{ 0xE0, 0x00, 0x1F, 0xFF });
+		v.set_bytes(1, 8, &[0xAB]).unwrap();
+		assert_eq!(v, vec!{ 0xE0, 0x00, 0xAB, 0xFF });
+		v[..].set_bytes(0, 0, &[]).unwrap();
+		assert_eq!(v.get_bytes::<2>(1, 4).unwrap(), [0x0A, 0xBF]);
+
+		// Nothing is modified, if the bytes exceed the vector
+		assert_eq!(v.set_bytes(2, 1, &[0x00, 0x00]).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v, vec!{ 0xE0, 0x00, 0xAB, 0xFF });
+	}
+
+	#[test]
+	fn test_fill_bits() {
+		let mut v: Vec<u8> = vec!{ 0x00; 6 };
+		v.set_bits(0, 3, 35).unwrap();
+		assert_eq!(v, vec!{ 0x1F, 0xFF, 0xFF, 0xFF, 0xFC, 0x00 });
+		v.clear_bits(1, 1, 6).unwrap();
+		assert_eq!(v[1], 0x81);
+		v.fill_bits(5, 7, 1, true).unwrap();
+		assert_eq!(v[5], 0x01);
+		assert_eq!(v.fill_bits(0, 0, 0, false).unwrap_err(), LEN_ZERO);
+		assert_eq!(v.set_bits(6, 0, 0).unwrap_err(), LEN_ZERO);
+
+		// Nothing is modified, if the range exceeds the vector
+		assert_eq!(v.fill_bits(2, 9, 24, false).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v, vec!{ 0x1F, 0x81, 0xFF, 0xFF, 0xFC, 0x01 });
+
+		let mut a = [0xFFu8; 2];
+		a[..].clear_bits(0, 6, 3).unwrap();
+		assert_eq!(a, [0xFC, 0x7F]);
+	}
+
+	#[test]
+	fn test_get_bits() {
+		// An 80 bit key at bit offset 3
+		let key: [u8; 10] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0xFE, 0xDC];
+		let mut v: Vec<u8> = vec!{ 0; 11 };
+		for (i, &b) in key.iter().enumerate() {
+			v.set(i as u32, 3, 8, b).unwrap();
+		}
+		assert_eq!(v.get_bits(0, 3, 80).unwrap(), key.to_vec());
+		assert_eq!(v[..].get_bits(0, 3, 12).unwrap(), vec!{ 0x01, 0x20 });
+		assert_eq!(v.get_bits(10, 0, 3).unwrap(), vec!{ 0b1000_0000 });
+		assert_eq!(v.get_bits(0, 9, 80).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_bits(0, 0, 0).unwrap_err(), LEN_ZERO);
+		assert_eq!(v.get_bits(0, u32::MAX, 2).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+
+	#[test]
+	fn test_bcd() {
+		// A 33 bit MPEG-like timestamp area with 9 digits starting at bit 7
+		let mut v: Vec<u8> = vec!{ 0xFF; 6 };
+		v.set_bcd(0, 7, 9, 123_456_789).unwrap();
+		assert_eq!(v, vec!{ 0xFE, 0x24, 0x68, 0xAC, 0xF1, 0x3F });
+		assert_eq!(v.get_bcd(0, 7, 9).unwrap(), 123_456_789);
+		assert_eq!(v[..].get_bcd(1, 0, 2).unwrap(), 24);
+		assert_eq!(v.get_bcd(0, 0, 2).unwrap_err(), BCD_DIGIT_MSG);
+		assert_eq!(v.get_bcd(0, 7, 11).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_bcd(0, 0, 20).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u64");
+		assert!(v.set_bcd(0, 7, 2, 100).is_err());
+		assert!(v.set_bcd(0, 0, 20, 1).is_err());
+
+		let mut w: Vec<u8> = vec!{ 0; 10 };
+		w.set_bcd(0, 4, 19, 9_999_999_999_999_999_999).unwrap();
+		assert_eq!(w.get_bcd(0, 4, 19).unwrap(), 9_999_999_999_999_999_999);
+		assert!(w.set_bcd(0, 4, 19, u64::MAX).is_err());
+	}
+
+	#[test]
+	fn test_fixed_point() {
+		let mut v: Vec<u8> = vec!{ 0; 9 };
+		v.set_fixed_point(0, 3, 1, 15, true, -0.5).unwrap();
+		assert_eq!(v.get_i16(0, 3, 16).unwrap(), -0x4000);
+		assert_eq!(v.get_fixed_point(0, 3, 1, 15, true).unwrap(), -0.5);
+		assert_eq!(v.get_fixed_point(0, 3, 1, 15, false).unwrap(), 1.5);
+
+		// Rounding to the nearest step of 1/16
+		v.set_fixed_point(2, 0, 4, 4, false, 3.1).unwrap();
+		assert_eq!(v[2], 0x32);
+		assert_eq!(v[..].get_fixed_point(2, 0, 4, 4, false).unwrap(), 3.125);
+
+		// Pure fractions, pure integers and all 64 bits
+		v.set_fixed_point(3, 0, 0, 8, false, 0.75).unwrap();
+		assert_eq!(v.get_fixed_point(3, 0, 0, 8, false).unwrap(), 0.75);
+		assert_eq!(v.get_fixed_point(3, 0, 8, 0, true).unwrap(), -64.0);
+		v.set_fixed_point(1, 0, 32, 32, true, -12345.5).unwrap();
+		assert_eq!(v.get_fixed_point(1, 0, 32, 32, true).unwrap(), -12345.5);
+
+		assert!(v.set_fixed_point(0, 0, 4, 4, false, 16.0).is_err());
+		assert!(v.set_fixed_point(0, 0, 4, 4, false, -0.1).is_err());
+		assert!(v.set_fixed_point(0, 0, 4, 4, true, 7.97).is_err());
+		v.set_fixed_point(0, 0, 4, 4, true, -8.0).unwrap();
+		assert!(v.set_fixed_point(0, 0, 4, 4, true, f64::NAN).is_err());
+		assert_eq!(v.set_fixed_point(0, 0, 40, 25, true, 1.0).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u64");
+		assert_eq!(v.set_fixed_point(0, 0, 0, 0, true, 0.0).unwrap_err(), LEN_ZERO);
+		assert_eq!(v.get_fixed_point(0, 0, 0, 0, true).unwrap_err(), LEN_ZERO);
+		assert!(v.get_fixed_point(0, 0, u32::MAX, 1, true).is_err());
+	}
+
+	#[test]
+	fn test_128_bit_types() {
+		let a: u128 = 0x2001_0DB8_0000_0000_0000_0000_0000_0001;
+		assert_eq!(a.get_u16(0, 16).unwrap(), 0x2001);
+		assert_eq!(a.get_u128(16, 112).unwrap(), 0x0DB8_0000_0000_0000_0000_0000_0001);
+		assert_eq!(a.get_i128(0, 3).unwrap(), 1);
+		assert_eq!((-1i128).get_i128(100, 28).unwrap(), -1);
+		assert_eq!((-1i128).get_u64(64, 64).unwrap(), u64::MAX);
+		assert_eq!(a.get_u64(0, 65).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u64");
+		assert_eq!(a.get_u8(127, 2).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(0xABu8.get_u128(0, 8).unwrap(), 0xAB);
+
+		assert!(a.get_bit(127).unwrap());
+		assert_eq!(0u128.set_bit(0).unwrap(), 1 << 127);
+		assert_eq!((-1i128).clear_bit(0).unwrap(), i128::MAX);
+		assert_eq!(a.get_bit(128).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		assert_eq!(0u128.set(0, 128, u128::MAX).unwrap(), u128::MAX);
+		assert_eq!(0u128.set(60, 8, 0xFFu8).unwrap(), 0xFF << 60);
+		assert_eq!(0i128.set(0, 100, i128::MIN >> 28).unwrap(), 1 << 127);
+		assert!(0u128.set(0, 64, 1u128 << 64).is_err());
+		assert!(0u128.set(0, 100, i128::MIN >> 27).is_err());
+
+		// An IPv6 address at an unaligned position
+		let mut v: Vec<u8> = vec!{ 0; 18 };
+		v.set(0, 4, 128, a).unwrap();
+		assert_eq!(&v[..3], &[0x02, 0x00, 0x10]);
+		assert_eq!(v.get_u128(0, 4, 128).unwrap(), a);
+		assert_eq!(v.get_u128(0, 4, 16).unwrap(), 0x2001);
+		assert_eq!(v.get_i128(0, 4, 128).unwrap(), a as i128);
+		assert_eq!(v.get_i128(16, 0, 4).unwrap(), 1);
+		assert_eq!(v.get_i128(16, 0, 16).unwrap(), 0x1000);
+		v.set(0, 0, 72, -2i128).unwrap();
+		assert_eq!(v.get_i128(0, 0, 72).unwrap(), -2);
+		assert_eq!(v.get_u128(0, 0, 129).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u128");
+		assert_eq!(v.get_u128(2, 1, 128).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(1u128.type_of(), "u128");
+		assert!((-1i128).is_signed());
+	}
+
+	#[cfg(feature = "tracing")]
+	#[test]
+	fn tracing_of_get_and_set() {
+		use std::sync::{Arc, Mutex};
+		use tracing::{span, Event, Metadata, Subscriber};
+
+		// Collects the names of the spans and counts the events
+		struct Collector(Arc<Mutex<(Vec<String>, usize)>>);
+
+		impl Subscriber for Collector {
+			fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+			fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+				let mut log = self.0.lock().unwrap();
+				log.0.push(s!(span.metadata().name()));
+				span::Id::from_u64(log.0.len() as u64)
+			}
+			fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+			fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+			fn event(&self, _: &Event<'_>) { self.0.lock().unwrap().1 += 1; }
+			fn enter(&self, _: &span::Id) {}
+			fn exit(&self, _: &span::Id) {}
+		}
+
+		let log = Arc::new(Mutex::new((Vec::new(), 0)));
+		tracing::subscriber::with_default(Collector(log.clone()), || {
+			let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+			assert_eq!(v.get_u16(1, 7, 3).unwrap(), 5);
+			assert!(v.get_u8(5, 0, 1).is_err());
+			v.set(1, 15, 2, 3u8).unwrap();
+		});
+
+		let log = log.lock().unwrap();
+		assert_eq!(log.0, vec!{ s!("get_u16"), s!("get_u8"), s!("set") });
+		// One event with the return value or the error per call
+		assert_eq!(log.1, 3);
+	}
 }
@@ -0,0 +1,788 @@
+//! Line codes used on serial links
+//!
+//! - **4b/5b** as used by FDDI and 100BASE-X. Each nibble is mapped to a 5 bit code group.
+//!   The high nibble of a byte is encoded first.
+//! - **8b/10b** as used by Fibre Channel, PCIe 1.x/2.x, SATA and Gigabit Ethernet.
+//!   Each byte is mapped to a 10 bit code group under control of the running disparity.
+//! - **64b/66b** as used by 10GBASE-R. Each 64 bit payload is prefixed by a 2 bit sync header.
+//!   The payloads are scrambled with the self synchronizing polynomial x^58 + x^39 + 1.
+//! - **Manchester** as used by 10BASE-T, RFID tags and many radio protocols. Each bit is sent as
+//!   two half bits with a transition in the middle.
+//! - **Differential Manchester** as used by Token Ring and AES3. A zero has an additional
+//!   transition at the start of the bit.
+//! - **NRZI** as used by FDDI and USB. The level changes for each one (or each zero).
+//!
+//! The code groups are written into a [`BitBuffer`](../struct.BitBuffer.html) in transmission
+//! order, i.e. the first transmitted bit (bit "a" of an 8b/10b code group) has the lowest bit index.
+//! The 4b/5b and 8b/10b codes can also be written with a [`BitWriter`](../struct.BitWriter.html)
+//! and read with a [`BitReader`](../struct.BitReader.html), e.g. after some bits of a capture have
+//! been skipped.
+
+use std::collections::HashMap;
+use super::{Result, BitBuffer, BitReader, BitWriter};
+
+static INVALID_CODE_GROUP_MSG: &str = "Invalid code group";
+static DISPARITY_ERROR_MSG: &str = "Running disparity error";
+static INCOMPLETE_CODE_GROUP_MSG: &str = "The number of bits is not a multiple of the code group size";
+static INVALID_CONTROL_SYMBOL_MSG: &str = "Invalid control symbol ";
+static INVALID_SYNC_HEADER_MSG: &str = "Invalid sync header";
+
+/// The 4b/5b code groups for the nibbles 0x0 to 0xF
+static CODE_4B5B: [u8; 16] = [
+	0b11110, 0b01001, 0b10100, 0b10101, 0b01010, 0b01011, 0b01110, 0b01111,
+	0b10010, 0b10011, 0b10110, 0b10111, 0b11010, 0b11011, 0b11100, 0b11101,
+];
+
+/// Encodes each byte as two 4b/5b code groups, the high nibble first
+pub fn encode_4b5b(source: &[u8]) -> BitBuffer {
+	let mut result = BitBuffer::new();
+	for byte in source {
+		// Cannot fail, since the length is always 5
+		let _ = result.push_bits(CODE_4B5B[(byte >> 4) as usize] as u64, 5);
+		let _ = result.push_bits(CODE_4B5B[(byte & 0x0F) as usize] as u64, 5);
+	}
+	result
+}
+
+/// Decodes pairs of 4b/5b code groups into bytes.
+///
+/// Returns an error if a code group is not a data code group (e.g. the idle or
+/// delimiter symbols) or if the number of bits is not a multiple of 10.
+pub fn decode_4b5b(source: &BitBuffer) -> Result<Vec<u8>> {
+	if !source.bit_len().is_multiple_of(10) {
+		return Err(s!(INCOMPLETE_CODE_GROUP_MSG));
+	}
+
+	let mut result = Vec::with_capacity(source.bit_len() / 10);
+	let mut nibbles = [0u8; 2];
+	for i in 0..source.bit_len() / 10 {
+		for (j, nibble) in nibbles.iter_mut().enumerate() {
+			let symbol = i * 2 + j;
+			let code = source.read_bits(symbol * 5, 5)? as u8;
+			*nibble = match CODE_4B5B.iter().position(|&c| c == code) {
+				Some(n) => n as u8,
+				None => return Err(format!("{} at symbol {}", INVALID_CODE_GROUP_MSG, symbol)),
+			};
+		}
+		result.push(nibbles[0] << 4 | nibbles[1]);
+	}
+	Ok(result)
+}
+
+/// The mapping of bits to the pairs of half bits of the Manchester code
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Manchester {
+	/// IEEE 802.3: A zero is sent as 10, a one as 01
+	Ieee8023,
+	/// G. E. Thomas: A zero is sent as 01, a one as 10
+	Thomas,
+}
+
+/// Encodes each bit as two half bits
+///
+/// ```rust
+/// use bitlab::*;
+/// use bitlab::linecode::*;
+/// let data = BitBuffer::from_bools(&[true, false, false]);
+/// let coded = encode_manchester(&data, Manchester::Ieee8023);
+/// assert_eq!(coded.read_bits(0, 6).unwrap(), 0b01_10_10);
+/// assert_eq!(decode_manchester(&coded, Manchester::Ieee8023).unwrap(), data);
+/// ```
+pub fn encode_manchester(source: &BitBuffer, convention: Manchester) -> BitBuffer {
+	let mut result = BitBuffer::new();
+	for bit in source.to_bools() {
+		let first = (convention == Manchester::Thomas) == bit;
+		result.push_bit(first);
+		result.push_bit(!first);
+	}
+	result
+}
+
+/// Decodes pairs of half bits.
+///
+/// Returns an error if a pair has no transition in the middle or if the number of bits is odd.
+pub fn decode_manchester(source: &BitBuffer, convention: Manchester) -> Result<BitBuffer> {
+	if !source.bit_len().is_multiple_of(2) {
+		return Err(s!(INCOMPLETE_CODE_GROUP_MSG));
+	}
+
+	let mut result = BitBuffer::new();
+	for i in 0..source.bit_len() / 2 {
+		let first = source.get_bit(i * 2)?;
+		if first == source.get_bit(i * 2 + 1)? {
+			return Err(format!("{} at symbol {}", INVALID_CODE_GROUP_MSG, i));
+		}
+		result.push_bit((convention == Manchester::Thomas) == first);
+	}
+	Ok(result)
+}
+
+/// Encodes each bit as two half bits with a transition in the middle. A zero has an additional
+/// transition at the start. initial_level is the level before the first bit.
+pub fn encode_differential_manchester(source: &BitBuffer, initial_level: bool) -> BitBuffer {
+	let mut result = BitBuffer::new();
+	let mut level = initial_level;
+	for bit in source.to_bools() {
+		if !bit {
+			level = !level;
+		}
+		result.push_bit(level);
+		level = !level;
+		result.push_bit(level);
+	}
+	result
+}
+
+/// Decodes a differential Manchester code, whose level before the first bit is initial_level.
+///
+/// Returns an error if a pair has no transition in the middle or if the number of bits is odd.
+///
+/// ```rust
+/// use bitlab::*;
+/// use bitlab::linecode::*;
+/// let data = BitBuffer::from(vec!{ 0xA5 });
+/// let coded = encode_differential_manchester(&data, false);
+/// assert_eq!(decode_differential_manchester(&coded, false).unwrap(), data);
+/// // The code does not depend on the polarity
+/// let inverted = encode_differential_manchester(&data, true);
+/// assert_eq!(decode_differential_manchester(&inverted, true).unwrap(), data);
+/// ```
+pub fn decode_differential_manchester(source: &BitBuffer, initial_level: bool) -> Result<BitBuffer> {
+	if !source.bit_len().is_multiple_of(2) {
+		return Err(s!(INCOMPLETE_CODE_GROUP_MSG));
+	}
+
+	let mut result = BitBuffer::new();
+	let mut level = initial_level;
+	for i in 0..source.bit_len() / 2 {
+		let first = source.get_bit(i * 2)?;
+		let second = source.get_bit(i * 2 + 1)?;
+		if first == second {
+			return Err(format!("{} at symbol {}", INVALID_CODE_GROUP_MSG, i));
+		}
+		result.push_bit(first == level);
+		level = second;
+	}
+	Ok(result)
+}
+
+/// Encodes the bits as levels, where a one is sent as a change of the level (NRZ-M).
+/// initial_level is the level before the first bit. For USB, where a zero changes
+/// the level, encode the inverted bits.
+///
+/// ```rust
+/// use bitlab::*;
+/// use bitlab::linecode::*;
+/// let data = BitBuffer::from_bools(&[true, true, false, true]);
+/// let coded = encode_nrzi(&data, false);
+/// assert_eq!(coded.to_bools(), vec!{ true, false, false, true });
+/// assert_eq!(decode_nrzi(&coded, false), data);
+/// ```
+pub fn encode_nrzi(source: &BitBuffer, initial_level: bool) -> BitBuffer {
+	let mut level = initial_level;
+	source.to_bools().into_iter().map(|bit| { level ^= bit; level }).collect()
+}
+
+/// Decodes levels, where a change of the level is a one. initial_level is the level before the first bit.
+pub fn decode_nrzi(source: &BitBuffer, initial_level: bool) -> BitBuffer {
+	let mut level = initial_level;
+	source.to_bools().into_iter().map(|bit| { let changed = bit != level; level = bit; changed }).collect()
+}
+
+/// Writes each byte as two 4b/5b code groups, the high nibble first
+pub fn write_4b5b(writer: &mut BitWriter, source: &[u8]) {
+	for byte in source {
+		// Cannot fail, since the length is always 5
+		let _ = writer.write_bits(CODE_4B5B[(byte >> 4) as usize] as u64, 5);
+		let _ = writer.write_bits(CODE_4B5B[(byte & 0x0F) as usize] as u64, 5);
+	}
+}
+
+/// Reads count bytes, each of them as two 4b/5b code groups.
+///
+/// Returns an error if a code group is not a data code group or if there are not enough bits.
+/// In this case, the position of the reader does not change.
+///
+/// ```rust
+/// use bitlab::*;
+/// use bitlab::linecode::*;
+/// let mut w = BitWriter::new();
+/// w.write_bits(0b101, 3).unwrap();
+/// write_4b5b(&mut w, &[0x12, 0x34]);
+/// let bytes = w.into_buffer().into_bytes();
+/// let mut r = BitReader::new(&bytes);
+/// r.skip_bits(3).unwrap();
+/// assert_eq!(read_4b5b(&mut r, 2).unwrap(), vec!{ 0x12, 0x34 });
+/// assert!(read_4b5b(&mut r, 1).is_err());
+/// ```
+pub fn read_4b5b(reader: &mut BitReader, count: usize) -> Result<Vec<u8>> {
+	let mut r = reader.clone();
+	let mut result = Vec::with_capacity(count);
+	for i in 0..count {
+		let mut byte = 0;
+		for j in 0..2 {
+			let code = r.read_bits(5)? as u8;
+			byte = match CODE_4B5B.iter().position(|&c| c == code) {
+				Some(n) => byte << 4 | n as u8,
+				None => return Err(format!("{} at symbol {}", INVALID_CODE_GROUP_MSG, i * 2 + j)),
+			};
+		}
+		result.push(byte);
+	}
+	*reader = r;
+	Ok(result)
+}
+
+/// A symbol of the 8b/10b code
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symbol8b10b {
+	/// A data byte D.x.y
+	Data(u8),
+	/// A control symbol K.x.y. Only K.28.0 to K.28.7, K.23.7, K.27.7, K.29.7 and K.30.7 are valid.
+	Control(u8),
+}
+
+/// 5b/6b code groups (abcdei) for the running disparity -1. None means that the code is the same for both disparities
+static CODE_5B6B: [(u8, Option<u8>); 32] = [
+	(0b100111, Some(0b011000)), (0b011101, Some(0b100010)), (0b101101, Some(0b010010)), (0b110001, None),
+	(0b110101, Some(0b001010)), (0b101001, None), (0b011001, None), (0b111000, Some(0b000111)),
+	(0b111001, Some(0b000110)), (0b100101, None), (0b010101, None), (0b110100, None),
+	(0b001101, None), (0b101100, None), (0b011100, None), (0b010111, Some(0b101000)),
+	(0b011011, Some(0b100100)), (0b100011, None), (0b010011, None), (0b110010, None),
+	(0b001011, None), (0b101010, None), (0b011010, None), (0b111010, Some(0b000101)),
+	(0b110011, Some(0b001100)), (0b100110, None), (0b010110, None), (0b110110, Some(0b001001)),
+	(0b001110, None), (0b101110, Some(0b010001)), (0b011110, Some(0b100001)), (0b101011, Some(0b010100)),
+];
+
+/// 3b/4b code groups (fghj) for data symbols. Index 8 is the alternate code A7.
+static CODE_3B4B_DATA: [(u8, Option<u8>); 9] = [
+	(0b1011, Some(0b0100)), (0b1001, None), (0b0101, None), (0b1100, Some(0b0011)),
+	(0b1101, Some(0b0010)), (0b1010, None), (0b0110, None), (0b1110, Some(0b0001)),
+	(0b0111, Some(0b1000)),
+];
+
+/// 3b/4b code groups (fghj) for control symbols
+static CODE_3B4B_CONTROL: [(u8, Option<u8>); 8] = [
+	(0b1011, Some(0b0100)), (0b0110, Some(0b1001)), (0b1010, Some(0b0101)), (0b1100, Some(0b0011)),
+	(0b1101, Some(0b0010)), (0b0101, Some(0b1010)), (0b1001, Some(0b0110)), (0b0111, Some(0b1000)),
+];
+
+/// Selects the code group for the current disparity and returns it with the new disparity
+fn select(codes: (u8, Option<u8>), width: u32, rd_positive: bool) -> (u8, bool) {
+	let code = match codes {
+		(_, Some(alternative)) if rd_positive => alternative,
+		(code, _) => code,
+	};
+
+	let ones = code.count_ones() * 2;
+	let rd_positive = if ones > width { true } else if ones < width { false } else { rd_positive };
+	(code, rd_positive)
+}
+
+/// Encodes a single symbol and returns the 10 bit code group (abcdei fghj) and the new running disparity
+fn encode_symbol(symbol: Symbol8b10b, rd_positive: bool) -> Result<(u16, bool)> {
+	let (value, control) = match symbol {
+		Symbol8b10b::Data(v) => (v, false),
+		Symbol8b10b::Control(v) => (v, true),
+	};
+
+	let x = (value & 0x1F) as usize;
+	let y = (value >> 5) as usize;
+
+	if control && x != 28 && !(y == 7 && (x == 23 || x == 27 || x == 29 || x == 30)) {
+		return Err(s!(INVALID_CONTROL_SYMBOL_MSG) + &format!("K.{}.{}", x, y));
+	}
+
+	let codes_6b = if control && x == 28 { (0b001111, Some(0b110000)) } else { CODE_5B6B[x] };
+	let (code_6b, rd_positive) = select(codes_6b, 6, rd_positive);
+
+	let codes_4b = if control {
+		CODE_3B4B_CONTROL[y]
+	} else if y == 7 && ((!rd_positive && (x == 17 || x == 18 || x == 20)) || (rd_positive && (x == 11 || x == 13 || x == 14))) {
+		// Use the alternate code to avoid a run of five identical bits
+		CODE_3B4B_DATA[8]
+	} else {
+		CODE_3B4B_DATA[y]
+	};
+	let (code_4b, rd_positive) = select(codes_4b, 4, rd_positive);
+
+	Ok(((code_6b as u16) << 4 | code_4b as u16, rd_positive))
+}
+
+/// Encodes symbols into 8b/10b code groups and keeps track of the running disparity
+#[derive(Clone, Debug, Default)]
+pub struct Encoder8b10b {
+	rd_positive: bool,
+}
+
+impl Encoder8b10b {
+	/// Creates an encoder with the initial running disparity -1
+	pub fn new() -> Encoder8b10b {
+		Encoder8b10b { rd_positive: false }
+	}
+
+	/// Returns the current running disparity (-1 or +1)
+	pub fn running_disparity(&self) -> i8 {
+		if self.rd_positive { 1 } else { -1 }
+	}
+
+	/// Appends the 10 bit code group of a symbol to target
+	pub fn encode(&mut self, symbol: Symbol8b10b, target: &mut BitBuffer) -> Result<()> {
+		let (code, rd_positive) = encode_symbol(symbol, self.rd_positive)?;
+		self.rd_positive = rd_positive;
+		target.push_bits(code as u64, 10)
+	}
+
+	/// Writes the 10 bit code group of a symbol with a BitWriter
+	pub fn write(&mut self, symbol: Symbol8b10b, writer: &mut BitWriter) -> Result<()> {
+		let (code, rd_positive) = encode_symbol(symbol, self.rd_positive)?;
+		self.rd_positive = rd_positive;
+		writer.write_bits(code as u64, 10)
+	}
+}
+
+/// Decodes 8b/10b code groups, keeps track of the running disparity and reports
+/// invalid code groups as well as running disparity errors
+#[derive(Clone, Debug)]
+pub struct Decoder8b10b {
+	rd_positive: bool,
+	// Code group --> (symbol, valid for RD -1, valid for RD +1)
+	table: HashMap<u16, (Symbol8b10b, bool, bool)>,
+}
+
+impl Default for Decoder8b10b {
+	fn default() -> Decoder8b10b {
+		Decoder8b10b::new()
+	}
+}
+
+impl Decoder8b10b {
+	/// Creates a decoder with the initial running disparity -1
+	pub fn new() -> Decoder8b10b {
+		let mut table = HashMap::new();
+		let symbols = (0..=255u8).map(Symbol8b10b::Data).chain((0..=255u8).map(Symbol8b10b::Control));
+		for symbol in symbols {
+			for &rd_positive in &[false, true] {
+				if let Ok((code, _)) = encode_symbol(symbol, rd_positive) {
+					let entry = table.entry(code).or_insert((symbol, false, false));
+					if rd_positive { entry.2 = true; } else { entry.1 = true; }
+				}
+			}
+		}
+		Decoder8b10b { rd_positive: false, table }
+	}
+
+	/// Returns the current running disparity (-1 or +1)
+	pub fn running_disparity(&self) -> i8 {
+		if self.rd_positive { 1 } else { -1 }
+	}
+
+	/// Decodes a single 10 bit code group (abcdei fghj, where a is the most significant of the 10 bits).
+	///
+	/// The running disparity is updated even if the code group violates it,
+	/// so that decoding can continue after reporting the error.
+	pub fn decode(&mut self, code: u16) -> Result<Symbol8b10b> {
+		let (symbol, valid_negative, valid_positive) = match self.table.get(&code) {
+			Some(&entry) => entry,
+			None => return Err(s!(INVALID_CODE_GROUP_MSG)),
+		};
+
+		let valid = if self.rd_positive { valid_positive } else { valid_negative };
+
+		let ones = code.count_ones();
+		if ones != 5 {
+			self.rd_positive = ones > 5;
+		}
+
+		if valid { Ok(symbol) } else { Err(s!(DISPARITY_ERROR_MSG)) }
+	}
+
+	/// Reads and decodes the next 10 bit code group from a BitReader.
+	///
+	/// Like the running disparity, the reader moves on even if the code group is invalid,
+	/// so that decoding can continue after reporting the error. Only if there are less than
+	/// 10 bits left, the position does not change.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// use bitlab::linecode::*;
+	/// let mut w = BitWriter::new();
+	/// let mut encoder = Encoder8b10b::new();
+	/// encoder.write(Symbol8b10b::Control(0xBC), &mut w).unwrap();
+	/// encoder.write(Symbol8b10b::Data(0x4A), &mut w).unwrap();
+	/// let bytes = w.into_buffer().into_bytes();
+	///
+	/// let mut r = BitReader::new(&bytes);
+	/// let mut decoder = Decoder8b10b::new();
+	/// assert_eq!(decoder.read(&mut r).unwrap(), Symbol8b10b::Control(0xBC));
+	/// assert_eq!(decoder.read(&mut r).unwrap(), Symbol8b10b::Data(0x4A));
+	/// assert_eq!(encoder.running_disparity(), decoder.running_disparity());
+	/// ```
+	pub fn read(&mut self, reader: &mut BitReader) -> Result<Symbol8b10b> {
+		let code = reader.read_bits(10)? as u16;
+		self.decode(code)
+	}
+}
+
+/// Encodes data bytes as 8b/10b code groups, starting with the running disparity -1
+pub fn encode_8b10b(source: &[u8]) -> BitBuffer {
+	let mut encoder = Encoder8b10b::new();
+	let mut result = BitBuffer::new();
+	for &byte in source {
+		// Data symbols are always valid
+		let _ = encoder.encode(Symbol8b10b::Data(byte), &mut result);
+	}
+	result
+}
+
+/// Decodes 8b/10b code groups, starting with the running disparity -1.
+///
+/// The error message contains the index of the first invalid code group.
+pub fn decode_8b10b(source: &BitBuffer) -> Result<Vec<Symbol8b10b>> {
+	if !source.bit_len().is_multiple_of(10) {
+		return Err(s!(INCOMPLETE_CODE_GROUP_MSG));
+	}
+
+	let mut decoder = Decoder8b10b::new();
+	let mut result = Vec::with_capacity(source.bit_len() / 10);
+	for i in 0..source.bit_len() / 10 {
+		let code = source.read_bits(i * 10, 10)? as u16;
+		match decoder.decode(code) {
+			Ok(symbol) => result.push(symbol),
+			Err(e) => return Err(format!("{} at symbol {}", e, i)),
+		}
+	}
+	Ok(result)
+}
+
+/// The 2 bit sync header of a 64b/66b block
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SyncHeader {
+	/// 01: The payload contains 8 data octets
+	Data,
+	/// 10: The payload starts with a block type field followed by control and data characters
+	Control,
+}
+
+impl SyncHeader {
+	fn bits(self) -> u64 {
+		match self {
+			SyncHeader::Data => 0b01,
+			SyncHeader::Control => 0b10,
+		}
+	}
+}
+
+/// Frames 64 bit payloads into 66 bit blocks by prepending the sync headers.
+///
+/// The payloads are written with the most significant bit first and are not scrambled.
+/// Use a [`Scrambler64b66b`](struct.Scrambler64b66b.html) on the payloads before framing, if needed.
+pub fn frame_64b66b(blocks: &[(SyncHeader, u64)]) -> BitBuffer {
+	let mut result = BitBuffer::new();
+	for &(header, payload) in blocks {
+		// Cannot fail, since the lengths are at most 64
+		let _ = result.push_bits(header.bits(), 2);
+		let _ = result.push_bits(payload, 64);
+	}
+	result
+}
+
+/// Splits a bit stream into 66 bit blocks starting at bit_offset and returns the sync headers and payloads.
+///
+/// Returns an error for an invalid sync header (00 or 11). Incomplete blocks at the end are ignored.
+pub fn deframe_64b66b(source: &BitBuffer, bit_offset: usize) -> Result<Vec<(SyncHeader, u64)>> {
+	let mut result = Vec::new();
+	let mut pos = bit_offset;
+	while pos + 66 <= source.bit_len() {
+		let header = match source.read_bits(pos, 2)? {
+			0b01 => SyncHeader::Data,
+			0b10 => SyncHeader::Control,
+			_ => return Err(format!("{} at block {}", INVALID_SYNC_HEADER_MSG, result.len())),
+		};
+		result.push((header, source.read_bits(pos + 2, 64)?));
+		pos += 66;
+	}
+	Ok(result)
+}
+
+/// Searches the first bit offset (0 to 65), at which n_blocks consecutive blocks have valid sync headers.
+///
+/// This is the block lock procedure of a receiver, which does not know the block boundaries.
+pub fn lock_64b66b(source: &BitBuffer, n_blocks: usize) -> Option<usize> {
+	(0..66).find(|&offset| {
+		offset + n_blocks * 66 <= source.bit_len() &&
+		(0..n_blocks).all(|i| {
+			let header = source.read_bits(offset + i * 66, 2).unwrap_or(0);
+			header == 0b01 || header == 0b10
+		})
+	})
+}
+
+/// The self synchronizing scrambler with the polynomial x^58 + x^39 + 1 used by 64b/66b
+#[derive(Clone, Debug)]
+pub struct Scrambler64b66b {
+	// The last 58 output bits, the most recent one is the least significant bit
+	state: u64,
+}
+
+impl Default for Scrambler64b66b {
+	fn default() -> Scrambler64b66b {
+		Scrambler64b66b::new()
+	}
+}
+
+impl Scrambler64b66b {
+	/// Creates a scrambler, whose state is all ones
+	pub fn new() -> Scrambler64b66b {
+		Scrambler64b66b::with_state(u64::MAX)
+	}
+
+	/// Creates a scrambler with a given state. Only the 58 least significant bits are used.
+	pub fn with_state(state: u64) -> Scrambler64b66b {
+		Scrambler64b66b { state: state & STATE_MASK_58 }
+	}
+
+	/// Scrambles a single bit
+	pub fn scramble_bit(&mut self, bit: bool) -> bool {
+		let out = bit ^ feedback_58(self.state);
+		self.state = (self.state << 1 | out as u64) & STATE_MASK_58;
+		out
+	}
+
+	/// Scrambles the 64 bits of a block payload, the most significant bit first
+	pub fn scramble_u64(&mut self, payload: u64) -> u64 {
+		(0..64).rev().fold(0, |acc, i| acc << 1 | self.scramble_bit(payload & (1 << i) != 0) as u64)
+	}
+
+	/// Scrambles all bits of a buffer
+	pub fn scramble(&mut self, source: &BitBuffer) -> BitBuffer {
+		let mut result = BitBuffer::new();
+		for i in 0..source.bit_len() {
+			result.push_bit(self.scramble_bit(source.get_bit(i).unwrap_or(false)));
+		}
+		result
+	}
+}
+
+/// The descrambler for the polynomial x^58 + x^39 + 1 used by 64b/66b.
+///
+/// Since the descrambler is self synchronizing, its output is correct after the first 58 bits,
+/// regardless of the initial state.
+#[derive(Clone, Debug)]
+pub struct Descrambler64b66b {
+	// The last 58 input bits, the most recent one is the least significant bit
+	state: u64,
+}
+
+impl Default for Descrambler64b66b {
+	fn default() -> Descrambler64b66b {
+		Descrambler64b66b::new()
+	}
+}
+
+impl Descrambler64b66b {
+	/// Creates a descrambler, whose state is all ones
+	pub fn new() -> Descrambler64b66b {
+		Descrambler64b66b::with_state(u64::MAX)
+	}
+
+	/// Creates a descrambler with a given state. Only the 58 least significant bits are used.
+	pub fn with_state(state: u64) -> Descrambler64b66b {
+		Descrambler64b66b { state: state & STATE_MASK_58 }
+	}
+
+	/// Descrambles a single bit
+	pub fn descramble_bit(&mut self, bit: bool) -> bool {
+		let out = bit ^ feedback_58(self.state);
+		self.state = (self.state << 1 | bit as u64) & STATE_MASK_58;
+		out
+	}
+
+	/// Descrambles the 64 bits of a block payload, the most significant bit first
+	pub fn descramble_u64(&mut self, payload: u64) -> u64 {
+		(0..64).rev().fold(0, |acc, i| acc << 1 | self.descramble_bit(payload & (1 << i) != 0) as u64)
+	}
+
+	/// Descrambles all bits of a buffer
+	pub fn descramble(&mut self, source: &BitBuffer) -> BitBuffer {
+		let mut result = BitBuffer::new();
+		for i in 0..source.bit_len() {
+			result.push_bit(self.descramble_bit(source.get_bit(i).unwrap_or(false)));
+		}
+		result
+	}
+}
+
+const STATE_MASK_58: u64 = (1 << 58) - 1;
+
+/// The taps of x^58 + x^39 + 1 are the bits sent 58 and 39 bits ago
+#[inline]
+fn feedback_58(state: u64) -> bool {
+	((state >> 57) ^ (state >> 38)) & 1 == 1
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn coding_4b5b() {
+		let coded = encode_4b5b(&[0x48, 0x0F]);
+		// 0x4 = 01010, 0x8 = 10010, 0x0 = 11110, 0xF = 11101
+		assert_eq!(coded.read_bits(0, 20).unwrap(), 0b01010_10010_11110_11101);
+		assert_eq!(decode_4b5b(&coded).unwrap(), vec!{ 0x48, 0x0F });
+
+		// 11111 is the idle symbol
+		let mut idle = BitBuffer::new();
+		idle.push_bits(0b01010_11111, 10).unwrap();
+		match decode_4b5b(&idle) {
+			Ok(_) => panic!("Missed the invalid code group"),
+			Err(e) => assert_eq!(e, "Invalid code group at symbol 1"),
+		}
+	}
+
+	#[test]
+	fn manchester_and_nrzi() {
+		let data = BitBuffer::from_bools(&[false, true, true, false, true]);
+
+		let coded = encode_manchester(&data, Manchester::Thomas);
+		assert_eq!(coded.read_bits(0, 10).unwrap(), 0b01_10_10_01_10);
+		assert_eq!(decode_manchester(&coded, Manchester::Thomas).unwrap(), data);
+		assert_eq!(decode_manchester(&coded, Manchester::Ieee8023).unwrap().to_bools(), vec!{ true, false, false, true, false });
+		let mut bad = coded.clone();
+		bad.push_bits(0b11, 2).unwrap();
+		assert_eq!(decode_manchester(&bad, Manchester::Thomas).unwrap_err(), "Invalid code group at symbol 5");
+		bad.push_bit(true);
+		assert_eq!(decode_manchester(&bad, Manchester::Thomas).unwrap_err(), INCOMPLETE_CODE_GROUP_MSG);
+
+		// Starting at the low level, only the zeros begin with a transition
+		let coded = encode_differential_manchester(&data, false);
+		assert_eq!(coded.read_bits(0, 10).unwrap(), 0b10_01_10_10_01);
+		assert_eq!(decode_differential_manchester(&coded, false).unwrap(), data);
+		assert!(decode_differential_manchester(&BitBuffer::from_bools(&[true, true]), false).is_err());
+
+		// A one changes the level
+		let coded = encode_nrzi(&data, true);
+		assert_eq!(coded.to_bools(), vec!{ true, false, true, true, false });
+		assert_eq!(decode_nrzi(&coded, true), data);
+		assert!(encode_nrzi(&BitBuffer::new(), false).is_empty());
+	}
+
+	#[test]
+	fn coding_with_reader_and_writer() {
+		let mut w = BitWriter::new();
+		write_4b5b(&mut w, &[0xA5]);
+		let mut encoder = Encoder8b10b::new();
+		for &byte in &[0x00u8, 0xFF, 0x3C] {
+			encoder.write(Symbol8b10b::Data(byte), &mut w).unwrap();
+		}
+		assert!(encoder.write(Symbol8b10b::Control(0x01), &mut w).is_err());
+		assert_eq!(w.bit_len(), 40);
+		let buffer = w.into_buffer();
+		assert_eq!(buffer.read_bits(10, 30).unwrap(), encode_8b10b(&[0x00, 0xFF, 0x3C]).read_bits(0, 30).unwrap());
+
+		let bytes = buffer.into_bytes();
+		let mut r = BitReader::new(&bytes);
+		assert_eq!(read_4b5b(&mut r, 1).unwrap(), vec!{ 0xA5 });
+		let mut decoder = Decoder8b10b::new();
+		let symbols: Vec<Symbol8b10b> = (0..3).map(|_| decoder.read(&mut r).unwrap()).collect();
+		assert_eq!(symbols, vec!{ Symbol8b10b::Data(0x00), Symbol8b10b::Data(0xFF), Symbol8b10b::Data(0x3C) });
+		assert_eq!(decoder.read(&mut r).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(r.position(), 40);
+
+		// The idle symbol 11111 is not a data code group
+		let idle = [0b0101_0111, 0b1100_0000];
+		let mut r = BitReader::new(&idle);
+		assert_eq!(read_4b5b(&mut r, 1).unwrap_err(), "Invalid code group at symbol 1");
+		assert_eq!(r.position(), 0);
+	}
+
+	#[test]
+	fn coding_8b10b() {
+		let mut encoder = Encoder8b10b::new();
+		let mut coded = BitBuffer::new();
+
+		// K.28.5 with RD -1 is the comma 001111 1010 and flips the running disparity
+		encoder.encode(Symbol8b10b::Control(0xBC), &mut coded).unwrap();
+		assert_eq!(coded.read_bits(0, 10).unwrap(), 0b00_1111_1010);
+		assert_eq!(encoder.running_disparity(), 1);
+
+		// D.21.5 is neutral, D.0.0 with RD +1 is 011000 1011
+		encoder.encode(Symbol8b10b::Data(0xB5), &mut coded).unwrap();
+		encoder.encode(Symbol8b10b::Data(0x00), &mut coded).unwrap();
+		assert_eq!(coded.read_bits(10, 10).unwrap(), 0b10_1010_1010);
+		assert_eq!(coded.read_bits(20, 10).unwrap(), 0b01_1000_1011);
+		assert_eq!(encoder.running_disparity(), 1);
+
+		// D.17.7 with RD -1 uses the alternate code 100011 0111
+		let a = encode_8b10b(&[0xF1]);
+		assert_eq!(a.read_bits(0, 10).unwrap(), 0b10_0011_0111);
+
+		assert!(encoder.encode(Symbol8b10b::Control(0x00), &mut coded).is_err());
+
+		assert_eq!(decode_8b10b(&coded).unwrap(), vec!{
+			Symbol8b10b::Control(0xBC), Symbol8b10b::Data(0xB5), Symbol8b10b::Data(0x00) });
+
+		let data: Vec<u8> = (0..=255).collect();
+		let expected: Vec<Symbol8b10b> = data.iter().map(|&b| Symbol8b10b::Data(b)).collect();
+		assert_eq!(decode_8b10b(&encode_8b10b(&data)).unwrap(), expected);
+
+		// D.0.0 for RD -1 (100111 0100) after K.28.5 (RD +1) violates the running disparity
+		let mut bad = BitBuffer::new();
+		bad.push_bits(0b00_1111_1010, 10).unwrap();
+		bad.push_bits(0b10_0111_0100, 10).unwrap();
+		match decode_8b10b(&bad) {
+			Ok(_) => panic!("Missed the disparity error"),
+			Err(e) => assert_eq!(e, "Running disparity error at symbol 1"),
+		}
+
+		let mut bad = BitBuffer::new();
+		bad.push_bits(0b11_1111_0000, 10).unwrap();
+		match decode_8b10b(&bad) {
+			Ok(_) => panic!("Missed the invalid code group"),
+			Err(e) => assert_eq!(e, "Invalid code group at symbol 0"),
+		}
+	}
+
+	#[test]
+	fn framing_and_scrambling_64b66b() {
+		let payloads = [0x0123_4567_89AB_CDEFu64, 0, u64::MAX];
+
+		let mut scrambler = Scrambler64b66b::new();
+		let blocks: Vec<(SyncHeader, u64)> = payloads.iter()
+			.map(|&p| (SyncHeader::Data, scrambler.scramble_u64(p)))
+			.collect();
+		// The scrambled all zero payload is not all zero anymore
+		assert_ne!(blocks[1].1, 0);
+
+		let mut framed = BitBuffer::new();
+		framed.push_bits(0b000, 3).unwrap(); // Some bits before the first block
+		let rest = frame_64b66b(&blocks);
+		for i in 0..rest.bit_len() {
+			framed.push_bit(rest.get_bit(i).unwrap());
+		}
+		assert_eq!(framed.bit_len(), 3 + 3 * 66);
+
+		assert_eq!(lock_64b66b(&framed, 3), Some(3));
+		let received = deframe_64b66b(&framed, 3).unwrap();
+		assert_eq!(received, blocks);
+
+		// A descrambler with the wrong state recovers after 58 bits, i.e. within the first block
+		let mut descrambler = Descrambler64b66b::with_state(0);
+		let descrambled: Vec<u64> = received.iter().map(|&(_, p)| descrambler.descramble_u64(p)).collect();
+		assert_ne!(descrambled[0], payloads[0]);
+		assert_eq!(descrambled[0] & 0x3F, payloads[0] & 0x3F); // The last 6 of 64 bits are correct
+		assert_eq!(&descrambled[1..], &payloads[1..]);
+
+		// The buffer based variants give the same result
+		let plain = BitBuffer::from(vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F });
+		let scrambled = Scrambler64b66b::new().scramble(&plain);
+		assert_eq!(Descrambler64b66b::new().descramble(&scrambled), plain);
+
+		match deframe_64b66b(&framed, 4) {
+			Ok(_) => panic!("Missed the invalid sync header"),
+			Err(e) => assert!(e.starts_with(INVALID_SYNC_HEADER_MSG)),
+		}
+	}
+}
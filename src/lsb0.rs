@@ -0,0 +1,303 @@
+//! Bit numbering starting at the least significant bit of each byte
+//!
+//! Formats like DEFLATE, GIF LZW and many radio protocols fill each byte starting at the
+//! **least** significant bit. In this numbering, the bit offset zero is the least significant
+//! bit of the byte at byte_offset, the bit offset 8 is the least significant bit of the next
+//! byte and so on. The first bit of a field is the least significant bit of its value.
+//!
+//! ```rust
+//! use bitlab::*;
+//! // A DEFLATE block header: BFINAL = 1 and BTYPE = 2 (dynamic Huffman codes)
+//! let v: Vec<u8> = vec!{ 0b0000_0101 };
+//! assert_eq!(v.get_u8_lsb0(0, 0, 1).unwrap(), 1);
+//! assert_eq!(v.get_u8_lsb0(0, 1, 2).unwrap(), 2);
+//! ```
+
+use super::*;
+
+// Reads up to 128 bits starting at an absolute bit position, where zero is the least significant bit
+// of the first byte. The first bit becomes the least significant bit of the result.
+// The caller is responsible for the range checks.
+fn read_bits(source: &[u8], bit_position: usize, length: u32) -> u128 {
+	let mut result: u128 = 0;
+	let mut pos = bit_position;
+	let mut done = 0;
+
+	// Take as many bits as possible from each byte
+	while done < length {
+		let bit_in_byte = (pos % 8) as u32;
+		let n = std::cmp::min(8 - bit_in_byte, length - done);
+		let bits = ((source[pos / 8] >> bit_in_byte) as u128) & ((1u128 << n) - 1);
+		result |= bits << done;
+		done += n;
+		pos += n as usize;
+	}
+
+	result
+}
+
+// Writes the length (up to 128) least significant bits of value starting at an absolute bit position,
+// where zero is the least significant bit of the first byte. The caller is responsible for the range checks.
+fn write_bits(target: &mut [u8], bit_position: usize, length: u32, value: u128) {
+	let mut pos = bit_position;
+	let mut done = 0;
+
+	// Replace as many bits as possible in each byte
+	while done < length {
+		let bit_in_byte = (pos % 8) as u32;
+		let n = std::cmp::min(8 - bit_in_byte, length - done);
+		let mask = (((1u16 << n) - 1) as u8) << bit_in_byte;
+		let bits = ((value >> done) as u8) << bit_in_byte;
+		target[pos / 8] = (target[pos / 8] & !mask) | (bits & mask);
+		done += n;
+		pos += n as usize;
+	}
+}
+
+// Validates the parameters of a getter and returns the absolute bit position
+fn check_access(len: usize, byte_offset: u32, bit_offset: u32, length: u32, max_length: u32, type_name: &str) -> Result<usize> {
+	if length == 0 { return Err(s!(LEN_ZERO)); }
+	if length > max_length { return Err(s!(LEN_TOO_BIG_MSG) + type_name); }
+	range_start(len, byte_offset, bit_offset, length as usize)
+}
+
+/// Extracts and inserts bits in a Vec<u8> or a byte slice, whose bits are numbered starting
+/// at the **least** significant bit of each byte. See the [module documentation](lsb0/index.html).
+pub trait Lsb0Bits {
+	/// Extracts a range of bits and returns a Result object containing a 8 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u8_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u8>;
+
+	/// Extracts a range of bits and returns a Result object containing a signed 8 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i8_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i8>;
+
+	/// Extracts a range of bits and returns a Result object containing a 16 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u16_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u16>;
+
+	/// Extracts a range of bits and returns a Result object containing a signed 16 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i16_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i16>;
+
+	/// Extracts a range of bits and returns a Result object containing a 32 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u32_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u32>;
+
+	/// Extracts a range of bits and returns a Result object containing a signed 32 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i32_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i32>;
+
+	/// Extracts a range of bits and returns a Result object containing a 64 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u64_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u64>;
+
+	/// Extracts a range of bits and returns a Result object containing a signed 64 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i64_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i64>;
+
+	/// Extracts a range of bits and returns a Result object containing a 128 bit unsigned integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_u128_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u128>;
+
+	/// Extracts a range of bits and returns a Result object containing a signed 128 bit integer or an error message.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	fn get_i128_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i128>;
+
+	/// Inserts a range of bits. The least significant bit of value is written first.
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the number of bits to skip. Zero is the least significant bit
+	/// - **length** (u32) the number of bits to be inserted.
+	/// - **value** the value to be inserted.
+	fn set_lsb0<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: SignedInfo + std::string::ToString + Copy,
+		T: num::cast::AsPrimitive<u128> + num::cast::AsPrimitive<i128>;
+}
+
+// The first parameter is the name of the getter, the second the unsigned and the third the returned type
+macro_rules! def_get_lsb0_fn {
+	( $name:ident, $u:ty, $t:ty ) => {
+		fn $name(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<$t> {
+			let bits = std::mem::size_of::<$t>() as u32 * 8;
+			let start = check_access(self.len(), byte_offset, bit_offset, length, bits, stringify!($t))?;
+			let v = read_bits(self, start, length) as $u;
+			// Sign extension. It has no effect on unsigned types.
+			Ok(((v << (bits - length)) as $t) >> (bits - length))
+		}
+	}
+}
+
+impl Lsb0Bits for [u8] {
+	def_get_lsb0_fn!(get_u8_lsb0, u8, u8);
+	def_get_lsb0_fn!(get_i8_lsb0, u8, i8);
+	def_get_lsb0_fn!(get_u16_lsb0, u16, u16);
+	def_get_lsb0_fn!(get_i16_lsb0, u16, i16);
+	def_get_lsb0_fn!(get_u32_lsb0, u32, u32);
+	def_get_lsb0_fn!(get_i32_lsb0, u32, i32);
+	def_get_lsb0_fn!(get_u64_lsb0, u64, u64);
+	def_get_lsb0_fn!(get_i64_lsb0, u64, i64);
+	def_get_lsb0_fn!(get_u128_lsb0, u128, u128);
+	def_get_lsb0_fn!(get_i128_lsb0, u128, i128);
+
+	fn set_lsb0<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: SignedInfo + std::string::ToString + Copy,
+		T: num::cast::AsPrimitive<u128> + num::cast::AsPrimitive<i128> {
+
+		if length == 0 { return Err(s!(LEN_ZERO)); }
+		if length > std::mem::size_of::<T>() as u32 * 8 {
+			return Err(s!(LEN_TOO_BIG_MSG) + std::any::type_name::<T>());
+		}
+		let start = range_start(self.len(), byte_offset, bit_offset, length as usize)?;
+		check_value_fits(value, length)?;
+
+		write_bits(self, start, length, value.as_());
+		Ok(())
+	}
+}
+
+// Vectors use the implementation for slices
+impl Lsb0Bits for Vec<u8> {
+	#[inline]
+	fn get_u8_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u8> {
+		self.as_slice().get_u8_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i8_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i8> {
+		self.as_slice().get_i8_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u16_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u16> {
+		self.as_slice().get_u16_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i16_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i16> {
+		self.as_slice().get_i16_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u32_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u32> {
+		self.as_slice().get_u32_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i32_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i32> {
+		self.as_slice().get_i32_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u64_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u64> {
+		self.as_slice().get_u64_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i64_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i64> {
+		self.as_slice().get_i64_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_u128_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<u128> {
+		self.as_slice().get_u128_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn get_i128_lsb0(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<i128> {
+		self.as_slice().get_i128_lsb0(byte_offset, bit_offset, length)
+	}
+
+	#[inline]
+	fn set_lsb0<T>(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: T) -> Result<()>
+		where T: SignedInfo + std::string::ToString + Copy,
+		T: num::cast::AsPrimitive<u128> + num::cast::AsPrimitive<i128> {
+		self.as_mut_slice().set_lsb0(byte_offset, bit_offset, length, value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lsb0_numbering() {
+		let v: Vec<u8> = vec!{ 0b1010_1100, 0b0000_0011, 0xFF };
+		assert_eq!(v.get_u8_lsb0(0, 2, 2).unwrap(), 0b11);
+		assert_eq!(v.get_u8_lsb0(0, 4, 4).unwrap(), 0b1010);
+		// The field continues with the least significant bits of the next byte
+		assert_eq!(v.get_u16_lsb0(0, 6, 5).unwrap(), 0b0_1110);
+		assert_eq!(v.get_u8_lsb0(0, 14, 2).unwrap(), 0);
+		assert_eq!(v.get_u32_lsb0(0, 0, 24).unwrap(), 0xFF_03AC);
+		assert_eq!(v.get_i8_lsb0(0, 4, 4).unwrap(), -6);
+		assert_eq!(v.get_i64_lsb0(1, 0, 16).unwrap(), -253);
+		assert_eq!(v[..].get_u64_lsb0(0, 4, 20).unwrap(), 0xF_F03A);
+		assert_eq!(v.get_i128_lsb0(0, 4, 20).unwrap(), -0xFC6);
+
+		assert_eq!(v.get_u8_lsb0(0, 0, 9).unwrap_err(), s!(LEN_TOO_BIG_MSG) + "u8");
+		assert_eq!(v.get_u16_lsb0(2, 1, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_u16_lsb0(0, 0, 0).unwrap_err(), LEN_ZERO);
+
+		let mut w: Vec<u8> = vec!{ 0x00, 0xFF };
+		w.set_lsb0(0, 0, 1, 1u8).unwrap();
+		w.set_lsb0(0, 1, 2, 2u8).unwrap();
+		w.set_lsb0(0, 6, 4, 0b1001u8).unwrap();
+		assert_eq!(w, vec!{ 0b0100_0101, 0b1111_1110 });
+		w.set_lsb0(0, 3, 13, -1i16).unwrap();
+		assert_eq!(w, vec!{ 0xFD, 0xFF });
+		assert!(w.set_lsb0(0, 0, 3, 8u8).is_err());
+		assert_eq!(w.set_lsb0(1, 4, 8, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+}
@@ -0,0 +1,95 @@
+//! Memory mapped files. Requires the feature **mmap**.
+//!
+//! Capture files of several GiB do not have to be read into a Vec<u8>. The operating system
+//! loads the pages of a mapped file on demand, when the bits are extracted. The functions of
+//! LargeOffsets reach beyond the first 4 GiB through the dereferenced byte slice.
+
+use std::fs::File;
+use std::path::Path;
+use memmap2::Mmap;
+use super::Result;
+
+/// A read only view on the bits of a memory mapped file. It dereferences to the bytes of the
+/// file, so that the functions of ExtractBitsFromVecU8, BitScan and LargeOffsets can be called on it.
+///
+/// ```rust,no_run
+/// use bitlab::*;
+/// use bitlab::mapped::MappedBits;
+/// // The capture is not modified by another process, while it is mapped
+/// let capture = unsafe { MappedBits::open("capture.bin") }.unwrap();
+/// let flags = capture.get_u8(10, 1, 3).unwrap();
+/// let late = capture.get_u32_at(40_000_000_000, 32).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MappedBits {
+	mmap: Mmap,
+}
+
+impl MappedBits {
+	/// Maps the file at path into memory
+	///
+	/// # Safety
+	///
+	/// See from_file.
+	pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<MappedBits> {
+		let file = File::open(path).map_err(|e| e.to_string())?;
+		MappedBits::from_file(&file)
+	}
+
+	/// Maps an open file into memory.
+	///
+	/// # Safety
+	///
+	/// The file must not be truncated or modified by this or any other process while it is mapped.
+	/// Otherwise the bytes behind the returned slices change, which is undefined behavior, or the
+	/// process crashes on access.
+	pub unsafe fn from_file(file: &File) -> Result<MappedBits> {
+		let mmap = Mmap::map(file).map_err(|e| e.to_string())?;
+		Ok(MappedBits { mmap })
+	}
+
+	/// Returns the bytes of the file
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.mmap
+	}
+}
+
+impl std::ops::Deref for MappedBits {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		&self.mmap
+	}
+}
+
+impl AsRef<[u8]> for MappedBits {
+	fn as_ref(&self) -> &[u8] {
+		&self.mmap
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+	use crate::{ExtractBitsFromVecU8, BitScan, LargeOffsets};
+
+	#[test]
+	fn mapping_files() {
+		let path = std::env::temp_dir().join(format!("bitlab_mapped_{}.bin", std::process::id()));
+		File::create(&path).unwrap().write_all(&[0x48, 0x61, 0x6C, 0x6C, 0x6F]).unwrap();
+
+		// The file is not modified while it is mapped
+		let mapped = unsafe { MappedBits::open(&path) }.unwrap();
+		assert_eq!(mapped.len(), 5);
+		assert_eq!(mapped.get_u8(1, 7, 3).unwrap(), 5);
+		assert_eq!(mapped.get_bytes::<2>(3, 0).unwrap(), [0x6C, 0x6F]);
+		assert_eq!(mapped.get_u16_at(15, 3).unwrap(), 5);
+		assert_eq!(mapped.find_pattern(0x6F, 8, 0).unwrap(), Some(32));
+		assert!(mapped.get_u8(4, 1, 8).is_err());
+		drop(mapped);
+
+		std::fs::remove_file(&path).unwrap();
+		assert!(unsafe { MappedBits::open(&path) }.is_err());
+	}
+}
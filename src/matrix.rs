@@ -0,0 +1,249 @@
+//! Two dimensional bit matrices
+
+use super::{Result, BitSlice, OUT_OF_RANGE_MSG, read_bits_from_slice, write_bits_to_slice};
+
+static STRIDE_TOO_SMALL_MSG: &str = "The stride must not be smaller than the width";
+
+// Transposes an 8 x 8 block, whose first row is the most significant byte
+fn transpose_8x8(mut x: u64) -> u64 {
+	let mut t = (x ^ (x >> 7)) & 0x00AA_00AA_00AA_00AA;
+	x = x ^ t ^ (t << 7);
+	t = (x ^ (x >> 14)) & 0x0000_CCCC_0000_CCCC;
+	x = x ^ t ^ (t << 14);
+	t = (x ^ (x >> 28)) & 0x0000_0000_F0F0_F0F0;
+	x ^ t ^ (t << 28)
+}
+
+/// A matrix of bits, which are stored row by row in a Vec<u8>, like a monochrome framebuffer or a font bitmap.
+///
+/// Each row starts stride bits after the previous one. By default, the stride is the width
+/// rounded up to a multiple of 8, so that every row starts at a byte boundary.
+/// Column zero is the **most** significant bit of the first byte of a row.
+///
+/// ```rust
+/// use bitlab::*;
+/// // The glyph "T" of a 3 x 5 font
+/// let glyph = BitMatrix::from_bytes(vec!{ 0b1110_0000, 0b0100_0000, 0b0100_0000, 0b0100_0000, 0b0100_0000 }, 3, 5).unwrap();
+/// assert!(glyph.get(0, 2).unwrap());
+/// assert!(!glyph.get(4, 2).unwrap());
+/// let stem = glyph.sub_matrix(1, 1, 1, 4).unwrap();
+/// assert_eq!(stem.as_bytes(), &[0x80, 0x80, 0x80, 0x80]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+	bytes: Vec<u8>,
+	width: usize,
+	height: usize,
+	stride: usize,
+}
+
+impl BitMatrix {
+	/// Creates a matrix of width x height cleared bits, whose rows start at byte boundaries
+	pub fn new(width: usize, height: usize) -> BitMatrix {
+		let stride = width.div_ceil(8) * 8;
+		BitMatrix { bytes: vec!{ 0; stride / 8 * height }, width, height, stride }
+	}
+
+	/// Takes over the bytes of a matrix, whose rows start at byte boundaries.
+	/// Returns an error if there are not enough bytes.
+	pub fn from_bytes(bytes: Vec<u8>, width: usize, height: usize) -> Result<BitMatrix> {
+		BitMatrix::from_bytes_with_stride(bytes, width, height, width.div_ceil(8) * 8)
+	}
+
+	/// Takes over the bytes of a matrix, whose rows start stride bits apart.
+	/// Returns an error if the stride is smaller than the width or if there are not enough bytes.
+	pub fn from_bytes_with_stride(bytes: Vec<u8>, width: usize, height: usize, stride: usize) -> Result<BitMatrix> {
+		if stride < width {
+			return Err(s!(STRIDE_TOO_SMALL_MSG));
+		}
+		let needed = match height {
+			0 => Some(0),
+			_ => stride.checked_mul(height - 1).and_then(|n| n.checked_add(width)),
+		};
+		match needed {
+			Some(n) if n <= bytes.len().saturating_mul(8) => Ok(BitMatrix { bytes, width, height, stride }),
+			_ => Err(s!(OUT_OF_RANGE_MSG)),
+		}
+	}
+
+	/// Returns the number of columns
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// Returns the number of rows
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// Returns the distance between the starts of two rows in bits
+	pub fn stride(&self) -> usize {
+		self.stride
+	}
+
+	/// Returns the underlying bytes
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Consumes the matrix and returns the underlying bytes
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+
+	// Returns the bit index of a cell or an error if it is outside of the matrix
+	fn index(&self, row: usize, col: usize) -> Result<usize> {
+		if row >= self.height || col >= self.width {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		Ok(row * self.stride + col)
+	}
+
+	/// Tests the bit at the given row and column
+	pub fn get(&self, row: usize, col: usize) -> Result<bool> {
+		let index = self.index(row, col)?;
+		Ok(read_bits_from_slice(&self.bytes, index, 1) == 1)
+	}
+
+	/// Sets the bit at the given row and column if bit is true or clears it otherwise
+	pub fn set(&mut self, row: usize, col: usize, bit: bool) -> Result<()> {
+		let index = self.index(row, col)?;
+		write_bits_to_slice(&mut self.bytes, index, 1, bit as u64);
+		Ok(())
+	}
+
+	/// Returns a view on the width bits of a row
+	pub fn row(&self, row: usize) -> Result<BitSlice<'_>> {
+		if row >= self.height {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		BitSlice::new(&self.bytes, row * self.stride, self.width)
+	}
+
+	/// Returns an iterator over the rows from top to bottom
+	pub fn rows(&self) -> impl Iterator<Item = BitSlice<'_>> {
+		// Cannot fail, since the size of the bytes has been checked on creation
+		(0..self.height).filter_map(move |row| self.row(row).ok())
+	}
+
+	/// Copies the rectangle of width x height bits, whose top left corner is at the given row
+	/// and column, into a new matrix, whose rows start at byte boundaries
+	pub fn sub_matrix(&self, row: usize, col: usize, width: usize, height: usize) -> Result<BitMatrix> {
+		match (row.checked_add(height), col.checked_add(width)) {
+			(Some(bottom), Some(right)) if bottom <= self.height && right <= self.width => {},
+			_ => return Err(s!(OUT_OF_RANGE_MSG)),
+		}
+
+		let mut result = BitMatrix::new(width, height);
+		for r in 0..height {
+			let mut done = 0;
+			while done < width {
+				let n = std::cmp::min(64, width - done);
+				let bits = read_bits_from_slice(&self.bytes, (row + r) * self.stride + col + done, n as u32);
+				write_bits_to_slice(&mut result.bytes, r * result.stride + done, n as u32, bits);
+				done += n;
+			}
+		}
+		Ok(result)
+	}
+
+	/// Returns a new matrix, whose rows are the columns of this one, and whose rows start at byte boundaries.
+	/// This converts e.g. the column-major data of many OLED and e-paper displays into row-major data and back.
+	///
+	/// The matrix is processed in blocks of 8 x 8 bits, which are transposed with a few shifts and masks.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// let m = BitMatrix::from_bytes(vec!{ 0b1000_0000, 0b1100_0000 }, 2, 2).unwrap();
+	/// let t = m.transpose();
+	/// assert_eq!(t.as_bytes(), &[0b1100_0000, 0b0100_0000]);
+	/// assert_eq!(t.transpose(), m);
+	/// ```
+	pub fn transpose(&self) -> BitMatrix {
+		let mut result = BitMatrix::new(self.height, self.width);
+		let result_row_bytes = result.stride / 8;
+		for row in (0..self.height).step_by(8) {
+			let rows = std::cmp::min(8, self.height - row);
+			for col in (0..self.width).step_by(8) {
+				let cols = std::cmp::min(8, self.width - col);
+
+				// Missing rows and columns of the block are zero
+				let mut block = 0u64;
+				for r in 0..rows {
+					let bits = read_bits_from_slice(&self.bytes, (row + r) * self.stride + col, cols as u32);
+					block |= (bits << (8 - cols)) << (56 - 8 * r);
+				}
+
+				let block = transpose_8x8(block);
+				for c in 0..cols {
+					result.bytes[(col + c) * result_row_bytes + row / 8] = (block >> (56 - 8 * c)) as u8;
+				}
+			}
+		}
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bit_matrices() {
+		let mut m = BitMatrix::new(10, 3);
+		assert_eq!((m.width(), m.height(), m.stride()), (10, 3, 16));
+		m.set(0, 0, true).unwrap();
+		m.set(1, 9, true).unwrap();
+		m.set(2, 4, true).unwrap();
+		assert_eq!(m.as_bytes(), &[0x80, 0x00, 0x00, 0x40, 0x08, 0x00]);
+		assert!(m.get(1, 9).unwrap());
+		assert_eq!(m.get(1, 10).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(m.set(3, 0, true).is_err());
+
+		let rows: Vec<u64> = m.rows().map(|r| r.read_bits(0, 10).unwrap()).collect();
+		assert_eq!(rows, vec!{ 0b10_0000_0000, 0b00_0000_0001, 0b00_0010_0000 });
+		assert!(m.row(3).is_err());
+
+		let sub = m.sub_matrix(1, 4, 6, 2).unwrap();
+		assert_eq!((sub.width(), sub.height()), (6, 2));
+		assert_eq!(sub.as_bytes(), &[0b0000_0100, 0b1000_0000]);
+		assert!(m.sub_matrix(1, 5, 6, 2).is_err());
+		assert_eq!(m.sub_matrix(0, 0, 10, 3).unwrap(), m);
+
+		// Packed rows of 5 bits
+		let packed = BitMatrix::from_bytes_with_stride(vec!{ 0b1000_0100, 0b0010_0000 }, 5, 3, 5).unwrap();
+		assert!(packed.get(0, 0).unwrap() && packed.get(1, 0).unwrap() && packed.get(2, 0).unwrap());
+		assert_eq!(packed.clone().into_bytes().len(), 2);
+		assert!(BitMatrix::from_bytes_with_stride(vec!{ 0; 2 }, 5, 4, 5).is_err());
+		assert_eq!(BitMatrix::from_bytes_with_stride(vec!{ 0; 2 }, 5, 2, 4).unwrap_err(), STRIDE_TOO_SMALL_MSG);
+		assert!(BitMatrix::from_bytes(vec!{}, 8, 0).is_ok());
+	}
+
+	#[test]
+	fn transposing_matrices() {
+		let mut m = BitMatrix::new(13, 19);
+		for (row, col) in [(0, 0), (0, 12), (3, 5), (9, 1), (18, 12), (18, 0), (7, 8)].iter() {
+			m.set(*row, *col, true).unwrap();
+		}
+		let t = m.transpose();
+		assert_eq!((t.width(), t.height(), t.stride()), (19, 13, 24));
+		for row in 0..19 {
+			for col in 0..13 {
+				assert_eq!(t.get(col, row).unwrap(), m.get(row, col).unwrap());
+			}
+		}
+		// The padding bits are zero
+		assert_eq!(t.as_bytes()[2], 0b0010_0000);
+		assert_eq!(t.transpose(), m);
+
+		// Packed rows with garbage after the last row
+		let packed = BitMatrix::from_bytes_with_stride(vec!{ 0b1100_1101, 0b1111_1111 }, 3, 3, 3).unwrap();
+		let t = packed.transpose();
+		assert_eq!(t.as_bytes(), &[0b1000_0000, 0b1110_0000, 0b0110_0000]);
+
+		let wide = BitMatrix::from_bytes(vec!{ 0xFF, 0x00, 0x0F }, 24, 1).unwrap();
+		assert_eq!(wide.transpose().as_bytes().len(), 24);
+		assert_eq!(wide.transpose().transpose(), wide);
+		assert_eq!(BitMatrix::new(0, 5).transpose(), BitMatrix::new(5, 0));
+	}
+}
@@ -0,0 +1,215 @@
+//! Small floating point formats with configurable exponent and mantissa widths
+//!
+//! A minifloat consists of a sign bit followed by the exponent and the mantissa (the fraction
+//! without the hidden bit). This covers e.g. the 8 bit formats E5M2 and E4M3 used for machine
+//! learning data, IEEE 754 half precision and custom formats in graphics data.
+//!
+//! ```rust
+//! use bitlab::minifloat::*;
+//! let v: Vec<u8> = vec!{ 0b0_0111_100, 0xC0 };
+//! assert_eq!(get_minifloat(&v, 0, 0, &E4M3).unwrap(), 1.5);
+//! assert_eq!(E4M3.encode(-448.0), 0b1_1111_110);
+//! ```
+
+use super::*;
+
+static WIDTH_MSG: &str = "A minifloat needs 1 to 11 exponent bits and at most 52 mantissa bits";
+static NAN_MSG: &str = "A minifloat with infinity needs at least 1 mantissa bit to encode NaN";
+
+/// Describes a minifloat format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinifloatFormat {
+	exp_bits: u32,
+	mant_bits: u32,
+	bias: i32,
+	has_infinity: bool,
+}
+
+/// The 8 bit format with 5 exponent bits and 2 mantissa bits. Special values as in IEEE 754.
+pub const E5M2: MinifloatFormat = MinifloatFormat { exp_bits: 5, mant_bits: 2, bias: 15, has_infinity: true };
+
+/// The 8 bit format with 4 exponent bits and 3 mantissa bits (E4M3FN). It has no infinity and
+/// only the NaN S.1111.111, so that the largest exponent is available for normal numbers.
+pub const E4M3: MinifloatFormat = MinifloatFormat { exp_bits: 4, mant_bits: 3, bias: 7, has_infinity: false };
+
+/// IEEE 754 half precision
+pub const F16: MinifloatFormat = MinifloatFormat { exp_bits: 5, mant_bits: 10, bias: 15, has_infinity: true };
+
+/// bfloat16, the upper half of an IEEE 754 single precision float
+pub const BF16: MinifloatFormat = MinifloatFormat { exp_bits: 8, mant_bits: 7, bias: 127, has_infinity: true };
+
+// Multiplies x by 2^exp without intermediate overflow or underflow
+fn scale(mut x: f64, mut exp: i32) -> f64 {
+	while exp > 1000 { x *= 2f64.powi(1000); exp -= 1000; }
+	while exp < -1000 { x *= 2f64.powi(-1000); exp += 1000; }
+	x * 2f64.powi(exp)
+}
+
+impl MinifloatFormat {
+	/// Creates a format with the special values of IEEE 754: The largest exponent is
+	/// reserved for infinity (mantissa zero) and NaN. Returns an error for unsupported widths.
+	/// Since NaN needs a non-zero mantissa, at least 1 mantissa bit is required.
+	pub fn new(exp_bits: u32, mant_bits: u32, bias: i32) -> Result<MinifloatFormat> {
+		if mant_bits == 0 {
+			return Err(s!(NAN_MSG));
+		}
+		MinifloatFormat::new_finite(exp_bits, mant_bits, bias).map(|f| MinifloatFormat { has_infinity: true, ..f })
+	}
+
+	/// Same as new, but without infinity: The largest exponent is used for normal
+	/// numbers and the only NaN has all exponent and mantissa bits set (like E4M3).
+	pub fn new_finite(exp_bits: u32, mant_bits: u32, bias: i32) -> Result<MinifloatFormat> {
+		if exp_bits == 0 || exp_bits > 11 || mant_bits > 52 {
+			return Err(s!(WIDTH_MSG));
+		}
+		Ok(MinifloatFormat { exp_bits, mant_bits, bias, has_infinity: false })
+	}
+
+	/// Returns the number of bits including the sign bit
+	pub fn bit_len(&self) -> u32 {
+		1 + self.exp_bits + self.mant_bits
+	}
+
+	/// Decodes the bit_len() least significant bits of bits
+	pub fn decode(&self, bits: u64) -> f64 {
+		let mb = self.mant_bits;
+		let max_exp = (1u64 << self.exp_bits) - 1;
+		let mant = bits & ((1u64 << mb) - 1);
+		let exp = (bits >> mb) & max_exp;
+		let sign = if (bits >> (self.exp_bits + mb)) & 1 == 1 { -1.0 } else { 1.0 };
+
+		if self.has_infinity && exp == max_exp {
+			return if mant == 0 { sign * f64::INFINITY } else { f64::NAN };
+		}
+		if !self.has_infinity && exp == max_exp && mant == (1u64 << mb) - 1 {
+			return f64::NAN;
+		}
+
+		// Subnormal numbers have the exponent of the smallest normal number, but no hidden bit
+		let (significand, exp) = if exp == 0 { (mant, 1) } else { (mant | (1u64 << mb), exp as i32) };
+		sign * scale(significand as f64, exp - self.bias - mb as i32)
+	}
+
+	/// Encodes a value by rounding to the nearest representable number (ties to even).
+	/// Values beyond the largest finite number become infinity, or NaN if the format has no infinity.
+	pub fn encode(&self, value: f64) -> u64 {
+		let mb = self.mant_bits;
+		let max_exp = (1u64 << self.exp_bits) - 1;
+		let sign = if value.is_sign_negative() { 1u64 << (self.exp_bits + mb) } else { 0 };
+		let nan = if self.has_infinity { (max_exp << mb) | (1u64 << mb >> 1) } else { (1u64 << (self.exp_bits + mb)) - 1 };
+		let overflow = if self.has_infinity { sign | (max_exp << mb) } else { sign | nan };
+
+		if value.is_nan() {
+			return nan;
+		}
+		if value.is_infinite() {
+			return overflow;
+		}
+
+		let a = value.abs();
+		if a == 0.0 {
+			return sign;
+		}
+
+		// The exponent of a in base 2
+		let bits = a.to_bits();
+		let e = if a >= f64::MIN_POSITIVE {
+			((bits >> 52) & 0x7FF) as i32 - 1023
+		} else {
+			-1074 + (63 - bits.leading_zeros() as i32)
+		};
+
+		// Count in units of the last place of the target exponent.
+		// Normal numbers include the hidden bit in n, so that the carry of the rounding
+		// and the transition from subnormal to normal numbers fall out of the addition below.
+		let emin = 1 - self.bias;
+		let e = e.max(emin);
+		let n = scale(a, mb as i32 - e).round_ties_even();
+		if (e - emin) as i64 >= max_exp as i64 {
+			return overflow;
+		}
+		let code = (((e - emin) as u64) << mb) + n as u64;
+
+		let max_finite = if self.has_infinity { (max_exp << mb) - 1 } else { nan - 1 };
+		if code > max_finite { overflow } else { sign | code }
+	}
+}
+
+/// Extracts a minifloat, which starts at the given byte and bit offset
+pub fn get_minifloat<V: ExtractBitsFromVecU8 + ?Sized>(source: &V, byte_offset: u32, bit_offset: u32, format: &MinifloatFormat) -> Result<f64> {
+	Ok(format.decode(source.get_u64(byte_offset, bit_offset, format.bit_len())?))
+}
+
+/// Encodes value as a minifloat and inserts it at the given byte and bit offset
+pub fn set_minifloat<V: InsertBitsIntoVecU8 + ?Sized>(target: &mut V, byte_offset: u32, bit_offset: u32, format: &MinifloatFormat, value: f64) -> Result<()> {
+	target.set(byte_offset, bit_offset, format.bit_len(), format.encode(value))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// The binary literals are grouped as sign_exponent_mantissa
+	#[allow(clippy::unusual_byte_groupings)]
+	#[test]
+	fn minifloat_round_trips() {
+		// All finite values of E5M2 and E4M3 survive the round trip
+		for f in [E5M2, E4M3].iter() {
+			for bits in 0..256u64 {
+				let v = f.decode(bits);
+				if v.is_nan() {
+					assert!(f.decode(f.encode(v)).is_nan());
+				} else {
+					assert_eq!(f.encode(v), bits, "{:08b}", bits);
+				}
+			}
+		}
+
+		assert_eq!(E5M2.decode(0b0_11110_11), 57344.0);
+		assert_eq!(E5M2.decode(0b1_11111_00), f64::NEG_INFINITY);
+		assert_eq!(E5M2.decode(0b0_00000_01), 2f64.powi(-16));
+		assert_eq!(E4M3.decode(0b0_1111_110), 448.0);
+		assert!(E4M3.decode(0b0_1111_111).is_nan());
+		assert_eq!(E4M3.decode(0b0_0000_001), 2f64.powi(-9));
+
+		// Rounding: 1.0625 is halfway between 1.0 and 1.125 in E4M3 --> even
+		assert_eq!(E4M3.encode(1.0625), 0b0_0111_000);
+		assert_eq!(E4M3.encode(1.07), 0b0_0111_001);
+		// Overflow
+		assert_eq!(E5M2.encode(1e6), 0b0_11111_00);
+		assert_eq!(E4M3.encode(-1e6), 0b1_1111_111);
+		assert_eq!(E4M3.encode(464.0), 0b0_1111_110);
+		// Subnormal to normal by rounding and underflow to zero
+		assert_eq!(E4M3.encode(2f64.powi(-6) * 0.99), 0b0_0001_000);
+		assert_eq!(E4M3.encode(2f64.powi(-11)), 0);
+
+		assert_eq!(F16.decode(0x3C00), 1.0);
+		assert_eq!(F16.encode(65504.0), 0x7BFF);
+		assert_eq!(F16.encode(-2.0), 0xC000);
+
+		let custom = MinifloatFormat::new(3, 4, 2).unwrap();
+		let mut v: Vec<u8> = vec!{ 0x00, 0x00 };
+		set_minifloat(&mut v, 0, 5, &custom, 3.25).unwrap();
+		assert_eq!(get_minifloat(&v, 0, 5, &custom).unwrap(), 3.25);
+		assert_eq!(custom.encode(3.25), 0b0_011_1010);
+		assert_eq!(v, vec!{ 0b0000_0001, 0b1101_0000 });
+
+		assert_eq!(MinifloatFormat::new(0, 3, 0).unwrap_err(), WIDTH_MSG);
+		assert_eq!(MinifloatFormat::new_finite(4, 3, 7).unwrap(), E4M3);
+
+		// Without mantissa bits, NaN can only be encoded without infinity
+		assert_eq!(MinifloatFormat::new(4, 0, 7).unwrap_err(), NAN_MSG);
+		let e4m0 = MinifloatFormat::new_finite(4, 0, 7).unwrap();
+		assert_eq!(e4m0.encode(f64::NAN), 0b0_1111);
+		assert!(e4m0.decode(0b0_1111).is_nan());
+		assert_eq!(e4m0.decode(0b1_1110), -128.0);
+		assert_eq!(e4m0.encode(f64::INFINITY), 0b0_1111);
+
+		// bfloat16 is a truncated f32
+		for &x in [1.0f32, -2.5, 1.5 * 2f32.powi(127), 2f32.powi(-130), f32::INFINITY].iter() {
+			let bits = (x.to_bits() >> 16) as u64;
+			assert_eq!(BF16.decode(bits), x as f64);
+			assert_eq!(BF16.encode(x as f64), bits);
+		}
+	}
+}
@@ -0,0 +1,111 @@
+//! Morton codes (Z-order curve)
+//!
+//! A Morton code interleaves the bits of two or three coordinates, so that points, which are close
+//! to each other, tend to have close codes. This makes them useful as keys of spatial indexes.
+//! The least significant bit of the code is the least significant bit of x, followed by the one of y
+//! (and z).
+//!
+//! ```rust
+//! use bitlab::morton::*;
+//! // x = 0b11, y = 0b01 --> 0b01_11
+//! assert_eq!(encode_2d(0b11, 0b01), 0b0111);
+//! assert_eq!(decode_2d(0b0111), (0b11, 0b01));
+//! ```
+
+use super::Result;
+
+static COORDINATE_TOO_BIG_MSG: &str = "A 3D coordinate must not have more than 21 bits";
+
+// Inserts a zero bit after each of the 32 bits of value
+fn spread_by_1(value: u32) -> u64 {
+	let mut x = value as u64;
+	x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+	x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+	x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+	x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+	x = (x | (x << 1)) & 0x5555_5555_5555_5555;
+	x
+}
+
+// Reverses spread_by_1
+fn compact_by_1(code: u64) -> u32 {
+	let mut x = code & 0x5555_5555_5555_5555;
+	x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+	x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+	x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+	x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+	x = (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF;
+	x as u32
+}
+
+// Inserts two zero bits after each of the 21 least significant bits of value
+fn spread_by_2(value: u32) -> u64 {
+	let mut x = value as u64 & 0x1F_FFFF;
+	x = (x | (x << 32)) & 0x001F_0000_0000_FFFF;
+	x = (x | (x << 16)) & 0x001F_0000_FF00_00FF;
+	x = (x | (x << 8)) & 0x100F_00F0_0F00_F00F;
+	x = (x | (x << 4)) & 0x10C3_0C30_C30C_30C3;
+	x = (x | (x << 2)) & 0x1249_2492_4924_9249;
+	x
+}
+
+// Reverses spread_by_2
+fn compact_by_2(code: u64) -> u32 {
+	let mut x = code & 0x1249_2492_4924_9249;
+	x = (x | (x >> 2)) & 0x10C3_0C30_C30C_30C3;
+	x = (x | (x >> 4)) & 0x100F_00F0_0F00_F00F;
+	x = (x | (x >> 8)) & 0x001F_0000_FF00_00FF;
+	x = (x | (x >> 16)) & 0x001F_0000_0000_FFFF;
+	x = (x | (x >> 32)) & 0x1F_FFFF;
+	x as u32
+}
+
+/// Interleaves the bits of two coordinates
+pub fn encode_2d(x: u32, y: u32) -> u64 {
+	spread_by_1(x) | spread_by_1(y) << 1
+}
+
+/// Splits a 2D Morton code into the coordinates (x, y)
+pub fn decode_2d(code: u64) -> (u32, u32) {
+	(compact_by_1(code), compact_by_1(code >> 1))
+}
+
+/// Interleaves the bits of three coordinates with up to 21 bits each.
+/// Returns an error if a coordinate has more than 21 bits.
+///
+/// ```rust
+/// use bitlab::morton::*;
+/// let code = encode_3d(5, 9, 2).unwrap();
+/// assert_eq!(decode_3d(code), (5, 9, 2));
+/// assert!(encode_3d(1 << 21, 0, 0).is_err());
+/// ```
+pub fn encode_3d(x: u32, y: u32, z: u32) -> Result<u64> {
+	if (x | y | z) >> 21 != 0 {
+		return Err(s!(COORDINATE_TOO_BIG_MSG));
+	}
+	Ok(spread_by_2(x) | spread_by_2(y) << 1 | spread_by_2(z) << 2)
+}
+
+/// Splits a 3D Morton code into the coordinates (x, y, z). The most significant bit of the code is ignored.
+pub fn decode_3d(code: u64) -> (u32, u32, u32) {
+	(compact_by_2(code), compact_by_2(code >> 1), compact_by_2(code >> 2))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn morton_codes() {
+		assert_eq!(encode_2d(u32::MAX, 0), 0x5555_5555_5555_5555);
+		assert_eq!(encode_2d(0, u32::MAX), 0xAAAA_AAAA_AAAA_AAAA);
+		assert_eq!(encode_2d(0x1234_5678, 0x9ABC_DEF0), 0x838C_8FB0_B3BC_BF40);
+		assert_eq!(decode_2d(0x838C_8FB0_B3BC_BF40), (0x1234_5678, 0x9ABC_DEF0));
+
+		assert_eq!(encode_3d(1, 1, 1).unwrap(), 0b111);
+		assert_eq!(encode_3d(0b10, 0, 0b1).unwrap(), 0b1100);
+		assert_eq!(encode_3d(0x1F_FFFF, 0, 0).unwrap(), 0x1249_2492_4924_9249);
+		assert_eq!(decode_3d(encode_3d(0x12_3456, 0x0B_CDEF, 0x1F_0F0F).unwrap()), (0x12_3456, 0x0B_CDEF, 0x1F_0F0F));
+		assert_eq!(encode_3d(0, 0, 1 << 21).unwrap_err(), COORDINATE_TOO_BIG_MSG);
+	}
+}
@@ -0,0 +1,102 @@
+//! Bit parsers for [nom](https://crates.io/crates/nom). Requires the feature **nom**.
+//!
+//! The parsers work on the input of nom's bit level parsers, which is a tuple of a byte slice
+//! and the bit offset (0 to 7) within its first byte, where zero is the most significant bit.
+//! Use nom::bits::bits to switch from a byte level parser.
+//!
+//! ```rust
+//! use bitlab::nom_parsers::{take_bits, tag_bits, BitInput};
+//! use nom::sequence::tuple;
+//! use nom::IResult;
+//!
+//! // The version and the header length of an IPv4 header
+//! fn ipv4_start(input: BitInput) -> IResult<BitInput, (u64, u8)> {
+//!     tuple((tag_bits(4, 4), take_bits::<u8, _>(4)))(input)
+//! }
+//!
+//! let data = [0x45u8, 0x00];
+//! let (rest, (version, ihl)) = ipv4_start((&data, 0)).unwrap();
+//! assert_eq!((version, ihl), (4, 5));
+//! assert_eq!(rest, (&data[1..], 0));
+//! assert!(ipv4_start((&[0x65u8][..], 0)).is_err());
+//! ```
+
+use nom::error::{ErrorKind, ParseError};
+use nom::{Err, IResult};
+use super::BitTarget;
+
+/// The input of nom's bit level parsers: The bytes and the bit offset within the first byte
+pub type BitInput<'a> = (&'a [u8], usize);
+
+// Returns the input after n bits if there are enough bits left
+fn advance(input: BitInput<'_>, n: u32) -> Option<BitInput<'_>> {
+	let (bytes, bit_offset) = input;
+	let end = bit_offset + n as usize;
+	if end > bytes.len() * 8 {
+		return None;
+	}
+	Some((&bytes[end / 8..], end % 8))
+}
+
+/// Returns a parser, which extracts n bits as the type T, e.g. a u16 or an i8.
+///
+/// The parser fails with ErrorKind::Eof if there are less than n bits left, and with
+/// ErrorKind::TooLarge if n is zero or too big for T.
+pub fn take_bits<'a, T, E>(n: u32) -> impl Fn(BitInput<'a>) -> IResult<BitInput<'a>, T, E>
+	where T: BitTarget, E: ParseError<BitInput<'a>> {
+	move |input: BitInput<'a>| {
+		let rest = advance(input, n).ok_or_else(|| Err::Error(E::from_error_kind(input, ErrorKind::Eof)))?;
+		match T::extract_from_bytes(input.0, 0, input.1 as u32, n) {
+			Ok(value) => Ok((rest, value)),
+			Err(_) => Err(Err::Error(E::from_error_kind(input, ErrorKind::TooLarge))),
+		}
+	}
+}
+
+/// Returns a parser, which expects the n (at most 64) bits of pattern and returns them.
+///
+/// The parser fails with ErrorKind::TagBits if the bits differ from the pattern or if there are
+/// less than n bits left, and with ErrorKind::TooLarge if n is zero or more than 64.
+pub fn tag_bits<'a, E>(pattern: u64, n: u32) -> impl Fn(BitInput<'a>) -> IResult<BitInput<'a>, u64, E>
+	where E: ParseError<BitInput<'a>> {
+	move |input: BitInput<'a>| {
+		let rest = advance(input, n).ok_or_else(|| Err::Error(E::from_error_kind(input, ErrorKind::TagBits)))?;
+		match u64::extract_from_bytes(input.0, 0, input.1 as u32, n) {
+			Ok(value) if value == pattern => Ok((rest, value)),
+			Ok(_) => Err(Err::Error(E::from_error_kind(input, ErrorKind::TagBits))),
+			Err(_) => Err(Err::Error(E::from_error_kind(input, ErrorKind::TooLarge))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nom::error::Error;
+
+	#[test]
+	fn parsing_with_nom() {
+		let data = [0b1011_0011u8, 0xF3, 0x0F];
+		let (rest, value) = take_bits::<u16, Error<_>>(12)((&data[..], 2)).unwrap();
+		assert_eq!(value, 0b1100_1111_1100);
+		assert_eq!(rest, (&data[1..], 6));
+		let (rest, value) = take_bits::<i8, Error<_>>(3)(rest).unwrap();
+		assert_eq!(value, -2);
+		assert_eq!(rest, (&data[2..], 1));
+		let err = take_bits::<u32, Error<_>>(8)(rest).unwrap_err();
+		assert_eq!(err, Err::Error(Error::new(rest, ErrorKind::Eof)));
+		let err = take_bits::<u8, Error<_>>(9)((&data[..], 0)).unwrap_err();
+		assert_eq!(err, Err::Error(Error::new((&data[..], 0), ErrorKind::TooLarge)));
+
+		let (rest, _) = tag_bits::<Error<_>>(0b1011, 4)((&data[..], 0)).unwrap();
+		assert_eq!(rest, (&data[..], 4));
+		assert!(tag_bits::<Error<_>>(0b1010, 4)((&data[..], 0)).is_err());
+		let err = tag_bits::<Error<_>>(0, 24)((&data[..], 1)).unwrap_err();
+		assert_eq!(err, Err::Error(Error::new((&data[..], 1), ErrorKind::TagBits)));
+		assert!(tag_bits::<Error<_>>(0, 0)((&data[..], 0)).is_err());
+
+		// Used from a byte level parser
+		let result: IResult<&[u8], u8> = nom::bits::bits(take_bits::<u8, Error<_>>(4))(&data[..]);
+		assert_eq!(result.unwrap(), (&data[1..], 0b1011));
+	}
+}
@@ -0,0 +1,143 @@
+//! Extraction and insertion at 64 bit positions
+//!
+//! The byte and bit offsets of ExtractBitsFromVecU8 and InsertBitsIntoVecU8 are u32, which
+//! limits them to the first 4 GiB of a buffer. The functions of this module take the absolute
+//! bit position as a u64 instead, so that memory mapped captures of any size can be parsed.
+//!
+//! ```rust
+//! use bitlab::*;
+//! let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+//! assert_eq!(v.get_u16_at(15, 3).unwrap(), 5);
+//! v.set_at(36, 4, 0xFu8).unwrap();
+//! assert_eq!(v[4], 0x6F);
+//! ```
+
+use std::convert::TryFrom;
+use super::{Result, BitValue, ExtractBitsFromVecU8, InsertBitsIntoVecU8, OUT_OF_RANGE_MSG};
+
+// Returns the bytes starting at the byte of bit_position and the bit offset within the first byte
+pub(crate) fn split(source: &[u8], bit_position: u64) -> Result<(&[u8], u32)> {
+	let byte = usize::try_from(bit_position / 8).map_err(|_| s!(OUT_OF_RANGE_MSG))?;
+	match source.get(byte..) {
+		Some(rest) => Ok((rest, (bit_position % 8) as u32)),
+		None => Err(s!(OUT_OF_RANGE_MSG)),
+	}
+}
+
+// Same as split for mutable bytes
+fn split_mut(target: &mut [u8], bit_position: u64) -> Result<(&mut [u8], u32)> {
+	let byte = usize::try_from(bit_position / 8).map_err(|_| s!(OUT_OF_RANGE_MSG))?;
+	match target.get_mut(byte..) {
+		Some(rest) => Ok((rest, (bit_position % 8) as u32)),
+		None => Err(s!(OUT_OF_RANGE_MSG)),
+	}
+}
+
+/// Extracts and inserts bits in a Vec<u8> or a byte slice at an absolute bit position.
+/// Zero is the most significant bit of the first byte. See the [module documentation](offset64/index.html).
+pub trait LargeOffsets {
+	/// Extracts length bits starting at bit_position as a 8 bit unsigned integer
+	fn get_u8_at(&self, bit_position: u64, length: u32) -> Result<u8>;
+
+	/// Extracts length bits starting at bit_position as a signed 8 bit integer
+	fn get_i8_at(&self, bit_position: u64, length: u32) -> Result<i8>;
+
+	/// Extracts length bits starting at bit_position as a 16 bit unsigned integer
+	fn get_u16_at(&self, bit_position: u64, length: u32) -> Result<u16>;
+
+	/// Extracts length bits starting at bit_position as a signed 16 bit integer
+	fn get_i16_at(&self, bit_position: u64, length: u32) -> Result<i16>;
+
+	/// Extracts length bits starting at bit_position as a 32 bit unsigned integer
+	fn get_u32_at(&self, bit_position: u64, length: u32) -> Result<u32>;
+
+	/// Extracts length bits starting at bit_position as a signed 32 bit integer
+	fn get_i32_at(&self, bit_position: u64, length: u32) -> Result<i32>;
+
+	/// Extracts length bits starting at bit_position as a 64 bit unsigned integer
+	fn get_u64_at(&self, bit_position: u64, length: u32) -> Result<u64>;
+
+	/// Extracts length bits starting at bit_position as a signed 64 bit integer
+	fn get_i64_at(&self, bit_position: u64, length: u32) -> Result<i64>;
+
+	/// Extracts length bits starting at bit_position as a 128 bit unsigned integer
+	fn get_u128_at(&self, bit_position: u64, length: u32) -> Result<u128>;
+
+	/// Extracts length bits starting at bit_position as a signed 128 bit integer
+	fn get_i128_at(&self, bit_position: u64, length: u32) -> Result<i128>;
+
+	/// Inserts value into length bits starting at bit_position. See InsertBitsIntoVecU8::set.
+	fn set_at<T: BitValue>(&mut self, bit_position: u64, length: u32, value: T) -> Result<()>;
+}
+
+// The first parameter is the name of the new function, the second the name of the
+// function with byte and bit offsets and the third the returned type
+macro_rules! def_get_at_fn {
+	( $name:ident, $get:ident, $t:ty ) => {
+		fn $name(&self, bit_position: u64, length: u32) -> Result<$t> {
+			let (rest, bit_offset) = split(self, bit_position)?;
+			rest.$get(0, bit_offset, length)
+		}
+	}
+}
+
+impl LargeOffsets for [u8] {
+	def_get_at_fn!(get_u8_at, get_u8, u8);
+	def_get_at_fn!(get_i8_at, get_i8, i8);
+	def_get_at_fn!(get_u16_at, get_u16, u16);
+	def_get_at_fn!(get_i16_at, get_i16, i16);
+	def_get_at_fn!(get_u32_at, get_u32, u32);
+	def_get_at_fn!(get_i32_at, get_i32, i32);
+	def_get_at_fn!(get_u64_at, get_u64, u64);
+	def_get_at_fn!(get_i64_at, get_i64, i64);
+	def_get_at_fn!(get_u128_at, get_u128, u128);
+	def_get_at_fn!(get_i128_at, get_i128, i128);
+
+	fn set_at<T: BitValue>(&mut self, bit_position: u64, length: u32, value: T) -> Result<()> {
+		let (rest, bit_offset) = split_mut(self, bit_position)?;
+		rest.set(0, bit_offset, length, value)
+	}
+}
+
+// The getters split vectors like slices. set_at uses the implementation for slices.
+impl LargeOffsets for Vec<u8> {
+	def_get_at_fn!(get_u8_at, get_u8, u8);
+	def_get_at_fn!(get_i8_at, get_i8, i8);
+	def_get_at_fn!(get_u16_at, get_u16, u16);
+	def_get_at_fn!(get_i16_at, get_i16, i16);
+	def_get_at_fn!(get_u32_at, get_u32, u32);
+	def_get_at_fn!(get_i32_at, get_i32, i32);
+	def_get_at_fn!(get_u64_at, get_u64, u64);
+	def_get_at_fn!(get_i64_at, get_i64, i64);
+	def_get_at_fn!(get_u128_at, get_u128, u128);
+	def_get_at_fn!(get_i128_at, get_i128, i128);
+
+	#[inline]
+	fn set_at<T: BitValue>(&mut self, bit_position: u64, length: u32, value: T) -> Result<()> {
+		self.as_mut_slice().set_at(bit_position, length, value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn absolute_bit_positions() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		assert_eq!(v.get_u8_at(15, 3).unwrap(), v.get_u8(1, 7, 3).unwrap());
+		assert_eq!(v.get_i32_at(15, 3).unwrap(), -3);
+		assert_eq!(v.get_u64_at(0, 40).unwrap(), 0x48_616C_6C6F);
+		assert_eq!(v[..].get_i16_at(32, 8).unwrap(), 0x6F);
+		assert_eq!(v.get_u8_at(33, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_u8_at(u64::MAX, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_u8_at(40, 1).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_u128_at(1, 39).unwrap(), 0x48_616C_6C6F & !(1 << 39));
+		assert_eq!(v[..].get_i128_at(4, 8).unwrap(), -122);
+
+		v.set_at(4, 8, 0xABu8).unwrap();
+		assert_eq!(&v[..2], &[0x4A, 0xB1]);
+		assert_eq!(v.set_at(39, 2, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.set_at(1 << 40, 2, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+}
@@ -0,0 +1,419 @@
+//! Packing of structs into bit fields with [serde](https://crates.io/crates/serde). Requires the feature **serde**.
+//!
+//! to_bits writes the fields of any type, which implements Serialize, one after the other into a
+//! BitBuffer. The widths of the fields are given by a schema in the order of the fields, where nested
+//! structs, tuples and arrays are flattened. from_bits reads them back.
+//!
+//! Supported are integers up to 64 bits, bools, chars, f32 and f64 (with the widths 32 and 64),
+//! structs, tuples, arrays and enums without data, which are packed as the index of the variant.
+//! Units and unit structs take no bits and no width.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use bitlab::packing::{from_bits, to_bits};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! enum Priority { Low, Normal, High }
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Header { version: u8, priority: Priority, urgent: bool, length: u16 }
+//!
+//! let schema = [3, 2, 1, 10];
+//! let header = Header { version: 5, priority: Priority::High, urgent: true, length: 1023 };
+//! let bits = to_bits(&header, &schema).unwrap();
+//! assert_eq!(bits.bit_len(), 16);
+//! assert_eq!(bits.as_bytes(), &[0b1011_0111, 0xFF]);
+//! assert_eq!(from_bits::<Header>(&bits, &schema).unwrap(), header);
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+use serde::{de, ser, Serialize};
+use serde::de::{DeserializeOwned, Visitor, IntoDeserializer};
+use super::{Result, BitBuffer, BitReader, BitWriter, LEN_ZERO, LEN_TOO_BIG_MSG, check_value_fits};
+
+static NO_WIDTH_MSG: &str = "The schema has no width for field ";
+static SURPLUS_WIDTHS_MSG: &str = "The schema has more widths than fields";
+static SURPLUS_BITS_MSG: &str = "Not all bits have been unpacked";
+static UNSUPPORTED_MSG: &str = "The bit packing does not support ";
+static FLOAT_WIDTH_MSG: &str = "The width of a float must be the size of its type";
+
+/// Packs the fields of value into a buffer, whose widths are given by the schema.
+/// Returns an error if the schema does not match the fields or if a value does not fit.
+pub fn to_bits<T: Serialize + ?Sized>(value: &T, schema: &[u32]) -> Result<BitBuffer> {
+	let mut packer = Packer { writer: BitWriter::new(), schema, index: 0 };
+	value.serialize(&mut packer).map_err(|e| e.0)?;
+	if packer.index != schema.len() {
+		return Err(s!(SURPLUS_WIDTHS_MSG));
+	}
+	Ok(packer.writer.into_buffer())
+}
+
+/// Unpacks a value, whose fields have the widths given by the schema, from all bits of a buffer.
+/// Returns an error if the schema does not match the fields or the bits.
+pub fn from_bits<T: DeserializeOwned>(bits: &BitBuffer, schema: &[u32]) -> Result<T> {
+	let mut unpacker = Unpacker { reader: BitReader::from(bits), schema, index: 0 };
+	let value = T::deserialize(&mut unpacker).map_err(|e| e.0)?;
+	if unpacker.index != schema.len() {
+		return Err(s!(SURPLUS_WIDTHS_MSG));
+	}
+	if unpacker.reader.remaining_bits() != 0 {
+		return Err(s!(SURPLUS_BITS_MSG));
+	}
+	Ok(value)
+}
+
+// The error type, which serde requires
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Error {
+		Error(msg.to_string())
+	}
+}
+
+impl de::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Error {
+		Error(msg.to_string())
+	}
+}
+
+fn unsupported<T>(what: &str) -> std::result::Result<T, Error> {
+	Err(Error(s!(UNSUPPORTED_MSG) + what))
+}
+
+// Returns the next width of the schema
+fn next_width(schema: &[u32], index: &mut usize) -> std::result::Result<u32, Error> {
+	let width = *schema.get(*index).ok_or_else(|| Error(s!(NO_WIDTH_MSG) + &index.to_string()))?;
+	if width == 0 {
+		return Err(Error(s!(LEN_ZERO)));
+	}
+	if width > 64 {
+		return Err(Error(s!(LEN_TOO_BIG_MSG) + "u64"));
+	}
+	*index += 1;
+	Ok(width)
+}
+
+struct Packer<'a> {
+	writer: BitWriter,
+	schema: &'a [u32],
+	index: usize,
+}
+
+impl Packer<'_> {
+	fn write_unsigned(&mut self, value: u64) -> std::result::Result<(), Error> {
+		let width = next_width(self.schema, &mut self.index)?;
+		check_value_fits(value, width).map_err(Error)?;
+		self.writer.write_bits(value, width).map_err(Error)
+	}
+
+	fn write_signed(&mut self, value: i64) -> std::result::Result<(), Error> {
+		let width = next_width(self.schema, &mut self.index)?;
+		check_value_fits(value, width).map_err(Error)?;
+		self.writer.write_bits(value as u64, width).map_err(Error)
+	}
+
+	fn write_float(&mut self, bits: u64, size: u32) -> std::result::Result<(), Error> {
+		let width = next_width(self.schema, &mut self.index)?;
+		if width != size {
+			return Err(Error(s!(FLOAT_WIDTH_MSG)));
+		}
+		self.writer.write_bits(bits, width).map_err(Error)
+	}
+}
+
+impl<'a, 'b> ser::Serializer for &'b mut Packer<'a> {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = ser::Impossible<(), Error>;
+	type SerializeTuple = Self;
+	type SerializeTupleStruct = Self;
+	type SerializeTupleVariant = ser::Impossible<(), Error>;
+	type SerializeMap = ser::Impossible<(), Error>;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = ser::Impossible<(), Error>;
+
+	fn serialize_bool(self, v: bool) -> std::result::Result<(), Error> { self.write_unsigned(v as u64) }
+	fn serialize_i8(self, v: i8) -> std::result::Result<(), Error> { self.write_signed(v as i64) }
+	fn serialize_i16(self, v: i16) -> std::result::Result<(), Error> { self.write_signed(v as i64) }
+	fn serialize_i32(self, v: i32) -> std::result::Result<(), Error> { self.write_signed(v as i64) }
+	fn serialize_i64(self, v: i64) -> std::result::Result<(), Error> { self.write_signed(v) }
+	fn serialize_u8(self, v: u8) -> std::result::Result<(), Error> { self.write_unsigned(v as u64) }
+	fn serialize_u16(self, v: u16) -> std::result::Result<(), Error> { self.write_unsigned(v as u64) }
+	fn serialize_u32(self, v: u32) -> std::result::Result<(), Error> { self.write_unsigned(v as u64) }
+	fn serialize_u64(self, v: u64) -> std::result::Result<(), Error> { self.write_unsigned(v) }
+	fn serialize_f32(self, v: f32) -> std::result::Result<(), Error> { self.write_float(v.to_bits() as u64, 32) }
+	fn serialize_f64(self, v: f64) -> std::result::Result<(), Error> { self.write_float(v.to_bits(), 64) }
+	fn serialize_char(self, v: char) -> std::result::Result<(), Error> { self.write_unsigned(v as u64) }
+	fn serialize_str(self, _: &str) -> std::result::Result<(), Error> { unsupported("strings") }
+	fn serialize_bytes(self, _: &[u8]) -> std::result::Result<(), Error> { unsupported("byte arrays") }
+	fn serialize_none(self) -> std::result::Result<(), Error> { unsupported("options") }
+	fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> std::result::Result<(), Error> { unsupported("options") }
+	fn serialize_unit(self) -> std::result::Result<(), Error> { Ok(()) }
+	fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<(), Error> { Ok(()) }
+
+	fn serialize_unit_variant(self, _: &'static str, index: u32, _: &'static str) -> std::result::Result<(), Error> {
+		self.write_unsigned(index as u64)
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> std::result::Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> std::result::Result<(), Error> {
+		unsupported("enum variants with data")
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> std::result::Result<Self::SerializeSeq, Error> { unsupported("sequences") }
+	fn serialize_tuple(self, _: usize) -> std::result::Result<Self, Error> { Ok(self) }
+	fn serialize_tuple_struct(self, _: &'static str, _: usize) -> std::result::Result<Self, Error> { Ok(self) }
+
+	fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> std::result::Result<Self::SerializeTupleVariant, Error> {
+		unsupported("enum variants with data")
+	}
+
+	fn serialize_map(self, _: Option<usize>) -> std::result::Result<Self::SerializeMap, Error> { unsupported("maps") }
+	fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self, Error> { Ok(self) }
+
+	fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> std::result::Result<Self::SerializeStructVariant, Error> {
+		unsupported("enum variants with data")
+	}
+}
+
+impl ser::SerializeTuple for &mut Packer<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> std::result::Result<(), Error> { Ok(()) }
+}
+
+impl ser::SerializeTupleStruct for &mut Packer<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> std::result::Result<(), Error> { Ok(()) }
+}
+
+impl ser::SerializeStruct for &mut Packer<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> std::result::Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> std::result::Result<(), Error> { Ok(()) }
+}
+
+struct Unpacker<'a> {
+	reader: BitReader<'a>,
+	schema: &'a [u32],
+	index: usize,
+}
+
+impl Unpacker<'_> {
+	fn read_unsigned(&mut self) -> std::result::Result<u64, Error> {
+		let width = next_width(self.schema, &mut self.index)?;
+		self.reader.read_bits(width).map_err(Error)
+	}
+
+	fn read_signed(&mut self) -> std::result::Result<i64, Error> {
+		let width = next_width(self.schema, &mut self.index)?;
+		self.reader.read_signed_bits(width).map_err(Error)
+	}
+
+	fn read_float(&mut self, size: u32) -> std::result::Result<u64, Error> {
+		let width = next_width(self.schema, &mut self.index)?;
+		if width != size {
+			return Err(Error(s!(FLOAT_WIDTH_MSG)));
+		}
+		self.reader.read_bits(width).map_err(Error)
+	}
+}
+
+// Converts an unpacked value into the type of the field
+fn convert<T: TryFrom<V>, V: fmt::Display + Copy>(value: V, ty: &str) -> std::result::Result<T, Error> {
+	T::try_from(value).map_err(|_| Error(format!("Failed to unpack {} as a {}.", value, ty)))
+}
+
+impl<'de, 'a, 'b> de::Deserializer<'de> for &'b mut Unpacker<'a> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, _: V) -> std::result::Result<V::Value, Error> {
+		unsupported("types, which are not known in advance")
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		match self.read_unsigned()? {
+			0 => visitor.visit_bool(false),
+			1 => visitor.visit_bool(true),
+			v => Err(Error(format!("Failed to unpack {} as a bool.", v))),
+		}
+	}
+
+	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_i8(convert(self.read_signed()?, "i8")?)
+	}
+
+	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_i16(convert(self.read_signed()?, "i16")?)
+	}
+
+	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_i32(convert(self.read_signed()?, "i32")?)
+	}
+
+	fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_i64(self.read_signed()?)
+	}
+
+	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_u8(convert(self.read_unsigned()?, "u8")?)
+	}
+
+	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_u16(convert(self.read_unsigned()?, "u16")?)
+	}
+
+	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_u32(convert(self.read_unsigned()?, "u32")?)
+	}
+
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_u64(self.read_unsigned()?)
+	}
+
+	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_f32(f32::from_bits(self.read_float(32)? as u32))
+	}
+
+	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_f64(f64::from_bits(self.read_float(64)?))
+	}
+
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		let code: u32 = convert(self.read_unsigned()?, "char")?;
+		visitor.visit_char(convert(code, "char")?)
+	}
+
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_seq(Fields { unpacker: self, remaining: len })
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _: &'static str, len: usize, visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_seq(Fields { unpacker: self, remaining: len })
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(self, _: &'static str, fields: &'static [&'static str], visitor: V) -> std::result::Result<V::Value, Error> {
+		visitor.visit_seq(Fields { unpacker: self, remaining: fields.len() })
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, _: &'static str, _: &'static [&'static str], visitor: V) -> std::result::Result<V::Value, Error> {
+		let index: u32 = convert(self.read_unsigned()?, "variant index")?;
+		visitor.visit_enum(index.into_deserializer())
+	}
+
+	serde::forward_to_deserialize_any! {
+		i128 u128 str string bytes byte_buf option seq map identifier ignored_any
+	}
+}
+
+// The fields of a struct or the elements of a tuple
+struct Fields<'a, 'b> {
+	unpacker: &'b mut Unpacker<'a>,
+	remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Fields<'_, '_> {
+	type Error = Error;
+
+	fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> std::result::Result<Option<T::Value>, Error> {
+		if self.remaining == 0 {
+			return Ok(None);
+		}
+		self.remaining -= 1;
+		seed.deserialize(&mut *self.unpacker).map(Some)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.remaining)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::Deserialize;
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Point(i8, i8);
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Sample {
+		id: u32,
+		position: Point,
+		flags: [bool; 3],
+		marker: (),
+		ratio: f32,
+		letter: char,
+	}
+
+	#[test]
+	fn packing_structs() {
+		let schema = [12, 5, 5, 1, 1, 1, 32, 7];
+		let sample = Sample { id: 0xABC, position: Point(-16, 15), flags: [true, false, true], marker: (), ratio: 0.5, letter: 'z' };
+		let bits = to_bits(&sample, &schema).unwrap();
+		assert_eq!(bits.bit_len(), 64);
+		assert_eq!(bits.read_bits(0, 25).unwrap(), 0xABC << 13 | 0b10000_01111 << 3 | 0b101);
+		assert_eq!(bits.read_bits(25, 32).unwrap(), 0.5f32.to_bits() as u64);
+		assert_eq!(from_bits::<Sample>(&bits, &schema).unwrap(), sample);
+
+		// The schema does not match the fields
+		assert_eq!(to_bits(&sample, &schema[..7]).unwrap_err(), "The schema has no width for field 7");
+		assert_eq!(to_bits(&sample, &[12, 5, 5, 1, 1, 1, 32, 7, 1]).unwrap_err(), SURPLUS_WIDTHS_MSG);
+		assert_eq!(to_bits(&sample, &[12, 5, 5, 1, 1, 1, 16, 7]).unwrap_err(), FLOAT_WIDTH_MSG);
+		assert_eq!(to_bits(&sample, &[0, 5, 5, 1, 1, 1, 32, 7]).unwrap_err(), LEN_ZERO);
+		assert_eq!(from_bits::<Sample>(&bits, &[12, 5, 5, 1, 1, 1, 32, 6]).unwrap_err(), SURPLUS_BITS_MSG);
+		assert!(from_bits::<Sample>(&bits, &[12, 5, 5, 1, 1, 1, 32, 8]).is_err());
+
+		// The values do not fit
+		assert!(to_bits(&Point(-17, 0), &[5, 5]).is_err());
+		assert!(to_bits(&300u16, &[8]).is_err());
+		assert_eq!(from_bits::<u8>(&BitBuffer::from(vec!{ 0x01, 0x00 }), &[16]).unwrap_err(), "Failed to unpack 256 as a u8.");
+		assert!(from_bits::<bool>(&BitBuffer::from(vec!{ 0x02 }), &[8]).is_err());
+
+		assert!(to_bits("text", &[8]).unwrap_err().starts_with(UNSUPPORTED_MSG));
+		assert!(to_bits(&Some(1u8), &[8]).unwrap_err().starts_with(UNSUPPORTED_MSG));
+		assert!(from_bits::<Vec<u8>>(&BitBuffer::new(), &[]).unwrap_err().starts_with(UNSUPPORTED_MSG));
+	}
+}
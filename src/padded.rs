@@ -0,0 +1,75 @@
+//! Best-effort extraction from truncated data
+//!
+//! The getters of ExtractBitsFromVecU8 return an error if a field extends past the end of the
+//! data. Captures are often truncated though, and a best-effort value is more useful than
+//! aborting the parse. The getters of this module treat the missing bits as zeros, i.e. the data
+//! behaves as if it was followed by an unlimited number of zero bytes. Invalid lengths are still errors.
+//!
+//! ```rust
+//! use bitlab::*;
+//! // A 32 bit field, of which only the first 12 bits were captured
+//! let v: Vec<u8> = vec!{ 0x00, 0xAB, 0xC0 };
+//! assert!(v.get_u32(1, 0, 32).is_err());
+//! assert_eq!(v.get_or_zero_pad::<u32>(1, 0, 32).unwrap(), 0xABC0_0000);
+//! ```
+
+use std::convert::TryFrom;
+use super::{Result, BitTarget, ExtractBitsFromVecU8};
+
+/// Extracts bits from a Vec<u8> or a byte slice, where the bits after the end are zeros.
+/// See the [module documentation](padded/index.html).
+pub trait ZeroPadded {
+	/// Extracts length bits as any integer type. Missing bits are zeros. See ExtractBitsFromVecU8::get_at.
+	fn get_or_zero_pad<T: BitTarget>(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<T>;
+}
+
+impl ZeroPadded for [u8] {
+	fn get_or_zero_pad<T: BitTarget>(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<T> {
+		let start = byte_offset as u64 * 8 + bit_offset as u64;
+		// A field of up to 128 bits spans at most 17 bytes. They are copied, so that the rest is zero.
+		let mut padded = [0u8; 17];
+		if let Some(rest) = usize::try_from(start / 8).ok().and_then(|byte| self.get(byte..)) {
+			let n = rest.len().min(padded.len());
+			padded[..n].copy_from_slice(&rest[..n]);
+		}
+		padded.get_at(0, (start % 8) as u32, length)
+	}
+}
+
+// Vectors use the implementation for slices
+impl ZeroPadded for Vec<u8> {
+	#[inline]
+	fn get_or_zero_pad<T: BitTarget>(&self, byte_offset: u32, bit_offset: u32, length: u32) -> Result<T> {
+		self.as_slice().get_or_zero_pad(byte_offset, bit_offset, length)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::LEN_ZERO;
+
+	#[test]
+	fn padding_truncated_fields() {
+		let v: Vec<u8> = vec!{ 0x12, 0x34, 0xF5 };
+		// Fields within the data are the same as with the strict getters
+		assert_eq!(v.get_or_zero_pad::<u16>(0, 4, 12).unwrap(), v.get_u16(0, 4, 12).unwrap());
+		assert_eq!(v.get_or_zero_pad::<i8>(2, 0, 4).unwrap(), -1);
+
+		assert_eq!(v.get_or_zero_pad::<u16>(2, 4, 8).unwrap(), 0x50);
+		assert_eq!(v.get_or_zero_pad::<i16>(2, 0, 12).unwrap(), -176);
+		assert_eq!(v[..].get_or_zero_pad::<u64>(0, 0, 64).unwrap(), 0x1234_F500_0000_0000);
+		assert_eq!(v.get_or_zero_pad::<i64>(0, 7, 64).unwrap(), 0x1A7A_8000_0000_0000);
+
+		// Fields beyond the end are zero
+		assert_eq!(v.get_or_zero_pad::<u32>(3, 0, 32).unwrap(), 0);
+		assert_eq!(v.get_or_zero_pad::<u8>(u32::MAX, 7, 8).unwrap(), 0);
+		assert_eq!(Vec::<u8>::new().get_or_zero_pad::<i32>(0, 0, 1).unwrap(), 0);
+		assert_eq!(v.get_or_zero_pad::<u128>(1, 7, 128).unwrap(), 0xF5 << 119);
+
+		// Invalid lengths are the same errors as with the strict getters
+		assert_eq!(v.get_or_zero_pad::<u8>(0, 0, 0).unwrap_err(), LEN_ZERO);
+		assert_eq!(v.get_or_zero_pad::<u8>(0, 0, 9).unwrap_err(), v.get_u8(0, 0, 9).unwrap_err());
+		assert_eq!(v.get_or_zero_pad::<u128>(0, 0, 129).unwrap_err(), v.get_u128(0, 0, 129).unwrap_err());
+	}
+}
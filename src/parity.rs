@@ -0,0 +1,105 @@
+//! Parity bits
+//!
+//! The functions of this module count the ones in a field, which may start and end at any bit.
+//! A parity bit, which is inserted or verified, directly follows the field like in UART frames.
+//!
+//! ```rust
+//! use bitlab::parity::*;
+//! // 7 data bits and an even parity bit
+//! let mut frame = [0b1011_0000u8];
+//! insert_parity_bit(&mut frame, 0, 0, 7, Parity::Even).unwrap();
+//! assert_eq!(frame, [0b1011_0001]);
+//! assert!(verify_parity_bit(&frame, 0, 0, 7, Parity::Even).unwrap());
+//! ```
+
+use super::{Result, range_start, read_bits_from_slice, write_bits_to_slice};
+
+/// The kind of a parity bit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parity {
+	/// The number of ones including the parity bit is even
+	Even,
+	/// The number of ones including the parity bit is odd
+	Odd,
+}
+
+// Counts the ones in a field, which lies within the source
+fn count_ones(source: &[u8], start: usize, length: usize) -> usize {
+	let mut ones = 0;
+	let mut index = 0;
+	while index < length {
+		let n = std::cmp::min(64, length - index) as u32;
+		ones += read_bits_from_slice(source, start + index, n).count_ones() as usize;
+		index += n as usize;
+	}
+	ones
+}
+
+/// Returns true if the number of ones in the field is odd.
+///
+/// Parameters:
+///
+/// - **source** the data source
+/// - **byte_offset** (u32) the number of bytes to skip in source
+/// - **bit_offset** (u32) the start position of the field. Zero is the most significant bit
+/// - **length** (usize) the number of bits in the field
+pub fn is_odd(source: &[u8], byte_offset: u32, bit_offset: u32, length: usize) -> Result<bool> {
+	let start = range_start(source.len(), byte_offset, bit_offset, length)?;
+	Ok(count_ones(source, start, length) % 2 == 1)
+}
+
+/// Returns the parity bit of the field. See is_odd for the parameters.
+pub fn parity_bit(source: &[u8], byte_offset: u32, bit_offset: u32, length: usize, parity: Parity) -> Result<bool> {
+	let odd = is_odd(source, byte_offset, bit_offset, length)?;
+	Ok(match parity {
+		Parity::Even => odd,
+		Parity::Odd => !odd,
+	})
+}
+
+/// Writes the parity bit of the field into the bit following it. See is_odd for the parameters.
+pub fn insert_parity_bit(target: &mut [u8], byte_offset: u32, bit_offset: u32, length: usize, parity: Parity) -> Result<()> {
+	let start = range_start(target.len(), byte_offset, bit_offset, length.saturating_add(1))?;
+	let bit = parity_bit(target, byte_offset, bit_offset, length, parity)?;
+	write_bits_to_slice(target, start + length, 1, bit as u64);
+	Ok(())
+}
+
+/// Returns true if the bit following the field is its correct parity bit. See is_odd for the parameters.
+pub fn verify_parity_bit(source: &[u8], byte_offset: u32, bit_offset: u32, length: usize, parity: Parity) -> Result<bool> {
+	let start = range_start(source.len(), byte_offset, bit_offset, length.saturating_add(1))?;
+	let expected = parity_bit(source, byte_offset, bit_offset, length, parity)?;
+	Ok(read_bits_from_slice(source, start + length, 1) == expected as u64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn parity_bits() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00 };
+		assert!(is_odd(&v, 0, 0, 4).unwrap());
+		assert!(!is_odd(&v, 0, 1, 0).unwrap());
+		// 0x61 0x6C: 3 + 4 ones
+		assert!(is_odd(&v, 1, 0, 16).unwrap());
+		// 80 bits with 1 + 3 + 4 + 4 + 6 + 40 + 0 ones
+		assert!(!is_odd(&v, 0, 4, 80).unwrap());
+		assert!(parity_bit(&v, 1, 0, 16, Parity::Even).unwrap());
+		assert!(!parity_bit(&v, 1, 0, 16, Parity::Odd).unwrap());
+		assert_eq!(is_odd(&v, 10, 1, 8).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		// 8 data bits across a byte boundary, followed by the parity bit
+		insert_parity_bit(&mut v, 0, 5, 8, Parity::Odd).unwrap();
+		assert_eq!(v[1], 0x61 | 0b0000_0100);
+		assert!(verify_parity_bit(&v, 0, 5, 8, Parity::Odd).unwrap());
+		assert!(!verify_parity_bit(&v, 0, 5, 8, Parity::Even).unwrap());
+		insert_parity_bit(&mut v, 0, 5, 8, Parity::Even).unwrap();
+		assert_eq!(v[1], 0x61);
+		assert!(verify_parity_bit(&v, 0, 5, 8, Parity::Even).unwrap());
+
+		assert_eq!(insert_parity_bit(&mut v, 10, 0, 8, Parity::Even).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(verify_parity_bit(&v, 10, 0, 8, Parity::Even).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+}
@@ -0,0 +1,134 @@
+//! Gathering and scattering bits by a mask like the PEXT and PDEP instructions
+//!
+//! bit_extract takes the bits of a value at the positions, which are set in a mask, and packs them
+//! into the least significant bits of the result. bit_deposit is the inverse operation, which
+//! distributes the least significant bits of a value to the positions of the set bits of the mask.
+//!
+//! With the feature **bmi2**, the u32 and u64 implementations use the PEXT and PDEP instructions,
+//! if the crate is compiled for an x86_64 target with BMI2 support
+//! (e.g. with `RUSTFLAGS="-C target-cpu=native"`).
+//!
+//! ```rust
+//! use bitlab::*;
+//! // Collect the flags in bits 7, 4 and 1 of a register
+//! let register: u8 = 0b1000_0010;
+//! assert_eq!(register.bit_extract(0b1001_0010), 0b101);
+//! assert_eq!(0b101u8.bit_deposit(0b1001_0010), register);
+//! ```
+
+/// Gathers and scatters bits by a mask. See the [module documentation](pext/index.html).
+pub trait MaskedBits {
+	/// Packs the bits of self at the set bits of mask into the least significant bits of the result
+	fn bit_extract(self, mask: Self) -> Self;
+
+	/// Distributes the least significant bits of self to the set bits of mask. All other bits are zero.
+	fn bit_deposit(self, mask: Self) -> Self;
+}
+
+// The software implementation for unsigned types
+macro_rules! def_masked_bits {
+	( $( $t:ty ),* ) => { $(
+		impl MaskedBits for $t {
+			fn bit_extract(self, mask: $t) -> $t {
+				let mut result: $t = 0;
+				let mut mask = mask;
+				let mut bit: $t = 1;
+				while mask != 0 {
+					let lowest = mask & mask.wrapping_neg();
+					if self & lowest != 0 {
+						result |= bit;
+					}
+					mask ^= lowest;
+					bit <<= 1;
+				}
+				result
+			}
+
+			fn bit_deposit(self, mask: $t) -> $t {
+				let mut result: $t = 0;
+				let mut mask = mask;
+				let mut bit: $t = 1;
+				while mask != 0 {
+					let lowest = mask & mask.wrapping_neg();
+					if self & bit != 0 {
+						result |= lowest;
+					}
+					mask ^= lowest;
+					bit <<= 1;
+				}
+				result
+			}
+		}
+	)* }
+}
+
+def_masked_bits!(u8, u16, u128, usize);
+
+#[cfg(not(all(feature = "bmi2", target_arch = "x86_64", target_feature = "bmi2")))]
+def_masked_bits!(u32, u64);
+
+#[cfg(all(feature = "bmi2", target_arch = "x86_64", target_feature = "bmi2"))]
+impl MaskedBits for u32 {
+	#[inline]
+	fn bit_extract(self, mask: u32) -> u32 {
+		// Safe, since the target supports BMI2
+		unsafe { std::arch::x86_64::_pext_u32(self, mask) }
+	}
+
+	#[inline]
+	fn bit_deposit(self, mask: u32) -> u32 {
+		unsafe { std::arch::x86_64::_pdep_u32(self, mask) }
+	}
+}
+
+#[cfg(all(feature = "bmi2", target_arch = "x86_64", target_feature = "bmi2"))]
+impl MaskedBits for u64 {
+	#[inline]
+	fn bit_extract(self, mask: u64) -> u64 {
+		// Safe, since the target supports BMI2
+		unsafe { std::arch::x86_64::_pext_u64(self, mask) }
+	}
+
+	#[inline]
+	fn bit_deposit(self, mask: u64) -> u64 {
+		unsafe { std::arch::x86_64::_pdep_u64(self, mask) }
+	}
+}
+
+// Signed types use the implementation for the unsigned type of the same size
+macro_rules! def_signed_masked_bits {
+	( $( $t:ty, $u:ty );* ) => { $(
+		impl MaskedBits for $t {
+			#[inline]
+			fn bit_extract(self, mask: $t) -> $t {
+				(self as $u).bit_extract(mask as $u) as $t
+			}
+
+			#[inline]
+			fn bit_deposit(self, mask: $t) -> $t {
+				(self as $u).bit_deposit(mask as $u) as $t
+			}
+		}
+	)* }
+}
+
+def_signed_masked_bits!(i8, u8; i16, u16; i32, u32; i64, u64; i128, u128; isize, usize);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn extracting_and_depositing_bits() {
+		assert_eq!(0x1234_5678u32.bit_extract(0xFF00_FF00), 0x1256);
+		assert_eq!(0x1256u32.bit_deposit(0xFF00_FF00), 0x1200_5600);
+		assert_eq!(u64::MAX.bit_extract(0x8000_0000_0000_0001), 0b11);
+		assert_eq!(0b11u64.bit_deposit(0x8000_0000_0000_0001), 0x8000_0000_0000_0001);
+		assert_eq!(0xABCDu16.bit_extract(0), 0);
+		assert_eq!(0xABCDu16.bit_extract(0xFFFF), 0xABCD);
+		assert_eq!(u128::MAX.bit_deposit(1 << 127), 1 << 127);
+		assert_eq!((-1i8).bit_extract(0x0F), 0x0F);
+		assert_eq!(0x0Fi8.bit_deposit(-16), -16);
+		assert_eq!(0b110usize.bit_deposit(0b1010_1000), 0b1010_0000);
+	}
+}
@@ -0,0 +1,196 @@
+//! Posit numbers
+//!
+//! A posit of n bits consists of a sign bit, a variable length regime (a run of equal bits
+//! terminated by the opposite bit), up to es exponent bits and the remaining fraction bits.
+//! Negative posits are stored as the two's complement of their absolute value.
+//! The only special values are zero (all bits clear) and NaR ("not a real", only the sign bit set),
+//! which is decoded as NaN.
+//!
+//! ```rust
+//! use bitlab::posit::*;
+//! let v: Vec<u8> = vec!{ 0b0100_0000, 0b0110_0000 };
+//! assert_eq!(get_posit(&v, 0, 0, &POSIT8).unwrap(), 1.0);
+//! assert_eq!(POSIT8.encode(-1.0), 0b1100_0000);
+//! ```
+
+use super::*;
+
+static FORMAT_MSG: &str = "A posit needs 2 to 32 bits and at most 5 exponent bits";
+
+/// Describes a posit format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositFormat {
+	n_bits: u32,
+	es: u32,
+}
+
+/// 8 bit posit with 2 exponent bits as in the posit standard (2022)
+pub const POSIT8: PositFormat = PositFormat { n_bits: 8, es: 2 };
+
+/// 16 bit posit with 2 exponent bits as in the posit standard (2022)
+pub const POSIT16: PositFormat = PositFormat { n_bits: 16, es: 2 };
+
+/// 32 bit posit with 2 exponent bits as in the posit standard (2022)
+pub const POSIT32: PositFormat = PositFormat { n_bits: 32, es: 2 };
+
+impl PositFormat {
+	/// Creates a format with n_bits bits (2 to 32) and es exponent bits (at most 5)
+	pub fn new(n_bits: u32, es: u32) -> Result<PositFormat> {
+		if !(2..=32).contains(&n_bits) || es > 5 {
+			return Err(s!(FORMAT_MSG));
+		}
+		Ok(PositFormat { n_bits, es })
+	}
+
+	/// Returns the number of bits
+	pub fn bit_len(&self) -> u32 {
+		self.n_bits
+	}
+
+	/// Decodes the bit_len() least significant bits of bits
+	pub fn decode(&self, bits: u64) -> f64 {
+		let n = self.n_bits;
+		let mask = (1u64 << n) - 1;
+		let sign_bit = 1u64 << (n - 1);
+		let mut x = bits & mask;
+
+		if x == 0 { return 0.0; }
+		if x == sign_bit { return f64::NAN; }
+
+		let negative = x & sign_bit != 0;
+		if negative {
+			x = x.wrapping_neg() & mask;
+		}
+
+		// The regime starts after the sign bit
+		let mut pos = n - 1;
+		let first = (x >> (pos - 1)) & 1;
+		let mut run = 0;
+		while pos > 0 && (x >> (pos - 1)) & 1 == first {
+			run += 1;
+			pos -= 1;
+		}
+		// Skip the terminating bit
+		pos = pos.saturating_sub(1);
+		let k = if first == 1 { run - 1 } else { -run };
+
+		// The exponent bits may be cut off at the end. The missing bits are zero.
+		let n_exp = self.es.min(pos);
+		let e = ((x >> (pos - n_exp)) & ((1u64 << n_exp) - 1)) << (self.es - n_exp);
+		pos -= n_exp;
+
+		let fraction = x & ((1u64 << pos) - 1);
+		let value = (1.0 + fraction as f64 / 2f64.powi(pos as i32)) * 2f64.powi(k * (1 << self.es) + e as i32);
+		if negative { -value } else { value }
+	}
+
+	/// Encodes a value by rounding to the nearest posit (ties to even).
+	/// Values beyond the largest or below the smallest posit are clamped, so that no
+	/// value except zero becomes zero. NaN and infinity become NaR.
+	pub fn encode(&self, value: f64) -> u64 {
+		let n = self.n_bits;
+		let mask = (1u64 << n) - 1;
+		let max_pos = (1u64 << (n - 1)) - 1;
+
+		if value == 0.0 { return 0; }
+		if !value.is_finite() { return 1u64 << (n - 1); }
+
+		// Split |value| into 2^scale * (1 + fraction / 2^52)
+		let bits = value.abs().to_bits();
+		let (scale, fraction) = if value.abs() >= f64::MIN_POSITIVE {
+			(((bits >> 52) & 0x7FF) as i32 - 1023, bits & ((1u64 << 52) - 1))
+		} else {
+			let shift = bits.leading_zeros() - 11;
+			(-1022 - shift as i32, (bits << shift) & ((1u64 << 52) - 1))
+		};
+
+		let k = scale >> self.es;
+		let e = (scale - (k << self.es)) as u128;
+		let max_k = n as i32 - 2;
+
+		let body = if k > max_k {
+			max_pos
+		} else if k < -max_k {
+			1
+		} else {
+			// The regime, the exponent and the fraction as one long bit string
+			let (regime, regime_len) = if k >= 0 {
+				(((1u128 << (k + 1)) - 1) << 1, k as u32 + 2)
+			} else {
+				(1u128, (-k) as u32 + 1)
+			};
+			let len = regime_len + self.es + 52;
+			let long = (((regime << self.es) | e) << 52) | fraction as u128;
+
+			// Keep the n - 1 most significant bits and round the rest to nearest even
+			let cut = len - (n - 1);
+			let mut body = (long >> cut) as u64;
+			let half = 1u128 << (cut - 1);
+			let rest = long & ((1u128 << cut) - 1);
+			if rest > half || (rest == half && body & 1 == 1) {
+				body += 1;
+			}
+			body.clamp(1, max_pos)
+		};
+
+		if value < 0.0 { body.wrapping_neg() & mask } else { body }
+	}
+}
+
+/// Extracts a posit, which starts at the given byte and bit offset
+pub fn get_posit<V: ExtractBitsFromVecU8 + ?Sized>(source: &V, byte_offset: u32, bit_offset: u32, format: &PositFormat) -> Result<f64> {
+	Ok(format.decode(source.get_u64(byte_offset, bit_offset, format.bit_len())?))
+}
+
+/// Encodes value as a posit and inserts it at the given byte and bit offset
+pub fn set_posit<V: InsertBitsIntoVecU8 + ?Sized>(target: &mut V, byte_offset: u32, bit_offset: u32, format: &PositFormat, value: f64) -> Result<()> {
+	target.set(byte_offset, bit_offset, format.bit_len(), format.encode(value))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn posit_round_trips() {
+		// All posits of some small formats survive the round trip
+		for &(n, es) in [(8, 0), (8, 2), (6, 1), (5, 3), (12, 5)].iter() {
+			let f = PositFormat::new(n, es).unwrap();
+			for bits in 0..1u64 << n {
+				let v = f.decode(bits);
+				if v.is_nan() {
+					assert_eq!(f.encode(v), 1 << (n - 1));
+				} else {
+					assert_eq!(f.encode(v), bits, "n = {} es = {} bits = {:b}", n, es, bits);
+				}
+			}
+		}
+
+		// sign 0, regime 10, exponent 01, fraction 101 --> 2^1 * 1.625
+		assert_eq!(POSIT8.decode(0b0100_1101), 3.25);
+		assert_eq!(POSIT8.decode(0b0111_1111), 2f64.powi(24));
+		assert_eq!(POSIT8.decode(0b0000_0001), 2f64.powi(-24));
+		assert_eq!(POSIT8.decode(0b1011_0011), -3.25);
+		assert!(POSIT8.decode(0b1000_0000).is_nan());
+		assert_eq!(POSIT16.decode(0x4000), 1.0);
+		assert_eq!(POSIT32.decode(0x7FFF_FFFF), 2f64.powi(120));
+
+		// Clamping and rounding
+		assert_eq!(POSIT8.encode(1e30), 0b0111_1111);
+		assert_eq!(POSIT8.encode(-1e-30), 0b1111_1111);
+		assert_eq!(POSIT8.encode(-1e30), 0b1000_0001);
+		assert_eq!(POSIT8.encode(f64::INFINITY), 0b1000_0000);
+		// 1.0625 is halfway between 1.0 (0100_0000) and 1.125 (0100_0001) --> even
+		assert_eq!(POSIT8.encode(1.0625), 0b0100_0000);
+		assert_eq!(POSIT8.encode(1.07), 0b0100_0001);
+		assert_eq!(POSIT32.encode(std::f64::consts::PI), 0x4C90_FDAA);
+
+		let mut v: Vec<u8> = vec!{ 0x00, 0x00, 0x00 };
+		set_posit(&mut v, 0, 4, &POSIT16, -0.5).unwrap();
+		assert_eq!(get_posit(&v, 0, 4, &POSIT16).unwrap(), -0.5);
+		assert_eq!(v, vec!{ 0x0C, 0x80, 0x00 });
+
+		assert_eq!(PositFormat::new(33, 2).unwrap_err(), FORMAT_MSG);
+		assert_eq!(PositFormat::new(8, 6).unwrap_err(), FORMAT_MSG);
+	}
+}
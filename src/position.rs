@@ -0,0 +1,121 @@
+//! Chained extraction and insertion, which return the position after the field
+//!
+//! The getters of this module take a BitPos and return the value together with the position
+//! after the field, so that consecutive fields can be parsed without computing the offsets by
+//! hand and without a BitReader. The buffer is not borrowed between the calls.
+//!
+//! ```rust
+//! use bitlab::*;
+//! let v: Vec<u8> = vec!{ 0x45, 0x00, 0x05, 0xDC };
+//! let (version, pos) = v.get_next::<u8>(BitPos::default(), 4).unwrap();
+//! let (ihl, pos) = v.get_next::<u8>(pos, 4).unwrap();
+//! let (length, pos) = v.get_next::<u16>(pos.advance(8), 16).unwrap();
+//! assert_eq!((version, ihl, length), (4, 5, 1500));
+//! assert_eq!(pos, BitPos::new(4, 0));
+//! ```
+
+use super::{Result, BitTarget, BitValue, ExtractBitsFromVecU8, LargeOffsets};
+use super::offset64::split;
+
+/// An absolute bit position, where zero is the most significant bit of the first byte
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitPos(u64);
+
+impl BitPos {
+	/// Creates the position of the bit at byte_offset and bit_offset. The bit offset may exceed 7.
+	pub fn new(byte_offset: u32, bit_offset: u32) -> BitPos {
+		BitPos(byte_offset as u64 * 8 + bit_offset as u64)
+	}
+
+	/// Creates a position from the number of bits before it
+	pub fn from_bits(bits: u64) -> BitPos {
+		BitPos(bits)
+	}
+
+	/// Returns the number of bits before the position
+	pub fn bits(self) -> u64 {
+		self.0
+	}
+
+	/// Returns the number of whole bytes before the position
+	pub fn byte_offset(self) -> u64 {
+		self.0 / 8
+	}
+
+	/// Returns the bit offset (0 to 7) within the byte at byte_offset
+	pub fn bit_offset(self) -> u32 {
+		(self.0 % 8) as u32
+	}
+
+	/// Returns the position n bits later, e.g. to skip a reserved field
+	pub fn advance(self, n: u64) -> BitPos {
+		BitPos(self.0.saturating_add(n))
+	}
+}
+
+/// Extracts and inserts bits in a Vec<u8> or a byte slice at a BitPos and returns the position after the field.
+/// See the [module documentation](position/index.html).
+pub trait ChainedBits {
+	/// Extracts length bits at pos as any integer type and returns it with the position after the field.
+	/// See ExtractBitsFromVecU8::get_at.
+	fn get_next<T: BitTarget>(&self, pos: BitPos, length: u32) -> Result<(T, BitPos)>;
+
+	/// Inserts value into length bits at pos and returns the position after the field. See InsertBitsIntoVecU8::set.
+	fn set_next<T: BitValue>(&mut self, pos: BitPos, length: u32, value: T) -> Result<BitPos>;
+}
+
+impl ChainedBits for [u8] {
+	fn get_next<T: BitTarget>(&self, pos: BitPos, length: u32) -> Result<(T, BitPos)> {
+		let (rest, bit_offset) = split(self, pos.0)?;
+		Ok((rest.get_at(0, bit_offset, length)?, pos.advance(length as u64)))
+	}
+
+	fn set_next<T: BitValue>(&mut self, pos: BitPos, length: u32, value: T) -> Result<BitPos> {
+		self.set_at(pos.0, length, value)?;
+		Ok(pos.advance(length as u64))
+	}
+}
+
+// Vectors use the implementation for slices
+impl ChainedBits for Vec<u8> {
+	#[inline]
+	fn get_next<T: BitTarget>(&self, pos: BitPos, length: u32) -> Result<(T, BitPos)> {
+		self.as_slice().get_next(pos, length)
+	}
+
+	#[inline]
+	fn set_next<T: BitValue>(&mut self, pos: BitPos, length: u32, value: T) -> Result<BitPos> {
+		self.as_mut_slice().set_next(pos, length, value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn chained_positions() {
+		let pos = BitPos::new(2, 11);
+		assert_eq!(pos.bits(), 27);
+		assert_eq!((pos.byte_offset(), pos.bit_offset()), (3, 3));
+		assert_eq!(BitPos::from_bits(27), pos);
+		assert_eq!(BitPos::from_bits(u64::MAX).advance(1).bits(), u64::MAX);
+
+		let mut v: Vec<u8> = vec!{ 0x00; 4 };
+		let pos = v.set_next(BitPos::default(), 3, 5u8).unwrap();
+		let pos = v[..].set_next(pos, 13, -2i16).unwrap();
+		let end = v.set_next(pos, 16, 0xBEEFu16).unwrap();
+		assert_eq!(end, BitPos::new(4, 0));
+		assert_eq!(v, vec!{ 0xBF, 0xFE, 0xBE, 0xEF });
+		assert_eq!(v.set_next(end, 1, 1u8).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		let (kind, pos) = v.get_next::<u8>(BitPos::default(), 3).unwrap();
+		let (delta, pos) = v[..].get_next::<i16>(pos, 13).unwrap();
+		let (magic, pos): (u32, BitPos) = v.get_next(pos, 16).unwrap();
+		assert_eq!((kind, delta, magic, pos), (5, -2, 0xBEEF, end));
+		assert_eq!(v.get_next::<i64>(BitPos::new(0, 1), 32).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v.get_next::<u64>(BitPos::new(0, 8), 24).unwrap(), (0xFEBEEF, end));
+		assert_eq!(v.get_next::<u128>(BitPos::from_bits(u64::MAX), 1).unwrap_err(), OUT_OF_RANGE_MSG);
+	}
+}
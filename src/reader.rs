@@ -0,0 +1,373 @@
+//! Sequential reading of bit fields
+
+use super::{Result, BitBuffer, OUT_OF_RANGE_MSG, LEN_TOO_BIG_MSG, read_bits_from_slice};
+
+pub(crate) static EXP_GOLOMB_MSG: &str = "The Exp-Golomb code does not fit into 64 bits";
+static VARINT_MSG: &str = "The varint does not fit into 64 bits";
+static RICE_MSG: &str = "The Rice code does not fit into 64 bits";
+pub(crate) static EMPTY_ALPHABET_MSG: &str = "The alphabet size must not be zero";
+
+/// Reads bit fields one after the other from a byte slice, starting at the **most**
+/// significant bit of the first byte.
+///
+/// A failing read does not move the position, so that the caller may try something else.
+/// For lookahead, the peek functions read without moving the position and reset returns to
+/// the position of the last call of mark.
+///
+/// ```rust
+/// use bitlab::*;
+/// // The start of an H.264 sequence parameter set: profile_idc, flags, level_idc and seq_parameter_set_id
+/// let sps = [0x42u8, 0xC0, 0x1E, 0x98];
+/// let mut r = BitReader::new(&sps);
+/// assert_eq!(r.read_bits(8).unwrap(), 66);
+/// r.skip_bits(8).unwrap();
+/// assert_eq!(r.read_bits(8).unwrap(), 30);
+/// assert_eq!(r.read_ue().unwrap(), 0);
+/// assert_eq!(r.read_ue().unwrap(), 5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+	bytes: &'a [u8],
+	bit_len: usize,
+	position: usize,
+	// The position, which reset returns to
+	mark: usize,
+}
+
+impl<'a> BitReader<'a> {
+	/// Creates a reader on all bits of the bytes
+	pub fn new(bytes: &'a [u8]) -> BitReader<'a> {
+		BitReader { bytes, bit_len: bytes.len() * 8, position: 0, mark: 0 }
+	}
+
+	/// Returns the number of bits read so far
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	/// Returns the number of bits, which have not been read yet
+	pub fn remaining_bits(&self) -> usize {
+		self.bit_len - self.position
+	}
+
+	/// Reads a single bit
+	pub fn read_bit(&mut self) -> Result<bool> {
+		Ok(self.read_bits(1)? == 1)
+	}
+
+	/// Reads length bits (at most 64) and returns them right aligned in a u64
+	pub fn read_bits(&mut self, length: u32) -> Result<u64> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		if length as usize > self.remaining_bits() {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		let value = read_bits_from_slice(self.bytes, self.position, length);
+		self.position += length as usize;
+		Ok(value)
+	}
+
+	/// Reads length bits (at most 64) as a two's complement signed integer
+	pub fn read_signed_bits(&mut self, length: u32) -> Result<i64> {
+		let value = self.read_bits(length)?;
+		if length == 0 {
+			return Ok(0);
+		}
+		// Sign extension
+		Ok(((value << (64 - length)) as i64) >> (64 - length))
+	}
+
+	/// Returns the next bit without moving the position
+	pub fn peek_bit(&self) -> Result<bool> {
+		Ok(self.peek_bits(1)? == 1)
+	}
+
+	/// Returns the next length bits (at most 64) right aligned in a u64 without moving the position
+	pub fn peek_bits(&self, length: u32) -> Result<u64> {
+		self.clone().read_bits(length)
+	}
+
+	/// Returns the next length bits (at most 64) as a two's complement signed integer without moving the position
+	pub fn peek_signed_bits(&self, length: u32) -> Result<i64> {
+		self.clone().read_signed_bits(length)
+	}
+
+	/// Remembers the current position for reset. Initially, the mark is at the start.
+	pub fn mark(&mut self) {
+		self.mark = self.position;
+	}
+
+	/// Returns to the position of the last call of mark, e.g. after a failed attempt to parse something
+	pub fn reset(&mut self) {
+		self.position = self.mark;
+	}
+
+	/// Skips n bits
+	pub fn skip_bits(&mut self, n: usize) -> Result<()> {
+		if n > self.remaining_bits() {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		self.position += n;
+		Ok(())
+	}
+
+	/// Skips the bits up to the next byte boundary
+	pub fn align_to_byte(&mut self) -> Result<()> {
+		self.skip_bits((8 - self.position % 8) % 8)
+	}
+
+	// Runs a read, which consists of several steps, and restores the position on error
+	fn atomic<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		let start = self.position;
+		let result = read(self);
+		if result.is_err() {
+			self.position = start;
+		}
+		result
+	}
+
+	/// Reads a unary code, which is n zeros followed by a one, and returns n
+	pub fn read_unary(&mut self) -> Result<u64> {
+		self.atomic(|r| {
+			let mut n = 0;
+			while !r.read_bit()? {
+				n += 1;
+			}
+			Ok(n)
+		})
+	}
+
+	/// Reads a truncated binary code of a value smaller than the alphabet size n.
+	/// With k = floor(log2(n)) the first 2^(k + 1) - n values are coded in k bits
+	/// and the others in k + 1 bits.
+	pub fn read_truncated_binary(&mut self, n: u64) -> Result<u64> {
+		if n == 0 {
+			return Err(s!(EMPTY_ALPHABET_MSG));
+		}
+		let k = 63 - n.leading_zeros();
+		let short_codes = ((1u128 << (k + 1)) - n as u128) as u64;
+		self.atomic(|r| {
+			let value = r.read_bits(k)?;
+			if value < short_codes {
+				Ok(value)
+			} else {
+				Ok(((value << 1) | r.read_bits(1)?) - short_codes)
+			}
+		})
+	}
+
+	/// Reads an unsigned Exp-Golomb code ue(v) as used in H.264 and H.265 headers
+	pub fn read_ue(&mut self) -> Result<u64> {
+		self.atomic(|r| {
+			// The number of leading zeros is the number of bits after the first one
+			let mut leading_zeros = 0;
+			while !r.read_bit()? {
+				leading_zeros += 1;
+				if leading_zeros > 63 {
+					return Err(s!(EXP_GOLOMB_MSG));
+				}
+			}
+			let rest = r.read_bits(leading_zeros)?;
+			Ok(((1u64 << leading_zeros) - 1) + rest)
+		})
+	}
+
+	/// Reads a signed Exp-Golomb code se(v) as used in H.264 and H.265 headers.
+	/// The codes 1, 2, 3, 4 ... of ue(v) map to 1, -1, 2, -2 ...
+	pub fn read_se(&mut self) -> Result<i64> {
+		let code = self.read_ue()?;
+		if code % 2 == 1 {
+			Ok(code.div_ceil(2) as i64)
+		} else {
+			Ok(-((code / 2) as i64))
+		}
+	}
+
+	/// Reads a base 128 varint (LEB128) as used by protocol buffers. Each group of 8 bits carries
+	/// 7 bits of the value, starting with the least significant ones, and a continuation flag in
+	/// its most significant bit. The groups do not need to be aligned to bytes.
+	pub fn read_varint(&mut self) -> Result<u64> {
+		self.atomic(|r| {
+			let mut value = 0u64;
+			for i in 0..10 {
+				let group = r.read_bits(8)?;
+				// The tenth group may only contribute the most significant bit of the value
+				if i == 9 && group > 1 {
+					return Err(s!(VARINT_MSG));
+				}
+				value |= (group & 0x7F) << (7 * i);
+				if group & 0x80 == 0 {
+					return Ok(value);
+				}
+			}
+			Err(s!(VARINT_MSG))
+		})
+	}
+
+	/// Reads a zigzag encoded signed varint (sint64 of protocol buffers).
+	/// The varints 0, 1, 2, 3, 4 ... map to 0, -1, 1, -2, 2 ...
+	pub fn read_zigzag_varint(&mut self) -> Result<i64> {
+		let n = self.read_varint()?;
+		Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+	}
+
+	/// Reads a Rice code with the parameter k (at most 64) as used in FLAC and Shorten.
+	/// The quotient value >> k is coded in unary as that many zeros followed by a one,
+	/// the remainder in the k least significant bits.
+	pub fn read_rice(&mut self, k: u32) -> Result<u64> {
+		if k > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		self.atomic(|r| {
+			let quotient = r.read_unary()?;
+			if quotient != 0 && quotient.leading_zeros() < k {
+				return Err(s!(RICE_MSG));
+			}
+			let remainder = r.read_bits(k)?;
+			if k == 64 {
+				Ok(remainder)
+			} else {
+				Ok((quotient << k) | remainder)
+			}
+		})
+	}
+
+	/// Reads a signed Rice code with the parameter k (at most 64) as used in FLAC.
+	/// The unsigned values 0, 1, 2, 3, 4 ... map to 0, -1, 1, -2, 2 ...
+	pub fn read_signed_rice(&mut self, k: u32) -> Result<i64> {
+		let n = self.read_rice(k)?;
+		Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+	}
+}
+
+impl<'a> From<&'a BitBuffer> for BitReader<'a> {
+	/// Creates a reader on the bits of the buffer
+	fn from(buffer: &'a BitBuffer) -> BitReader<'a> {
+		BitReader { bytes: buffer.as_bytes(), bit_len: buffer.bit_len(), position: 0, mark: 0 }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reading_bit_fields() {
+		let data = [0x48u8, 0x61, 0x6C];
+		let mut r = BitReader::new(&data);
+		assert_eq!(r.read_bits(4).unwrap(), 4);
+		assert!(r.read_bit().unwrap());
+		assert_eq!(r.read_signed_bits(3).unwrap(), 0);
+		r.skip_bits(7).unwrap();
+		assert_eq!(r.read_signed_bits(3).unwrap(), -3);
+		assert_eq!(r.position(), 18);
+		r.align_to_byte().unwrap();
+		assert_eq!(r.remaining_bits(), 0);
+		r.align_to_byte().unwrap();
+		assert_eq!(r.read_bit().unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(r.read_bits(65).is_err());
+
+		// 1, 010, 011, 00100, 00101, 00111, 0001000 --> ue: 0 1 2 3 4 6 7, se: 0 1 -1 2 -2 -3 4
+		let codes = [0b1010_0110, 0b0100_0010, 0b1001_1100, 0b0100_0000];
+		let mut r = BitReader::new(&codes);
+		let ue: Vec<u64> = (0..7).map(|_| r.read_ue().unwrap()).collect();
+		assert_eq!(ue, vec!{ 0, 1, 2, 3, 4, 6, 7 });
+		let mut r = BitReader::new(&codes);
+		let se: Vec<i64> = (0..7).map(|_| r.read_se().unwrap()).collect();
+		assert_eq!(se, vec!{ 0, 1, -1, 2, -2, -3, 4 });
+
+		// The truncated code does not move the position
+		assert_eq!(r.read_ue().unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(r.position(), 29);
+		let zeros = [0u8; 9];
+		assert_eq!(BitReader::new(&zeros).read_ue().unwrap_err(), EXP_GOLOMB_MSG);
+	}
+
+	#[test]
+	fn peeking_and_backtracking() {
+		let data = [0b1011_0011u8, 0xF0];
+		let mut r = BitReader::new(&data);
+		assert!(r.peek_bit().unwrap());
+		assert_eq!(r.peek_bits(4).unwrap(), 0b1011);
+		assert_eq!(r.peek_signed_bits(4).unwrap(), -5);
+		assert_eq!(r.position(), 0);
+		assert_eq!(r.peek_bits(17).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert!(r.peek_bits(65).is_err());
+
+		r.skip_bits(4).unwrap();
+		r.mark();
+		assert_eq!(r.read_bits(8).unwrap(), 0x3F);
+		assert_eq!(r.read_unary().unwrap_err(), OUT_OF_RANGE_MSG);
+		r.reset();
+		assert_eq!(r.position(), 4);
+		assert_eq!(r.read_ue().unwrap(), 6);
+		// The mark stays until it is moved
+		r.reset();
+		assert_eq!(r.peek_bits(12).unwrap(), 0x3F0);
+		assert!(!r.clone().peek_bit().unwrap());
+
+		let mut r = BitReader::new(&data);
+		r.skip_bits(16).unwrap();
+		assert_eq!(r.peek_bit().unwrap_err(), OUT_OF_RANGE_MSG);
+		r.reset();
+		assert_eq!(r.position(), 0);
+	}
+
+	#[test]
+	fn reading_varints() {
+		// 300 = 0xAC 0x02 and 1 after a 3 bit header
+		let data = [0b1011_0101, 0b1000_0000, 0b0100_0000, 0b0010_0000];
+		let mut r = BitReader::new(&data);
+		assert_eq!(r.read_bits(3).unwrap(), 0b101);
+		assert_eq!(r.read_varint().unwrap(), 300);
+		assert_eq!(r.read_zigzag_varint().unwrap(), -1);
+
+		let max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+		assert_eq!(BitReader::new(&max).read_varint().unwrap(), u64::MAX);
+		assert_eq!(BitReader::new(&max).read_zigzag_varint().unwrap(), i64::MIN);
+		let too_big = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+		assert_eq!(BitReader::new(&too_big).read_varint().unwrap_err(), VARINT_MSG);
+		let mut r = BitReader::new(&too_big[..3]);
+		assert_eq!(r.read_varint().unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(r.position(), 0);
+	}
+
+	#[test]
+	fn reading_unary_and_truncated_binary_codes() {
+		// Unary 3, 0 and truncated binary codes for n = 5: 00 --> 0, 111 --> 4, 01 --> 1
+		let data = [0b0001_1001, 0b1101_0000];
+		let mut r = BitReader::new(&data);
+		assert_eq!(r.read_unary().unwrap(), 3);
+		assert_eq!(r.read_unary().unwrap(), 0);
+		assert_eq!(r.read_truncated_binary(5).unwrap(), 0);
+		assert_eq!(r.read_truncated_binary(5).unwrap(), 4);
+		assert_eq!(r.read_truncated_binary(5).unwrap(), 1);
+		assert_eq!(r.position(), 12);
+		// A single value does not need any bits
+		assert_eq!(r.read_truncated_binary(1).unwrap(), 0);
+		assert_eq!(r.read_truncated_binary(0).unwrap_err(), EMPTY_ALPHABET_MSG);
+		assert_eq!(r.read_unary().unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(r.position(), 12);
+	}
+
+	#[test]
+	fn reading_rice_codes() {
+		// k = 2: 0001 10 --> 14, 1 01 --> 1, 01 11 --> -4 as signed
+		let data = [0b0001_1010, 0b1011_1000];
+		let mut r = BitReader::new(&data);
+		assert_eq!(r.read_rice(2).unwrap(), 14);
+		assert_eq!(r.read_rice(2).unwrap(), 1);
+		assert_eq!(r.read_signed_rice(2).unwrap(), -4);
+		assert_eq!(r.position(), 13);
+		// Only zeros are left
+		assert_eq!(r.read_rice(0).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(r.position(), 13);
+		assert!(r.read_rice(65).is_err());
+
+		let two = [0b0010_0000u8];
+		assert_eq!(BitReader::new(&two).read_rice(63).unwrap_err(), RICE_MSG);
+		let max = [0xFFu8; 9];
+		assert_eq!(BitReader::new(&max).read_rice(64).unwrap(), u64::MAX);
+	}
+}
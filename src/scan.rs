@@ -0,0 +1,329 @@
+//! Scanning for set and cleared bits and for bit patterns
+//!
+//! Bit indices count from the **most** significant bit of the first byte. The functions skip
+//! whole bytes, which do not contain a matching bit, so that large allocation bitmaps can be
+//! searched quickly.
+//!
+//! ```rust
+//! use bitlab::*;
+//! // An allocation bitmap, where a set bit is a used block
+//! let bitmap: Vec<u8> = vec!{ 0xFF, 0xFF, 0xF7, 0xFF };
+//! assert_eq!(bitmap.first_zero(0), Some(20));
+//! assert_eq!(bitmap.first_zero(21), None);
+//! assert_eq!(bitmap.last_one(20), Some(19));
+//! ```
+
+use super::{Result, BitBuffer, LEN_ZERO, LEN_TOO_BIG_MSG, read_bits_from_slice, check_value_fits};
+
+// Returns the index of the first bit at or after from, which equals the bit, among the first bit_len bits
+fn find_first(bytes: &[u8], bit_len: usize, from: usize, bit: bool) -> Option<usize> {
+	if from >= bit_len {
+		return None;
+	}
+	let invert = if bit { 0x00 } else { 0xFF };
+	let mut i = from / 8;
+	let mut byte = (bytes[i] ^ invert) & (0xFF >> (from % 8));
+	loop {
+		if byte != 0 {
+			let index = i * 8 + byte.leading_zeros() as usize;
+			return if index < bit_len { Some(index) } else { None };
+		}
+		i += 1;
+		if i * 8 >= bit_len {
+			return None;
+		}
+		byte = bytes[i] ^ invert;
+	}
+}
+
+// Returns the index of the last bit before end, which equals the bit, among the first bit_len bits
+fn find_last(bytes: &[u8], bit_len: usize, end: usize, bit: bool) -> Option<usize> {
+	let end = std::cmp::min(end, bit_len);
+	if end == 0 {
+		return None;
+	}
+	let invert = if bit { 0x00 } else { 0xFF };
+	let mut i = (end - 1) / 8;
+	let mut byte = (bytes[i] ^ invert) & (0xFFu8 << (8 - (end - i * 8)));
+	loop {
+		if byte != 0 {
+			return Some(i * 8 + 7 - byte.trailing_zeros() as usize);
+		}
+		if i == 0 {
+			return None;
+		}
+		i -= 1;
+		byte = bytes[i] ^ invert;
+	}
+}
+
+// Returns the index of the first occurrence of the pattern at or after from among the first bit_len bits.
+// If there is a mask, only the bits, which are set in it, are compared.
+pub(crate) fn find_bits(bytes: &[u8], bit_len: usize, pattern: u64, mask: Option<u64>, pattern_len: u32, from: usize) -> Result<Option<usize>> {
+	if pattern_len == 0 {
+		return Err(s!(LEN_ZERO));
+	}
+	if pattern_len > 64 {
+		return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+	}
+	check_value_fits(pattern, pattern_len)?;
+	if let Some(mask) = mask {
+		check_value_fits(mask, pattern_len)?;
+	}
+
+	let len = pattern_len as usize;
+	if from > bit_len || bit_len - from < len {
+		return Ok(None);
+	}
+
+	// Shift the following bits one by one into a window of pattern_len bits
+	let window_mask = u64::MAX >> (64 - pattern_len);
+	let mask = mask.unwrap_or(window_mask);
+	let pattern = pattern & mask;
+	let mut window = read_bits_from_slice(bytes, from, pattern_len);
+	let mut index = from;
+	loop {
+		if window & mask == pattern {
+			return Ok(Some(index));
+		}
+		let next = index + len;
+		if next >= bit_len {
+			return Ok(None);
+		}
+		let bit = (bytes[next / 8] >> (7 - next % 8)) & 1;
+		window = ((window << 1) | bit as u64) & window_mask;
+		index += 1;
+	}
+}
+
+/// Parses a pattern like "101x_x1x0" with up to 64 bits, where 'x' is a wildcard bit and
+/// underscores separate groups of bits. Returns the pattern, the mask of the compared bits and
+/// the length of the pattern for find_masked_pattern.
+pub fn parse_pattern(text: &str) -> Result<(u64, u64, u32)> {
+	let mut pattern: u64 = 0;
+	let mut mask: u64 = 0;
+	let mut length: u32 = 0;
+	for c in text.chars().filter(|&c| c != '_') {
+		if length == 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		let (bit, care) = match c {
+			'0' => (0, 1),
+			'1' => (1, 1),
+			'x' | 'X' => (0, 0),
+			_ => return Err(format!("Invalid character {} in the pattern {}", c, text)),
+		};
+		pattern = (pattern << 1) | bit;
+		mask = (mask << 1) | care;
+		length += 1;
+	}
+	if length == 0 {
+		return Err(s!(LEN_ZERO));
+	}
+	Ok((pattern, mask, length))
+}
+
+/// Finds set and cleared bits and bit patterns in a Vec<u8>, a byte slice or a BitBuffer.
+/// See the [module documentation](scan/index.html).
+pub trait BitScan {
+	/// Returns the index of the first set bit at or after from_bit or None, if there is none
+	fn first_one(&self, from_bit: usize) -> Option<usize>;
+
+	/// Returns the index of the first cleared bit at or after from_bit or None, if there is none
+	fn first_zero(&self, from_bit: usize) -> Option<usize>;
+
+	/// Returns the index of the last set bit before end_bit or None, if there is none.
+	/// Use usize::MAX to search from the end.
+	fn last_one(&self, end_bit: usize) -> Option<usize>;
+
+	/// Returns the index of the last cleared bit before end_bit or None, if there is none.
+	/// Use usize::MAX to search from the end.
+	fn last_zero(&self, end_bit: usize) -> Option<usize>;
+
+	/// Returns the index of the first occurrence of the pattern_len least significant bits of
+	/// pattern at or after from_bit, which may start at any bit offset, or None, if there is none.
+	/// Returns an error if pattern_len is zero or more than 64 or if the pattern does not fit into it.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// // An MPEG start code 0x000001 shifted by three bits
+	/// let capture: Vec<u8> = vec!{ 0xE0, 0x00, 0x00, 0x3F };
+	/// assert_eq!(capture.find_pattern(0x00_0001, 24, 0).unwrap(), Some(3));
+	/// assert_eq!(capture.find_pattern(0x00_0001, 24, 4).unwrap(), None);
+	/// ```
+	fn find_pattern(&self, pattern: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>>;
+
+	/// Same as find_pattern, but only compares the bits, which are set in mask. The other bits
+	/// of the pattern are wildcards. See parse_pattern to create pattern and mask from a string.
+	///
+	/// ```rust
+	/// use bitlab::*;
+	/// use bitlab::scan::parse_pattern;
+	/// let (pattern, mask, length) = parse_pattern("101x_x1x0").unwrap();
+	/// let v: Vec<u8> = vec!{ 0x0A, 0x64 };
+	/// assert_eq!(v.find_masked_pattern(pattern, mask, length, 0).unwrap(), Some(4));
+	/// ```
+	fn find_masked_pattern(&self, pattern: u64, mask: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>>;
+}
+
+impl BitScan for [u8] {
+	fn first_one(&self, from_bit: usize) -> Option<usize> {
+		find_first(self, self.len() * 8, from_bit, true)
+	}
+
+	fn first_zero(&self, from_bit: usize) -> Option<usize> {
+		find_first(self, self.len() * 8, from_bit, false)
+	}
+
+	fn last_one(&self, end_bit: usize) -> Option<usize> {
+		find_last(self, self.len() * 8, end_bit, true)
+	}
+
+	fn last_zero(&self, end_bit: usize) -> Option<usize> {
+		find_last(self, self.len() * 8, end_bit, false)
+	}
+
+	fn find_pattern(&self, pattern: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>> {
+		find_bits(self, self.len() * 8, pattern, None, pattern_len, from_bit)
+	}
+
+	fn find_masked_pattern(&self, pattern: u64, mask: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>> {
+		find_bits(self, self.len() * 8, pattern, Some(mask), pattern_len, from_bit)
+	}
+}
+
+// Vectors use the implementation for slices
+impl BitScan for Vec<u8> {
+	#[inline]
+	fn first_one(&self, from_bit: usize) -> Option<usize> {
+		self.as_slice().first_one(from_bit)
+	}
+
+	#[inline]
+	fn first_zero(&self, from_bit: usize) -> Option<usize> {
+		self.as_slice().first_zero(from_bit)
+	}
+
+	#[inline]
+	fn last_one(&self, end_bit: usize) -> Option<usize> {
+		self.as_slice().last_one(end_bit)
+	}
+
+	#[inline]
+	fn last_zero(&self, end_bit: usize) -> Option<usize> {
+		self.as_slice().last_zero(end_bit)
+	}
+
+	#[inline]
+	fn find_pattern(&self, pattern: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>> {
+		self.as_slice().find_pattern(pattern, pattern_len, from_bit)
+	}
+
+	#[inline]
+	fn find_masked_pattern(&self, pattern: u64, mask: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>> {
+		self.as_slice().find_masked_pattern(pattern, mask, pattern_len, from_bit)
+	}
+}
+
+// The padding bits of the last byte are never found
+impl BitScan for BitBuffer {
+	fn first_one(&self, from_bit: usize) -> Option<usize> {
+		find_first(self.as_bytes(), self.bit_len(), from_bit, true)
+	}
+
+	fn first_zero(&self, from_bit: usize) -> Option<usize> {
+		find_first(self.as_bytes(), self.bit_len(), from_bit, false)
+	}
+
+	fn last_one(&self, end_bit: usize) -> Option<usize> {
+		find_last(self.as_bytes(), self.bit_len(), end_bit, true)
+	}
+
+	fn last_zero(&self, end_bit: usize) -> Option<usize> {
+		find_last(self.as_bytes(), self.bit_len(), end_bit, false)
+	}
+
+	fn find_pattern(&self, pattern: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>> {
+		find_bits(self.as_bytes(), self.bit_len(), pattern, None, pattern_len, from_bit)
+	}
+
+	fn find_masked_pattern(&self, pattern: u64, mask: u64, pattern_len: u32, from_bit: usize) -> Result<Option<usize>> {
+		find_bits(self.as_bytes(), self.bit_len(), pattern, Some(mask), pattern_len, from_bit)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scanning_for_bits() {
+		let v: Vec<u8> = vec!{ 0x00, 0x00, 0x10, 0x00, 0x81 };
+		assert_eq!(v.first_one(0), Some(19));
+		assert_eq!(v.first_one(19), Some(19));
+		assert_eq!(v.first_one(20), Some(32));
+		assert_eq!(v.first_one(33), Some(39));
+		assert_eq!(v.first_one(40), None);
+		assert_eq!(v.last_one(usize::MAX), Some(39));
+		assert_eq!(v.last_one(39), Some(32));
+		assert_eq!(v.last_one(32), Some(19));
+		assert_eq!(v.last_one(19), None);
+		assert_eq!(v.first_zero(32), Some(33));
+		assert_eq!(v.last_zero(usize::MAX), Some(38));
+		assert_eq!(v.last_zero(20), Some(18));
+
+		let ones = [0xFFu8; 3];
+		assert_eq!(ones[..].first_zero(0), None);
+		assert_eq!(ones[..].last_zero(24), None);
+		assert_eq!(Vec::<u8>::new().first_one(0), None);
+		assert_eq!(Vec::<u8>::new().last_one(usize::MAX), None);
+
+		// The padding bits of a BitBuffer are never found
+		let b = BitBuffer::from_bools(&[true, true, true, false, true]);
+		assert_eq!(b.first_zero(4), None);
+		assert_eq!(b.last_zero(usize::MAX), Some(3));
+		assert_eq!(b.last_one(usize::MAX), Some(4));
+		assert_eq!(b.first_one(5), None);
+	}
+
+	#[test]
+	fn searching_patterns() {
+		// Two transport stream packets with the sync byte 0x47, the second one shifted by 5 bits
+		let v: Vec<u8> = vec!{ 0x47, 0x00, 0x00, 0x00, 0x02, 0x38, 0x00, 0x00 };
+		assert_eq!(v.find_pattern(0x47, 8, 0).unwrap(), Some(0));
+		assert_eq!(v.find_pattern(0x47, 8, 1).unwrap(), Some(37));
+		assert_eq!(v.find_pattern(0x47, 8, 38).unwrap(), None);
+		assert_eq!(v.find_pattern(0, 24, 45).unwrap(), None);
+		assert_eq!(v.find_pattern(0, 19, 45).unwrap(), Some(45));
+		assert_eq!(v[..].find_pattern(0b1, 1, 65).unwrap(), None);
+		assert_eq!(v.find_pattern(0, 64, 0).unwrap(), None);
+		assert_eq!(v.find_pattern(0, 0, 0).unwrap_err(), LEN_ZERO);
+		assert!(v.find_pattern(0x100, 8, 0).is_err());
+		assert!(v.find_pattern(0, 65, 0).is_err());
+
+		// The padding bits of a BitBuffer are not part of the stream
+		let b = BitBuffer::from_bools(&[true, false, true]);
+		assert_eq!(b.find_pattern(0b10, 2, 0).unwrap(), Some(0));
+		assert_eq!(b.find_pattern(0b10, 2, 1).unwrap(), None);
+	}
+
+	#[test]
+	fn searching_masked_patterns() {
+		assert_eq!(parse_pattern("101x_x1x0").unwrap(), (0b1010_0100, 0b1110_0101, 8));
+		assert_eq!(parse_pattern("X").unwrap(), (0, 0, 1));
+		assert!(parse_pattern("10z").is_err());
+		assert_eq!(parse_pattern("__").unwrap_err(), LEN_ZERO);
+		assert!(parse_pattern(&"1".repeat(65)).is_err());
+		assert_eq!(parse_pattern(&"1".repeat(64)).unwrap(), (u64::MAX, u64::MAX, 64));
+
+		let v: Vec<u8> = vec!{ 0b0001_0111, 0b1100_0000 };
+		let (pattern, mask, length) = parse_pattern("1x11").unwrap();
+		assert_eq!(v.find_masked_pattern(pattern, mask, length, 0).unwrap(), Some(3));
+		assert_eq!(v.find_masked_pattern(pattern, mask, length, 4).unwrap(), Some(5));
+		assert_eq!(v.find_masked_pattern(pattern, mask, length, 7).unwrap(), None);
+
+		// A mask without any bits matches everywhere
+		assert_eq!(v.find_masked_pattern(0, 0, 16, 0).unwrap(), Some(0));
+		assert!(v.find_masked_pattern(0, 0x1F, 4, 0).is_err());
+	}
+}
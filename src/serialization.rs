@@ -0,0 +1,80 @@
+//! Serialization of bit sequences with [serde](https://crates.io/crates/serde). Requires the feature **serde**.
+//!
+//! BitBuffer and BitSlice are serialized as a struct of the bytes and the bit length, so that
+//! the exact bit length survives the round trip. A BitSlice is deserialized as a BitBuffer.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use super::{BitBuffer, BitSlice};
+
+impl Serialize for BitBuffer {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("BitBuffer", 2)?;
+		state.serialize_field("bytes", self.as_bytes())?;
+		state.serialize_field("bit_len", &self.bit_len())?;
+		state.end()
+	}
+}
+
+impl Serialize for BitSlice<'_> {
+	/// Serializes the bits like a BitBuffer, i.e. the first bit is moved to the start of the first byte
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		self.to_buffer().serialize(serializer)
+	}
+}
+
+// The serialized form of a BitBuffer before it is checked
+#[derive(Deserialize)]
+#[serde(rename = "BitBuffer")]
+struct RawBitBuffer {
+	bytes: Vec<u8>,
+	bit_len: usize,
+}
+
+impl<'de> Deserialize<'de> for BitBuffer {
+	/// Fails if there are less than bit_len bits. Surplus bytes and the unused bits of the last byte are discarded.
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<BitBuffer, D::Error> {
+		let raw = RawBitBuffer::deserialize(deserializer)?;
+		BitBuffer::from_bytes(raw.bytes, raw.bit_len).map_err(D::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_test::{assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token};
+
+	#[test]
+	fn serializing_bits() {
+		let buffer = BitBuffer::from_bytes(vec!{ 0xAB, 0xC0 }, 10).unwrap();
+		assert_tokens(&buffer, &[
+			Token::Struct { name: "BitBuffer", len: 2 },
+			Token::Str("bytes"),
+			Token::Seq { len: Some(2) }, Token::U8(0xAB), Token::U8(0xC0), Token::SeqEnd,
+			Token::Str("bit_len"),
+			Token::U64(10),
+			Token::StructEnd,
+		]);
+
+		let bytes = [0x0F, 0xF0];
+		let slice = BitSlice::new(&bytes, 4, 6).unwrap();
+		assert_ser_tokens(&slice, &[
+			Token::Struct { name: "BitBuffer", len: 2 },
+			Token::Str("bytes"),
+			Token::Seq { len: Some(1) }, Token::U8(0xFC), Token::SeqEnd,
+			Token::Str("bit_len"),
+			Token::U64(6),
+			Token::StructEnd,
+		]);
+
+		assert_de_tokens_error::<BitBuffer>(&[
+			Token::Struct { name: "BitBuffer", len: 2 },
+			Token::Str("bytes"),
+			Token::Seq { len: Some(1) }, Token::U8(0xFF), Token::SeqEnd,
+			Token::Str("bit_len"),
+			Token::U64(9),
+			Token::StructEnd,
+		], "Out of range");
+	}
+}
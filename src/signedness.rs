@@ -0,0 +1,101 @@
+//! Encodings of signed integers in bit fields
+
+use super::{Result, LEN_ZERO, LEN_TOO_BIG_MSG, check_value_fits};
+
+/// How a signed value is encoded when it is inserted with InsertBitsIntoVecU8::set_signed
+///
+/// ```rust
+/// use bitlab::*;
+/// assert_eq!(SignednessPolicy::TwosComplement.encode(-3, 4).unwrap(), 0b1101);
+/// assert_eq!(SignednessPolicy::SignMagnitude.encode(-3, 4).unwrap(), 0b1011);
+/// assert!(SignednessPolicy::Reject.encode(-3, 4).is_err());
+/// // Without a sign bit, all bits are available for the magnitude
+/// assert_eq!(SignednessPolicy::Reject.encode(15, 4).unwrap(), 0b1111);
+/// assert!(SignednessPolicy::TwosComplement.encode(15, 4).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SignednessPolicy {
+	/// Two's complement. A field of n bits holds -2^(n-1) to 2^(n-1) - 1. This is stricter than set, which
+	/// keeps its original check and accepts 2^(n-1), although its bits are read back as -2^(n-1).
+	TwosComplement,
+	/// The most significant bit is the sign, the other bits hold the magnitude. A field of n bits holds
+	/// -(2^(n-1) - 1) to 2^(n-1) - 1. Zero is always encoded with a cleared sign bit.
+	SignMagnitude,
+	/// The field is unsigned and negative values are rejected. A field of n bits holds 0 to 2^n - 1.
+	Reject,
+}
+
+impl SignednessPolicy {
+	/// Returns the bits of value in a field of length bits (at most 64), right aligned.
+	/// Returns an error if the value can not be represented in the field.
+	pub fn encode(self, value: i64, length: u32) -> Result<u64> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		let mask = u64::MAX >> (64 - length);
+
+		match self {
+			SignednessPolicy::TwosComplement => {
+				let required = 65 - if value < 0 { (!value).leading_zeros() } else { value.leading_zeros() };
+				if required > length {
+					return Err(format!("Failed to insert {} as a {} bit two's complement integer variable, since it requires at least {} bits.",
+						value, length, required));
+				}
+				Ok(value as u64 & mask)
+			},
+			SignednessPolicy::SignMagnitude => {
+				let magnitude = value.unsigned_abs();
+				let required = 65 - magnitude.leading_zeros();
+				if required > length {
+					return Err(format!("Failed to insert {} as a {} bit sign-magnitude integer variable, since it requires at least {} bits.",
+						value, length, required));
+				}
+				Ok(((value < 0) as u64) << (length - 1) | magnitude)
+			},
+			SignednessPolicy::Reject => {
+				if value < 0 {
+					return Err(format!("Failed to insert {} as a {} bit unsigned integer variable, since it is negative.", value, length));
+				}
+				check_value_fits(value as u64, length)?;
+				Ok(value as u64)
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encoding_signed_values() {
+		use SignednessPolicy::*;
+
+		assert_eq!(TwosComplement.encode(-4, 3).unwrap(), 0b100);
+		assert!(TwosComplement.encode(-5, 3).is_err());
+		assert_eq!(TwosComplement.encode(4, 3).unwrap_err(),
+			"Failed to insert 4 as a 3 bit two's complement integer variable, since it requires at least 4 bits.");
+		assert_eq!(TwosComplement.encode(-1, 1).unwrap(), 1);
+		assert!(TwosComplement.encode(1, 1).is_err());
+		assert_eq!(TwosComplement.encode(i64::MIN, 64).unwrap(), 1 << 63);
+
+		assert_eq!(SignMagnitude.encode(-3, 3).unwrap(), 0b111);
+		assert_eq!(SignMagnitude.encode(3, 3).unwrap(), 0b011);
+		assert_eq!(SignMagnitude.encode(0, 1).unwrap(), 0);
+		assert_eq!(SignMagnitude.encode(-4, 3).unwrap_err(),
+			"Failed to insert -4 as a 3 bit sign-magnitude integer variable, since it requires at least 4 bits.");
+		assert_eq!(SignMagnitude.encode(-i64::MAX, 64).unwrap(), u64::MAX);
+		assert!(SignMagnitude.encode(i64::MIN, 64).is_err());
+
+		assert_eq!(Reject.encode(7, 3).unwrap(), 7);
+		assert!(Reject.encode(8, 3).is_err());
+		assert_eq!(Reject.encode(-1, 8).unwrap_err(), "Failed to insert -1 as a 8 bit unsigned integer variable, since it is negative.");
+		assert_eq!(Reject.encode(i64::MAX, 64).unwrap(), i64::MAX as u64);
+
+		assert_eq!(Reject.encode(0, 0).unwrap_err(), LEN_ZERO);
+		assert!(TwosComplement.encode(0, 65).is_err());
+	}
+}
@@ -0,0 +1,155 @@
+//! Sequential writing of bit fields to any io::Write
+
+use std::io::Write;
+use super::{Result, LEN_TOO_BIG_MSG};
+
+static WRITE_ERROR_MSG: &str = "Failed to write to the sink: ";
+
+// The number of complete bytes, which are collected before they are passed to the writer
+const CHUNK_SIZE: usize = 4096;
+
+/// Appends bit fields one after the other to an io::Write like a File, starting at the **most**
+/// significant bit of the first byte. Complete bytes are passed to the writer in chunks, so that
+/// large bitstreams do not have to be kept in memory.
+///
+/// The last byte is usually incomplete. flush_with_padding fills it up with padding bits and
+/// passes all bytes to the writer. Bits, which have not been flushed, are lost when the sink is dropped.
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut sink = BitSink::new(Vec::new());
+/// sink.write_bits(0b101, 3).unwrap();
+/// sink.write_bits(0xFF, 8).unwrap();
+/// sink.flush_with_padding(false).unwrap();
+/// assert_eq!(sink.bit_len(), 16);
+/// assert_eq!(sink.into_inner(), vec!{ 0xBF, 0xE0 });
+/// ```
+#[derive(Debug)]
+pub struct BitSink<W: Write> {
+	writer: W,
+	// The complete bytes, which have not been passed to the writer yet
+	bytes: Vec<u8>,
+	// The bits of the incomplete byte, right aligned
+	pending: u8,
+	pending_len: u32,
+	bit_len: usize,
+}
+
+impl<W: Write> BitSink<W> {
+	/// Creates a sink, which writes to the writer
+	pub fn new(writer: W) -> BitSink<W> {
+		BitSink { writer, bytes: Vec::with_capacity(CHUNK_SIZE), pending: 0, pending_len: 0, bit_len: 0 }
+	}
+
+	/// Returns the number of bits written so far including the padding bits
+	pub fn bit_len(&self) -> usize {
+		self.bit_len
+	}
+
+	/// Appends a single bit
+	pub fn write_bit(&mut self, bit: bool) -> Result<()> {
+		self.write_bits(bit as u64, 1)
+	}
+
+	/// Appends the length (at most 64) least significant bits of value
+	pub fn write_bits(&mut self, value: u64, length: u32) -> Result<()> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+
+		let mut remaining = length;
+		while remaining > 0 {
+			let n = std::cmp::min(8 - self.pending_len, remaining);
+			let bits = (value >> (remaining - n)) & ((1 << n) - 1);
+			self.pending = ((self.pending as u64) << n | bits) as u8;
+			self.pending_len += n;
+			remaining -= n;
+			if self.pending_len == 8 {
+				self.bytes.push(self.pending);
+				self.pending = 0;
+				self.pending_len = 0;
+			}
+		}
+		self.bit_len += length as usize;
+
+		if self.bytes.len() >= CHUNK_SIZE {
+			self.write_bytes()?;
+		}
+		Ok(())
+	}
+
+	/// Fills up the incomplete last byte with padding bits, which are ones if bit is true or
+	/// zeros otherwise, and passes all bytes to the writer, which is flushed as well.
+	/// Nothing is padded if the number of bits is a multiple of 8.
+	pub fn flush_with_padding(&mut self, bit: bool) -> Result<()> {
+		if self.pending_len > 0 {
+			let padding = 8 - self.pending_len;
+			self.write_bits(if bit { u64::MAX } else { 0 }, padding)?;
+		}
+		self.write_bytes()?;
+		self.writer.flush().map_err(|e| s!(WRITE_ERROR_MSG) + &e.to_string())
+	}
+
+	/// Consumes the sink and returns the writer. Call flush_with_padding before, since bits,
+	/// which have not been flushed, are lost.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+
+	// Passes the complete bytes to the writer
+	fn write_bytes(&mut self) -> Result<()> {
+		self.writer.write_all(&self.bytes).map_err(|e| s!(WRITE_ERROR_MSG) + &e.to_string())?;
+		self.bytes.clear();
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io;
+
+	// Accepts at most limit bytes
+	struct Limited {
+		bytes: Vec<u8>,
+		limit: usize,
+	}
+
+	impl Write for Limited {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			if self.bytes.len() + buf.len() > self.limit {
+				return Err(io::Error::other("full"));
+			}
+			self.bytes.extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn writing_to_a_stream() {
+		let mut sink = BitSink::new(Vec::new());
+		sink.write_bit(true).unwrap();
+		sink.write_bits(0x0123_4567_89AB_CDEF, 64).unwrap();
+		sink.write_bits(0, 0).unwrap();
+		assert!(sink.write_bits(0, 65).is_err());
+		assert_eq!(sink.bit_len(), 65);
+		sink.flush_with_padding(true).unwrap();
+		assert_eq!(sink.bit_len(), 72);
+		// Flushing again does not add padding
+		sink.flush_with_padding(true).unwrap();
+		assert_eq!(sink.into_inner(), vec!{ 0x80, 0x91, 0xA2, 0xB3, 0xC4, 0xD5, 0xE6, 0xF7, 0xFF });
+
+		// The complete bytes are passed to the writer in chunks
+		let mut sink = BitSink::new(Limited { bytes: Vec::new(), limit: 2 * CHUNK_SIZE });
+		for i in 0..2 * CHUNK_SIZE {
+			sink.write_bits(i as u64, 8).unwrap();
+		}
+		assert_eq!(sink.writer.bytes.len(), 2 * CHUNK_SIZE);
+		sink.write_bits(1, 1).unwrap();
+		assert!(sink.flush_with_padding(false).unwrap_err().starts_with(WRITE_ERROR_MSG));
+	}
+}
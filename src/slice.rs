@@ -0,0 +1,52 @@
+//! Range checked extraction and insertion on byte slices, shared by the foreign language interfaces
+
+use super::*;
+
+// Validates the parameters and returns the absolute bit position
+fn check_access(len: usize, byte_offset: u32, bit_offset: u32, length: u32) -> Result<usize> {
+	if length == 0 { return Err(s!(LEN_ZERO)); }
+	if length > 64 { return Err(s!(LEN_TOO_BIG_MSG) + "u64"); }
+
+	range_start(len, byte_offset, bit_offset, length as usize)
+}
+
+/// Same as get_u64 for a Vec<u8>
+pub fn get_u64(source: &[u8], byte_offset: u32, bit_offset: u32, length: u32) -> Result<u64> {
+	let start = check_access(source.len(), byte_offset, bit_offset, length)?;
+	Ok(read_bits_from_slice(source, start, length))
+}
+
+/// Same as get_i64 for a Vec<u8>
+pub fn get_i64(source: &[u8], byte_offset: u32, bit_offset: u32, length: u32) -> Result<i64> {
+	let bits = get_u64(source, byte_offset, bit_offset, length)?;
+	// Sign extension
+	Ok(((bits << (64 - length)) as i64) >> (64 - length))
+}
+
+/// Same as set for a Vec<u8> and a u64 value
+pub fn set_u64(target: &mut [u8], byte_offset: u32, bit_offset: u32, length: u32, value: u64) -> Result<()> {
+	let start = check_access(target.len(), byte_offset, bit_offset, length)?;
+
+	let n = n_required_bits_for_an_unsigned_int(value);
+	if n > length {
+		return Err(format!("Failed to insert {} as a {} bit unsigned integer variable, since it requires at least {} bits.",
+			value, length, n));
+	}
+
+	write_bits_to_slice(target, start, length, value);
+	Ok(())
+}
+
+/// Same as set for a Vec<u8> and an i64 value
+pub fn set_i64(target: &mut [u8], byte_offset: u32, bit_offset: u32, length: u32, value: i64) -> Result<()> {
+	let start = check_access(target.len(), byte_offset, bit_offset, length)?;
+
+	let n = n_required_bits_for_a_signed_int(value);
+	if n > length {
+		return Err(format!("Failed to insert {} as a {} bit signed integer variable, since it requires at least {} bits.",
+			value, length, n));
+	}
+
+	write_bits_to_slice(target, start, length, value as u64);
+	Ok(())
+}
@@ -0,0 +1,237 @@
+//! Sequential reading of bit fields from any io::Read
+
+use std::io::{ErrorKind, Read};
+use super::{Result, OUT_OF_RANGE_MSG, LEN_TOO_BIG_MSG, read_bits_from_slice};
+
+static READ_ERROR_MSG: &str = "Failed to read from the source: ";
+
+// The number of bytes, which are requested from the reader at once
+const CHUNK_SIZE: usize = 4096;
+
+/// Reads bit fields one after the other from an io::Read like a File, starting at the **most**
+/// significant bit of the first byte. Only the bytes, which have not been read completely,
+/// are kept in memory.
+///
+/// Like the BitReader, a failing read does not move the position. Wrapping the reader in a
+/// BufReader is not necessary, since the source requests chunks of 4 KiB.
+///
+/// ```rust
+/// use bitlab::*;
+/// // The logical screen descriptor of a GIF file: width, height and the packed byte
+/// let file = std::io::Cursor::new(vec!{ 0x0A, 0x00, 0x0A, 0x00, 0x91, 0x00, 0x00 });
+/// let mut source = BitSource::new(file);
+/// source.skip_bits(32).unwrap();
+/// assert!(source.read_bit().unwrap());
+/// assert_eq!(source.read_bits(3).unwrap(), 1);
+/// assert_eq!(source.position(), 36);
+/// ```
+#[derive(Debug)]
+pub struct BitSource<R: Read> {
+	reader: R,
+	buffer: Vec<u8>,
+	// The position within the buffer
+	bit_pos: usize,
+	// The number of bits, which have been dropped from the front of the buffer
+	dropped: usize,
+	eof: bool,
+}
+
+impl<R: Read> BitSource<R> {
+	/// Creates a source, which reads from the reader
+	pub fn new(reader: R) -> BitSource<R> {
+		BitSource { reader, buffer: Vec::new(), bit_pos: 0, dropped: 0, eof: false }
+	}
+
+	/// Returns the number of bits read so far
+	pub fn position(&self) -> usize {
+		self.dropped + self.bit_pos
+	}
+
+	/// Returns true if all bits of the reader have been read
+	pub fn is_at_end(&mut self) -> Result<bool> {
+		Ok(!self.fill(1)?)
+	}
+
+	/// Consumes the source and returns the reader. The bytes, which have been buffered but not
+	/// read completely, are lost.
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+
+	// Reads from the reader until at least bits bits are buffered. Returns false if the reader
+	// ends before.
+	fn fill(&mut self, bits: usize) -> Result<bool> {
+		while self.buffer.len() * 8 - self.bit_pos < bits && !self.eof {
+			// Drop the bytes, which have been read completely
+			let done = self.bit_pos / 8;
+			if done > 0 {
+				self.buffer.drain(..done);
+				self.bit_pos -= done * 8;
+				self.dropped += done * 8;
+			}
+
+			let old_len = self.buffer.len();
+			self.buffer.resize(old_len + CHUNK_SIZE, 0);
+			let result = self.reader.read(&mut self.buffer[old_len..]);
+			match result {
+				Ok(n) => {
+					self.buffer.truncate(old_len + n);
+					self.eof = n == 0;
+				},
+				Err(ref e) if e.kind() == ErrorKind::Interrupted => self.buffer.truncate(old_len),
+				Err(e) => {
+					self.buffer.truncate(old_len);
+					return Err(s!(READ_ERROR_MSG) + &e.to_string());
+				},
+			}
+		}
+		Ok(self.buffer.len() * 8 - self.bit_pos >= bits)
+	}
+
+	/// Reads a single bit
+	pub fn read_bit(&mut self) -> Result<bool> {
+		Ok(self.read_bits(1)? == 1)
+	}
+
+	/// Reads length bits (at most 64) and returns them right aligned in a u64
+	pub fn read_bits(&mut self, length: u32) -> Result<u64> {
+		if length > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		if !self.fill(length as usize)? {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		let value = read_bits_from_slice(&self.buffer, self.bit_pos, length);
+		self.bit_pos += length as usize;
+		Ok(value)
+	}
+
+	/// Reads length bits (at most 64) as a two's complement signed integer
+	pub fn read_signed_bits(&mut self, length: u32) -> Result<i64> {
+		let value = self.read_bits(length)?;
+		if length == 0 {
+			return Ok(0);
+		}
+		// Sign extension
+		Ok(((value << (64 - length)) as i64) >> (64 - length))
+	}
+
+	/// Skips n bits. The whole bytes, which are skipped, are discarded instead of buffered.
+	/// Therefore, unlike a failing read, a failing skip moves the position to where the reader
+	/// ended or failed.
+	pub fn skip_bits(&mut self, n: usize) -> Result<()> {
+		let buffered = self.buffer.len() * 8 - self.bit_pos;
+		if n <= buffered {
+			self.bit_pos += n;
+			return Ok(());
+		}
+
+		// Drop the buffer and discard the whole bytes after it
+		let mut rest = n - buffered;
+		self.dropped += self.buffer.len() * 8;
+		self.buffer.clear();
+		self.bit_pos = 0;
+		let mut scratch = [0u8; CHUNK_SIZE];
+		while rest >= 8 && !self.eof {
+			let bytes = std::cmp::min(rest / 8, CHUNK_SIZE);
+			match self.reader.read(&mut scratch[..bytes]) {
+				Ok(0) => self.eof = true,
+				Ok(k) => {
+					rest -= k * 8;
+					self.dropped += k * 8;
+				},
+				Err(ref e) if e.kind() == ErrorKind::Interrupted => {},
+				Err(e) => return Err(s!(READ_ERROR_MSG) + &e.to_string()),
+			}
+		}
+
+		// Only the partial byte is buffered
+		if !self.fill(rest)? {
+			self.bit_pos = self.buffer.len() * 8;
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		self.bit_pos += rest;
+		Ok(())
+	}
+
+	/// Skips the bits up to the next byte boundary
+	pub fn align_to_byte(&mut self) -> Result<()> {
+		self.skip_bits((8 - self.position() % 8) % 8)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io;
+
+	// Returns one byte per call and is interrupted before every other byte
+	struct Trickle {
+		bytes: Vec<u8>,
+		position: usize,
+		interrupt: bool,
+	}
+
+	impl Read for Trickle {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.interrupt = !self.interrupt;
+			if self.interrupt {
+				return Err(io::Error::new(ErrorKind::Interrupted, "interrupted"));
+			}
+			if self.position == self.bytes.len() {
+				return Ok(0);
+			}
+			buf[0] = self.bytes[self.position];
+			self.position += 1;
+			Ok(1)
+		}
+	}
+
+	struct Broken;
+
+	impl Read for Broken {
+		fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+			Err(io::Error::other("broken"))
+		}
+	}
+
+	#[test]
+	fn reading_from_a_stream() {
+		let mut s = BitSource::new(Trickle { bytes: vec!{ 0x12, 0x34, 0x56, 0x78, 0x9A }, position: 0, interrupt: false });
+		assert_eq!(s.read_bits(4).unwrap(), 0x1);
+		assert_eq!(s.read_bits(24).unwrap(), 0x23_4567);
+		assert_eq!(s.position(), 28);
+		assert_eq!(s.read_signed_bits(4).unwrap(), -8);
+		assert_eq!(s.read_bits(9).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(s.position(), 32);
+		assert!(s.read_bit().unwrap());
+		s.align_to_byte().unwrap();
+		assert!(s.is_at_end().unwrap());
+		assert!(s.skip_bits(1).is_err());
+		assert!(s.read_bits(65).is_err());
+		assert_eq!(s.into_inner().position, 5);
+
+		// More than one chunk
+		let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+		let mut s = BitSource::new(io::Cursor::new(data));
+		s.skip_bits(4).unwrap();
+		for i in 0..9_999u32 {
+			assert_eq!(s.read_bits(8).unwrap(), ((i as u8) << 4 | (i + 1) as u8 >> 4) as u64);
+		}
+		assert_eq!(s.read_bits(4).unwrap(), 0x0F);
+
+		// Skipped bytes are not buffered
+		let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+		let mut s = BitSource::new(Trickle { bytes: data, position: 0, interrupt: false });
+		s.skip_bits(4).unwrap();
+		s.skip_bits(9_000 * 8).unwrap();
+		assert_eq!(s.buffer.len(), 1);
+		assert_eq!(s.position(), 72_004);
+		assert_eq!(s.read_bits(8).unwrap(), 0x82);
+		assert_eq!(s.skip_bits(8_000).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(s.position(), 80_000);
+
+		let mut s = BitSource::new(Broken);
+		assert!(s.read_bit().unwrap_err().starts_with(READ_ERROR_MSG));
+	}
+}
@@ -0,0 +1,132 @@
+//! Bit stuffing as used by HDLC, PPP in synchronous mode and USB
+//!
+//! After a run of a configurable number of identical bits, the transmitter inserts the opposite
+//! stuff bit, so that the data never looks like a flag sequence and the receiver sees enough
+//! transitions. The receiver removes these bits again.
+//!
+//! ```rust
+//! use bitlab::*;
+//! use bitlab::stuffing::BitStuffing;
+//! // Six ones would look like the HDLC flag 0x7E
+//! let data = BitBuffer::from_bytes(vec!{ 0b0111_1110 }, 8).unwrap();
+//! let stuffed = BitStuffing::HDLC.stuff(&data);
+//! assert_eq!(stuffed.bit_len(), 9);
+//! assert_eq!(stuffed.read_bits(0, 9).unwrap(), 0b0_1111_1010);
+//! assert_eq!(BitStuffing::HDLC.destuff(&stuffed).unwrap(), data);
+//! ```
+
+use super::{Result, BitBuffer, LEN_ZERO};
+
+static MISSING_STUFF_BIT_MSG: &str = "Missing stuff bit at bit ";
+
+/// Describes the bit stuffing rule: A stuff bit is inserted after run_length consecutive
+/// bits, which are the opposite of the stuff bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitStuffing {
+	run_length: u32,
+	stuff_bit: bool,
+}
+
+impl BitStuffing {
+	/// HDLC and PPP: A zero is inserted after five consecutive ones
+	pub const HDLC: BitStuffing = BitStuffing { run_length: 5, stuff_bit: false };
+
+	/// Creates a rule, which inserts the stuff bit after run_length consecutive bits of the opposite value.
+	/// Returns an error if run_length is zero.
+	pub fn new(run_length: u32, stuff_bit: bool) -> Result<BitStuffing> {
+		if run_length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		Ok(BitStuffing { run_length, stuff_bit })
+	}
+
+	/// Returns the number of consecutive bits, after which a stuff bit is inserted
+	pub fn run_length(&self) -> u32 {
+		self.run_length
+	}
+
+	/// Returns the value of the inserted bits
+	pub fn stuff_bit(&self) -> bool {
+		self.stuff_bit
+	}
+
+	/// Returns a copy of source with the stuff bits inserted. A stuff bit is also inserted
+	/// after a run at the very end of source.
+	pub fn stuff(&self, source: &BitBuffer) -> BitBuffer {
+		let mut result = BitBuffer::new();
+		let mut run = 0;
+		for bit in source.to_bools() {
+			result.push_bit(bit);
+			if bit == self.stuff_bit {
+				run = 0;
+			} else {
+				run += 1;
+				if run == self.run_length {
+					result.push_bit(self.stuff_bit);
+					run = 0;
+				}
+			}
+		}
+		result
+	}
+
+	/// Returns a copy of source with the stuff bits removed.
+	///
+	/// Returns an error if a run is not followed by a stuff bit, e.g. because it is a flag
+	/// or an abort sequence, or if the stuff bit after the last run is missing.
+	pub fn destuff(&self, source: &BitBuffer) -> Result<BitBuffer> {
+		let mut result = BitBuffer::new();
+		let mut run = 0;
+		let bits = source.to_bools();
+		let mut i = 0;
+		while i < bits.len() {
+			let bit = bits[i];
+			result.push_bit(bit);
+			if bit == self.stuff_bit {
+				run = 0;
+			} else {
+				run += 1;
+				if run == self.run_length {
+					// Skip the stuff bit
+					i += 1;
+					if bits.get(i) != Some(&self.stuff_bit) {
+						return Err(s!(MISSING_STUFF_BIT_MSG) + &i.to_string());
+					}
+					run = 0;
+				}
+			}
+			i += 1;
+		}
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stuffing_bits() {
+		let data = BitBuffer::from(vec!{ 0xFF, 0x7C, 0x00 });
+		let stuffed = BitStuffing::HDLC.stuff(&data);
+		// 11111 0 111 0 11111 0 00 00000000
+		assert_eq!(stuffed.bit_len(), 26);
+		assert_eq!(stuffed.read_bits(0, 26).unwrap(), 0x3EE_F800);
+		assert_eq!(BitStuffing::HDLC.destuff(&stuffed).unwrap(), data);
+
+		// A flag in the stuffed stream
+		let flag = BitBuffer::from(vec!{ 0x7E });
+		assert_eq!(BitStuffing::HDLC.destuff(&flag).unwrap_err(), "Missing stuff bit at bit 6");
+		let end = BitBuffer::from_bytes(vec!{ 0xF8 }, 5).unwrap();
+		assert!(BitStuffing::HDLC.destuff(&end).is_err());
+
+		// Insert a one after three zeros
+		let rule = BitStuffing::new(3, true).unwrap();
+		assert_eq!((rule.run_length(), rule.stuff_bit()), (3, true));
+		let zeros = BitBuffer::from(vec!{ 0x00 });
+		let stuffed = rule.stuff(&zeros);
+		assert_eq!(stuffed.read_bits(0, 10).unwrap(), 0b00_0100_0100);
+		assert_eq!(rule.destuff(&stuffed).unwrap(), zeros);
+		assert_eq!(BitStuffing::new(0, false).unwrap_err(), LEN_ZERO);
+	}
+}
@@ -0,0 +1,244 @@
+//! Text encodings for payloads of arbitrary bit length
+//!
+//! The hex (4 bits per symbol) and Base32 (5 bits per symbol, RFC 4648 alphabet)
+//! encodings below work on a [`BitBuffer`](../struct.BitBuffer.html), whose length is
+//! not necessarily a multiple of 8. If the last symbol contains padding bits, their number
+//! is appended after a slash, so the exact bit length survives the round trip:
+//!
+//! ```rust
+//! use bitlab::*;
+//! // 11 bits: 0110_1100_100
+//! let b = BitBuffer::from_bytes(vec!{ 0x6C, 0x80 }, 11).unwrap();
+//! let s = text::encode_hex(&b);
+//! assert_eq!(s, "6C8/1");
+//! assert_eq!(text::decode_hex(&s).unwrap(), b);
+//! ```
+
+use std::fmt::{self, Write};
+use super::{Result, BitBuffer, LEN_ZERO, LEN_TOO_BIG_MSG};
+
+static HEX_ALPHABET: &[u8; 16] = b"0123456789ABCDEF";
+static BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+static INVALID_SYMBOL_MSG: &str = "Invalid symbol: ";
+static INVALID_PADDING_MSG: &str = "Invalid padding";
+
+/// Encodes a buffer as upper case hex digits.
+/// A suffix "/n" is appended if the last digit contains n padding bits.
+pub fn encode_hex(source: &BitBuffer) -> String {
+	encode(source, 4, HEX_ALPHABET)
+}
+
+/// Decodes a string created by encode_hex. Both upper and lower case digits are accepted.
+/// Returns an error for an invalid padding suffix or if the padding bits are not zero.
+pub fn decode_hex(text: &str) -> Result<BitBuffer> {
+	decode(text, 4, HEX_ALPHABET)
+}
+
+/// Encodes a buffer using the RFC 4648 Base32 alphabet without the "=" padding.
+/// A suffix "/n" is appended if the last symbol contains n padding bits.
+pub fn encode_base32(source: &BitBuffer) -> String {
+	encode(source, 5, BASE32_ALPHABET)
+}
+
+/// Decodes a string created by encode_base32. Both upper and lower case symbols are accepted.
+/// Returns an error for an invalid padding suffix or if the padding bits are not zero.
+pub fn decode_base32(text: &str) -> Result<BitBuffer> {
+	decode(text, 5, BASE32_ALPHABET)
+}
+
+fn encode(source: &BitBuffer, width: usize, alphabet: &[u8]) -> String {
+	let n_symbols = source.bit_len().div_ceil(width);
+	let mut result = String::with_capacity(n_symbols + 3);
+
+	for i in 0..n_symbols {
+		let mut index = 0;
+		for j in i * width..(i + 1) * width {
+			// Bits beyond the end are padding and encoded as zero
+			index = (index << 1) | source.get_bit(j).unwrap_or(false) as usize;
+		}
+		result.push(alphabet[index] as char);
+	}
+
+	let n_padding = n_symbols * width - source.bit_len();
+	if n_padding > 0 {
+		result.push('/');
+		result.push_str(&n_padding.to_string());
+	}
+
+	result
+}
+
+/// Renders bytes as a classic hexdump with 16 bytes per line: the address, the bytes in hex
+/// and their printable ASCII characters. The first byte has the address first_address.
+///
+/// ```rust
+/// use bitlab::text::hexdump;
+/// assert_eq!(hexdump(b"Hallo", 0x10),
+///     "00000010  48 61 6C 6C 6F                                   Hallo\n");
+/// ```
+pub fn hexdump(data: &[u8], first_address: usize) -> String {
+	let mut text = String::new();
+
+	for (i, line) in data.chunks(16).enumerate() {
+		hexdump_line(&mut text, first_address + i * 16, line, |_| false);
+	}
+
+	text
+}
+
+// ANSI escape sequences for reverse video
+pub(crate) static REVERSE: &str = "\x1b[7m";
+pub(crate) static RESET: &str = "\x1b[0m";
+
+/// Appends one line of a hexdump with up to 16 bytes to text. The bytes, for whose index in
+/// the line reversed returns true, are shown in reverse video.
+pub(crate) fn hexdump_line<F: Fn(usize) -> bool>(text: &mut String, address: usize, line: &[u8], reversed: F) {
+	let _ = write!(text, "{:08X} ", address);
+	for (j, byte) in line.iter().enumerate() {
+		if reversed(j) {
+			let _ = write!(text, " {}{:02X}{}", REVERSE, byte, RESET);
+		} else {
+			let _ = write!(text, " {:02X}", byte);
+		}
+	}
+	text.push_str(&"   ".repeat(16 - line.len()));
+	text.push_str("  ");
+	text.extend(line.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+	text.push('\n');
+}
+
+/// Displays a value as exactly length binary digits, which are grouped from the right, e.g.
+/// a field of 5 bits as 0b0_0101. Unlike {:b}, the width of the field rather than the width
+/// of the type is shown. Negative values are shown in two's complement.
+///
+/// ```rust
+/// use bitlab::text::BitDisplay;
+/// assert_eq!(BitDisplay::new(5u8, 5).unwrap().to_string(), "0b0_0101");
+/// assert_eq!(BitDisplay::new(-3i32, 6).unwrap().with_group(3).to_string(), "0b111_101");
+/// assert_eq!(format!("{:#}", BitDisplay::new(0xA5u8, 8).unwrap()), "1010_0101");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitDisplay {
+	value: u128,
+	length: u32,
+	group: u32,
+}
+
+impl BitDisplay {
+	/// Displays the length (at most 128) least significant bits of value in groups of 4 bits.
+	/// Returns an error if length is zero or greater than 128.
+	pub fn new<T: num::cast::AsPrimitive<i128>>(value: T, length: u32) -> Result<BitDisplay> {
+		if length == 0 {
+			return Err(s!(LEN_ZERO));
+		}
+		if length > 128 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u128");
+		}
+		// Signed values are sign extended, so that their two's complement is shown
+		let value = value.as_() as u128;
+		let value = if length == 128 { value } else { value & ((1 << length) - 1) };
+		Ok(BitDisplay { value, length, group: 4 })
+	}
+
+	/// Sets the number of bits per group. Zero disables the grouping.
+	pub fn with_group(mut self, group: u32) -> BitDisplay {
+		self.group = group;
+		self
+	}
+}
+
+impl fmt::Display for BitDisplay {
+	/// Writes the prefix 0b followed by the digits. The alternate form {:#} omits the prefix.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if !f.alternate() {
+			f.write_str("0b")?;
+		}
+		for i in (0..self.length).rev() {
+			f.write_char(if self.value >> i & 1 == 1 { '1' } else { '0' })?;
+			if i > 0 && self.group > 0 && i % self.group == 0 {
+				f.write_char('_')?;
+			}
+		}
+		Ok(())
+	}
+}
+
+fn decode(text: &str, width: usize, alphabet: &[u8]) -> Result<BitBuffer> {
+	let (symbols, n_padding) = match text.find('/') {
+		Some(i) => match text[i + 1..].parse::<usize>() {
+			// Only the suffix, which encode appends, is accepted
+			Ok(n) if n > 0 && n < width && i > 0 && text[i + 1..] == n.to_string() => (&text[..i], n),
+			_ => return Err(s!(INVALID_PADDING_MSG)),
+		},
+		None => (text, 0),
+	};
+
+	let mut result = BitBuffer::new();
+	for c in symbols.chars() {
+		let index = match alphabet.iter().position(|&a| a as char == c.to_ascii_uppercase()) {
+			Some(i) => i,
+			None => return Err(s!(INVALID_SYMBOL_MSG) + &c.to_string()),
+		};
+		for j in (0..width).rev() {
+			result.push_bit(index & (1 << j) != 0);
+		}
+	}
+
+	let bit_len = result.bit_len() - n_padding;
+	if result.read_bits(bit_len, n_padding as u32)? != 0 {
+		return Err(s!(INVALID_PADDING_MSG));
+	}
+	BitBuffer::from_bytes(result.into_bytes(), bit_len)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn text_round_trips() {
+		let v = BitBuffer::from(vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F });
+		assert_eq!(encode_hex(&v), "48616C6C6F");
+		assert_eq!(encode_base32(&v), "JBQWY3DP");
+		assert_eq!(decode_base32("jbqwy3dp").unwrap(), v);
+
+		// 13 bits = 0100_1000_0110_0 --> Base32 0100_1 000_01 100 + 00 padding
+		let v = BitBuffer::from_bytes(vec!{ 0x48, 0x61 }, 13).unwrap();
+		assert_eq!(encode_hex(&v), "4860/3");
+		assert_eq!(encode_base32(&v), "JBQ/2");
+		assert_eq!(decode_hex("4860/3").unwrap(), v);
+		assert_eq!(decode_base32("JBQ/2").unwrap(), v);
+
+		assert_eq!(encode_hex(&BitBuffer::new()), "");
+		assert_eq!(decode_hex("").unwrap(), BitBuffer::new());
+
+		match decode_hex("4860/4") {
+			Ok(_) => panic!("Missed the padding check"),
+			Err(e) => assert_eq!(e, INVALID_PADDING_MSG),
+		}
+
+		// The padding bits must be zero and a suffix must not be zero or have leading zeros
+		for text in &["4860/0", "4861/3", "4860/03", "4860/+3", "/2"] {
+			assert_eq!(decode_hex(text).unwrap_err(), INVALID_PADDING_MSG);
+		}
+		assert_eq!(decode_base32("JBR/2").unwrap_err(), INVALID_PADDING_MSG);
+
+		match decode_base32("JB1") {
+			Ok(_) => panic!("Missed the symbol check"),
+			Err(e) => assert_eq!(e, s!(INVALID_SYMBOL_MSG) + "1"),
+		}
+	}
+
+	#[test]
+	fn displaying_bits() {
+		assert_eq!(BitDisplay::new(1u8, 1).unwrap().to_string(), "0b1");
+		assert_eq!(BitDisplay::new(0u16, 9).unwrap().to_string(), "0b0_0000_0000");
+		assert_eq!(BitDisplay::new(0x1FFu16, 8).unwrap().to_string(), "0b1111_1111");
+		assert_eq!(BitDisplay::new(-1i8, 12).unwrap().with_group(0).to_string(), "0b111111111111");
+		assert_eq!(format!("{:#}", BitDisplay::new(u128::MAX, 128).unwrap().with_group(64)),
+			format!("{}_{}", "1".repeat(64), "1".repeat(64)));
+		assert_eq!(BitDisplay::new(0u8, 0).unwrap_err(), LEN_ZERO);
+		assert!(BitDisplay::new(0u8, 129).is_err());
+	}
+}
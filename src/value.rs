@@ -0,0 +1,228 @@
+//! Values, whose type is only known at run time
+
+use std::fmt;
+use super::*;
+
+/// The type of a [`Value`](enum.Value.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+	/// Unsigned 8 bit integer
+	U8,
+	/// Unsigned 16 bit integer
+	U16,
+	/// Unsigned 32 bit integer
+	U32,
+	/// Unsigned 64 bit integer
+	U64,
+	/// Signed 8 bit integer
+	I8,
+	/// Signed 16 bit integer
+	I16,
+	/// Signed 32 bit integer
+	I32,
+	/// Signed 64 bit integer
+	I64,
+	/// IEEE 754 single precision float. The length must be 32 bits.
+	F32,
+	/// IEEE 754 double precision float. The length must be 64 bits.
+	F64,
+	/// A flag, which is true if any of its bits is set
+	Bool,
+	/// A sequence of bytes. The length must be a multiple of 8 bits.
+	Bytes,
+}
+
+/// A value extracted by get_dyn or inserted by set_dyn
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	/// Unsigned 8 bit integer
+	U8(u8),
+	/// Unsigned 16 bit integer
+	U16(u16),
+	/// Unsigned 32 bit integer
+	U32(u32),
+	/// Unsigned 64 bit integer
+	U64(u64),
+	/// Signed 8 bit integer
+	I8(i8),
+	/// Signed 16 bit integer
+	I16(i16),
+	/// Signed 32 bit integer
+	I32(i32),
+	/// Signed 64 bit integer
+	I64(i64),
+	/// IEEE 754 single precision float
+	F32(f32),
+	/// IEEE 754 double precision float
+	F64(f64),
+	/// A flag
+	Bool(bool),
+	/// A sequence of bytes
+	Bytes(Vec<u8>),
+}
+
+impl Value {
+	/// Returns the type of the value
+	pub fn kind(&self) -> ValueKind {
+		match self {
+			Value::U8(_)    => ValueKind::U8,
+			Value::U16(_)   => ValueKind::U16,
+			Value::U32(_)   => ValueKind::U32,
+			Value::U64(_)   => ValueKind::U64,
+			Value::I8(_)    => ValueKind::I8,
+			Value::I16(_)   => ValueKind::I16,
+			Value::I32(_)   => ValueKind::I32,
+			Value::I64(_)   => ValueKind::I64,
+			Value::F32(_)   => ValueKind::F32,
+			Value::F64(_)   => ValueKind::F64,
+			Value::Bool(_)  => ValueKind::Bool,
+			Value::Bytes(_) => ValueKind::Bytes,
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Value::U8(v)    => write!(f, "{}", v),
+			Value::U16(v)   => write!(f, "{}", v),
+			Value::U32(v)   => write!(f, "{}", v),
+			Value::U64(v)   => write!(f, "{}", v),
+			Value::I8(v)    => write!(f, "{}", v),
+			Value::I16(v)   => write!(f, "{}", v),
+			Value::I32(v)   => write!(f, "{}", v),
+			Value::I64(v)   => write!(f, "{}", v),
+			Value::F32(v)   => write!(f, "{}", v),
+			Value::F64(v)   => write!(f, "{}", v),
+			Value::Bool(v)  => write!(f, "{}", v),
+			Value::Bytes(v) => v.iter().try_for_each(|b| write!(f, "{:02X}", b)),
+		}
+	}
+}
+
+// Returns an error, if length does not fit to the kind
+fn check_length(kind: ValueKind, length: u32) -> Result<()> {
+	match kind {
+		ValueKind::F32 if length != 32 => Err(s!("The length of a f32 must be 32 bits")),
+		ValueKind::F64 if length != 64 => Err(s!("The length of a f64 must be 64 bits")),
+		ValueKind::Bytes if length == 0 => Err(s!(LEN_ZERO)),
+		ValueKind::Bytes if !length.is_multiple_of(8) => Err(s!("The length of bytes must be a multiple of 8 bits")),
+		_ => Ok(()),
+	}
+}
+
+/// Extracts and inserts values, whose type is described at run time (e.g. by a configuration file).
+///
+/// The trait is implemented for all types, which implement ExtractBitsFromVecU8 and InsertBitsIntoVecU8.
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+/// assert_eq!(v.get_dyn(1, 7, 3, ValueKind::U16).unwrap(), Value::U16(5));
+/// v.set_dyn(0, 0, 16, &Value::Bytes(vec!{ 0x4A, 0x6F })).unwrap();
+/// assert_eq!(v.get_dyn(0, 0, 40, ValueKind::Bytes).unwrap().to_string(), "4A6F6C6C6F");
+/// ```
+pub trait DynamicBits {
+	/// Extracts a range of bits and interprets them as the given kind
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip in source
+	/// - **bit_offset** (u32) the start position of the bits to be extracted. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be extracted.
+	/// - **kind** (ValueKind) the type of the result
+	fn get_dyn(&self, byte_offset: u32, bit_offset: u32, length: u32, kind: ValueKind) -> Result<Value>;
+
+	/// Inserts a value into a range of bits
+	///
+	/// Parameters:
+	///
+	/// - **byte_offset** (u32) the number of bytes to skip
+	/// - **bit_offset** (u32) the number of bits to skip. Zero is the most significant bit
+	/// - **length** (u32) the number of bits to be inserted.
+	/// - **value** (Value) the value to be inserted.
+	fn set_dyn(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: &Value) -> Result<()>;
+}
+
+impl<V> DynamicBits for V where V: ExtractBitsFromVecU8 + InsertBitsIntoVecU8 + ?Sized {
+	fn get_dyn(&self, byte_offset: u32, bit_offset: u32, length: u32, kind: ValueKind) -> Result<Value> {
+		check_length(kind, length)?;
+
+		Ok(match kind {
+			ValueKind::U8    => Value::U8(self.get_u8(byte_offset, bit_offset, length)?),
+			ValueKind::U16   => Value::U16(self.get_u16(byte_offset, bit_offset, length)?),
+			ValueKind::U32   => Value::U32(self.get_u32(byte_offset, bit_offset, length)?),
+			ValueKind::U64   => Value::U64(self.get_u64(byte_offset, bit_offset, length)?),
+			ValueKind::I8    => Value::I8(self.get_i8(byte_offset, bit_offset, length)?),
+			ValueKind::I16   => Value::I16(self.get_i16(byte_offset, bit_offset, length)?),
+			ValueKind::I32   => Value::I32(self.get_i32(byte_offset, bit_offset, length)?),
+			ValueKind::I64   => Value::I64(self.get_i64(byte_offset, bit_offset, length)?),
+			ValueKind::F32   => Value::F32(self.get_f32(byte_offset, bit_offset)?),
+			ValueKind::F64   => Value::F64(self.get_f64(byte_offset, bit_offset)?),
+			ValueKind::Bool  => Value::Bool(self.get_u64(byte_offset, bit_offset, length)? != 0),
+			ValueKind::Bytes => Value::Bytes(self.get_bits(byte_offset, bit_offset, length)?),
+		})
+	}
+
+	fn set_dyn(&mut self, byte_offset: u32, bit_offset: u32, length: u32, value: &Value) -> Result<()> {
+		check_length(value.kind(), length)?;
+
+		match *value {
+			Value::U8(v)    => self.set(byte_offset, bit_offset, length, v),
+			Value::U16(v)   => self.set(byte_offset, bit_offset, length, v),
+			Value::U32(v)   => self.set(byte_offset, bit_offset, length, v),
+			Value::U64(v)   => self.set(byte_offset, bit_offset, length, v),
+			Value::I8(v)    => self.set(byte_offset, bit_offset, length, v),
+			Value::I16(v)   => self.set(byte_offset, bit_offset, length, v),
+			Value::I32(v)   => self.set(byte_offset, bit_offset, length, v),
+			Value::I64(v)   => self.set(byte_offset, bit_offset, length, v),
+			Value::F32(v)   => self.set(byte_offset, bit_offset, length, v.to_bits()),
+			Value::F64(v)   => self.set(byte_offset, bit_offset, length, v.to_bits()),
+			Value::Bool(v)  => self.set(byte_offset, bit_offset, length, v as u64),
+			Value::Bytes(ref bytes) => {
+				if bytes.len() as u64 * 8 != length as u64 {
+					return Err(format!("{} bytes do not fit into {} bits", bytes.len(), length));
+				}
+				// Check the whole range first, so that nothing is modified on error
+				self.get_u8(byte_offset.checked_add(length / 8 - 1).ok_or_else(|| s!(OUT_OF_RANGE_MSG))?, bit_offset, 8)?;
+				for (i, &b) in bytes.iter().enumerate() {
+					self.set(byte_offset + i as u32, bit_offset, 8, b)?;
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dynamic_values() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F, 0x00, 0x00, 0x00, 0x00 };
+		assert_eq!(v.get_dyn(1, 7, 3, ValueKind::U8).unwrap(), Value::U8(5));
+		assert_eq!(v.get_dyn(1, 7, 3, ValueKind::I64).unwrap(), Value::I64(-3));
+		assert_eq!(v.get_dyn(0, 1, 1, ValueKind::Bool).unwrap(), Value::Bool(true));
+		assert_eq!(v.get_dyn(0, 4, 16, ValueKind::Bytes).unwrap(), Value::Bytes(vec!{ 0x86, 0x16 }));
+
+		v.set_dyn(5, 0, 32, &Value::F32(1.5)).unwrap();
+		assert_eq!(v.get_dyn(5, 0, 32, ValueKind::F32).unwrap(), Value::F32(1.5));
+		assert_eq!(&v[5..], &[0x3F, 0xC0, 0x00, 0x00]);
+
+		v.set_dyn(0, 4, 16, &Value::Bytes(vec!{ 0xAB, 0xCD })).unwrap();
+		assert_eq!(&v[..3], &[0x4A, 0xBC, 0xDC]);
+		v.set_dyn(0, 0, 3, &Value::Bool(true)).unwrap();
+		v.set_dyn(0, 3, 5, &Value::I8(-2)).unwrap();
+		assert_eq!(v[0], 0b0011_1110);
+
+		assert_eq!(v.get_dyn(0, 0, 31, ValueKind::F32).unwrap_err(), "The length of a f32 must be 32 bits");
+		assert_eq!(v.get_dyn(0, 0, 12, ValueKind::Bytes).unwrap_err(), "The length of bytes must be a multiple of 8 bits");
+		assert_eq!(v.set_dyn(0, 0, 8, &Value::Bytes(vec!{ 1, 2 })).unwrap_err(), "2 bytes do not fit into 8 bits");
+		assert_eq!(v.set_dyn(8, 1, 16, &Value::Bytes(vec!{ 1, 2 })).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(v[8], 0x00);
+
+		assert_eq!(Value::I16(-7).to_string(), "-7");
+		assert_eq!(Value::Bytes(vec!{ 0x0A, 0xFF }).to_string(), "0AFF");
+	}
+}
@@ -0,0 +1,111 @@
+//! JavaScript interface via wasm-bindgen (requires the feature **wasm**)
+//!
+//! The byte buffers are passed as Uint8Array and the 64 bit values as BigInt.
+//! Failing functions throw the error message as a string.
+//!
+//! ```js
+//! import { getUnsigned, setUnsigned, Layout } from "bitlab";
+//! const data = new Uint8Array([0x48, 0x61, 0x6C, 0x6C, 0x6F]);
+//! getUnsigned(data, 1, 7, 3); // 5n
+//! const layout = new Layout("version:u4, ihl:u4");
+//! layout.extract(data); // ["4", "8"]
+//! ```
+//!
+//! The crate has no dissector, which decodes nested protocols into a tree, so there is no binding
+//! for it. A Layout describes a flat sequence of fields.
+
+use wasm_bindgen::prelude::*;
+use super::{Result, Layout, slice, text};
+
+/// Extracts length bits (at most 64) as an unsigned integer
+#[wasm_bindgen(js_name = getUnsigned)]
+pub fn get_unsigned(data: &[u8], byte_offset: u32, bit_offset: u32, length: u32) -> Result<u64> {
+	slice::get_u64(data, byte_offset, bit_offset, length)
+}
+
+/// Extracts length bits (at most 64) as a two's complement signed integer
+#[wasm_bindgen(js_name = getSigned)]
+pub fn get_signed(data: &[u8], byte_offset: u32, bit_offset: u32, length: u32) -> Result<i64> {
+	slice::get_i64(data, byte_offset, bit_offset, length)
+}
+
+/// Inserts an unsigned value into length bits (at most 64). The array is modified in place.
+#[wasm_bindgen(js_name = setUnsigned)]
+pub fn set_unsigned(data: &mut [u8], byte_offset: u32, bit_offset: u32, length: u32, value: u64) -> Result<()> {
+	slice::set_u64(data, byte_offset, bit_offset, length, value)
+}
+
+/// Inserts a signed value as a two's complement integer into length bits (at most 64).
+/// The array is modified in place.
+#[wasm_bindgen(js_name = setSigned)]
+pub fn set_signed(data: &mut [u8], byte_offset: u32, bit_offset: u32, length: u32, value: i64) -> Result<()> {
+	slice::set_i64(data, byte_offset, bit_offset, length, value)
+}
+
+/// Renders the bytes as a hexdump, see [`text::hexdump`](../text/fn.hexdump.html)
+#[wasm_bindgen]
+pub fn hexdump(data: &[u8], first_address: usize) -> String {
+	text::hexdump(data, first_address)
+}
+
+/// A [`Layout`](../struct.Layout.html), which is available as Layout in JavaScript
+#[wasm_bindgen(js_name = Layout)]
+pub struct JsLayout {
+	layout: Layout,
+}
+
+#[wasm_bindgen(js_class = Layout)]
+impl JsLayout {
+	/// Parses a description like "version:u4, ihl:u4", see Layout::parse
+	#[wasm_bindgen(constructor)]
+	pub fn new(description: &str) -> Result<JsLayout> {
+		Ok(JsLayout { layout: Layout::parse(description)? })
+	}
+
+	/// Returns the total number of bits
+	#[wasm_bindgen(getter, js_name = bitLen)]
+	pub fn bit_len(&self) -> u32 {
+		self.layout.bit_len()
+	}
+
+	/// Returns the names of the fields in the order of the description
+	#[wasm_bindgen(js_name = fieldNames)]
+	pub fn field_names(&self) -> Vec<String> {
+		self.layout.fields().iter().map(|f| f.name.clone()).collect()
+	}
+
+	/// Extracts all fields from the start of data and returns the values in the order of the fields.
+	/// The values are rendered as text, where bytes are hex digits.
+	pub fn extract(&self, data: &[u8]) -> Result<Vec<String>> {
+		let values = self.layout.extract(data)?;
+		Ok(self.layout.fields().iter().map(|f| values[&f.name].to_string()).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::OUT_OF_RANGE_MSG;
+
+	#[test]
+	fn calling_the_javascript_interface() {
+		let mut v: Vec<u8> = vec!{ 0x48, 0x61, 0x6C, 0x6C, 0x6F };
+		assert_eq!(get_unsigned(&v, 1, 7, 3).unwrap(), 5);
+		assert_eq!(get_signed(&v, 1, 7, 3).unwrap(), -3);
+		assert_eq!(get_signed(&v, 0, 0, 64).unwrap_err(), OUT_OF_RANGE_MSG);
+
+		set_signed(&mut v, 0, 4, 4, -1).unwrap();
+		set_unsigned(&mut v, 4, 0, 8, 0x21).unwrap();
+		assert_eq!(v, vec!{ 0x4F, 0x61, 0x6C, 0x6C, 0x21 });
+		assert!(set_unsigned(&mut v, 0, 0, 3, 8).is_err());
+
+		assert!(hexdump(&v, 0).ends_with("Oall!\n"));
+
+		let layout = JsLayout::new("version:u4, ihl:u4, id:bytes2").unwrap();
+		assert_eq!(layout.bit_len(), 24);
+		assert_eq!(layout.field_names(), vec!{ "version", "ihl", "id" });
+		assert_eq!(layout.extract(&v).unwrap(), vec!{ "4", "15", "616C" });
+		assert!(layout.extract(&v[..2]).is_err());
+		assert!(JsLayout::new("version").is_err());
+	}
+}
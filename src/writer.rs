@@ -0,0 +1,352 @@
+//! Sequential writing of bit fields
+
+use super::{Result, BitBuffer, BitLength, OUT_OF_RANGE_MSG, LEN_TOO_BIG_MSG};
+use super::reader::{EXP_GOLOMB_MSG, EMPTY_ALPHABET_MSG};
+
+static NO_OPEN_SECTION_MSG: &str = "There is no open section";
+static RICE_QUOTIENT_MSG: &str = "The quotient of the Rice code exceeds BitWriter::MAX_UNARY";
+static UNARY_MSG: &str = "The unary code exceeds BitWriter::MAX_UNARY";
+
+/// Appends bit fields one after the other to a [`BitBuffer`](struct.BitBuffer.html).
+///
+/// The writer can account the written bits per labeled section, which shows where the
+/// bit budget of an encoder goes. Sections may be nested, in which case the bits are
+/// accounted for each of the open sections. Sections with the same label are summed up.
+///
+/// ```rust
+/// use bitlab::*;
+/// let mut w = BitWriter::new();
+/// w.begin_section("header");
+/// w.write_bits(0b101, 3).unwrap();
+/// w.end_section().unwrap();
+/// w.begin_section("payload");
+/// w.write_bits(0xFFFF, 16).unwrap();
+/// w.end_section().unwrap();
+/// assert_eq!(w.section_bits("header"), 3);
+/// assert_eq!(w.section_bits("payload"), 16);
+/// assert_eq!(w.bit_len(), 19);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BitWriter {
+	buffer: BitBuffer,
+	// The open sections with their start positions
+	open_sections: Vec<(String, usize)>,
+	// The totals per label in the order of their first use
+	totals: Vec<(String, usize)>,
+}
+
+impl BitWriter {
+	/// The largest value of a unary code and the largest quotient of a Rice code, which are written as
+	/// that many zeros. Larger values are rejected, since they are most likely caused by a wrong parameter.
+	pub const MAX_UNARY: u64 = 1 << 20;
+
+	/// Creates a writer with an empty buffer
+	pub fn new() -> BitWriter {
+		BitWriter::default()
+	}
+
+	/// Returns the number of bits written so far
+	pub fn bit_len(&self) -> usize {
+		self.buffer.bit_len()
+	}
+
+	/// Appends a single bit
+	pub fn write_bit(&mut self, bit: bool) {
+		self.buffer.push_bit(bit);
+	}
+
+	/// Appends the length (at most 64) least significant bits of value
+	pub fn write_bits(&mut self, value: u64, length: u32) -> Result<()> {
+		self.buffer.push_bits(value, length)
+	}
+
+	/// Appends the unary code of n, which is n zeros followed by a one.
+	/// Returns an error if n exceeds BitWriter::MAX_UNARY.
+	pub fn write_unary(&mut self, n: u64) -> Result<()> {
+		if n > BitWriter::MAX_UNARY {
+			return Err(s!(UNARY_MSG));
+		}
+		self.buffer.push_zeros(n as usize);
+		self.buffer.push_bit(true);
+		Ok(())
+	}
+
+	/// Appends the truncated binary code of a value smaller than the alphabet size n,
+	/// see BitReader::read_truncated_binary
+	pub fn write_truncated_binary(&mut self, value: u64, n: u64) -> Result<()> {
+		if n == 0 {
+			return Err(s!(EMPTY_ALPHABET_MSG));
+		}
+		if value >= n {
+			return Err(s!(OUT_OF_RANGE_MSG));
+		}
+		let k = 63 - n.leading_zeros();
+		let short_codes = ((1u128 << (k + 1)) - n as u128) as u64;
+		if value < short_codes {
+			self.buffer.push_bits(value, k)
+		} else {
+			self.buffer.push_bits(value + short_codes, k + 1)
+		}
+	}
+
+	/// Appends an unsigned Exp-Golomb code ue(v) as used in H.264 and H.265 headers.
+	/// The largest value is u64::MAX - 1.
+	pub fn write_ue(&mut self, value: u64) -> Result<()> {
+		let code = value.checked_add(1).ok_or_else(|| s!(EXP_GOLOMB_MSG))?;
+		// As many zeros as there are bits after the leading one of the code
+		let n = code.bit_length();
+		self.buffer.push_bits(0, n - 1)?;
+		self.buffer.push_bits(code, n)
+	}
+
+	/// Appends a signed Exp-Golomb code se(v) as used in H.264 and H.265 headers.
+	/// The values 1, -1, 2, -2 ... are written as the codes 1, 2, 3, 4 ... of ue(v).
+	pub fn write_se(&mut self, value: i64) -> Result<()> {
+		let magnitude = value.unsigned_abs();
+		if value > 0 {
+			self.write_ue(2 * magnitude - 1)
+		} else {
+			self.write_ue(magnitude.checked_mul(2).ok_or_else(|| s!(EXP_GOLOMB_MSG))?)
+		}
+	}
+
+	/// Appends a base 128 varint (LEB128) as used by protocol buffers, see BitReader::read_varint
+	pub fn write_varint(&mut self, mut value: u64) {
+		while value >= 0x80 {
+			self.push_byte((value & 0x7F) | 0x80);
+			value >>= 7;
+		}
+		self.push_byte(value);
+	}
+
+	/// Appends a zigzag encoded signed varint (sint64 of protocol buffers)
+	pub fn write_zigzag_varint(&mut self, value: i64) {
+		self.write_varint(((value << 1) ^ (value >> 63)) as u64);
+	}
+
+	/// Appends a Rice code with the parameter k (at most 64), see BitReader::read_rice.
+	/// The quotient value >> k is written as that many zeros. Returns an error if the
+	/// quotient exceeds BitWriter::MAX_UNARY, so k should keep it small.
+	pub fn write_rice(&mut self, value: u64, k: u32) -> Result<()> {
+		if k > 64 {
+			return Err(s!(LEN_TOO_BIG_MSG) + "u64");
+		}
+		let quotient = value.checked_shr(k).unwrap_or(0);
+		if quotient > BitWriter::MAX_UNARY {
+			return Err(s!(RICE_QUOTIENT_MSG));
+		}
+		self.write_unary(quotient)?;
+		self.buffer.push_bits(value, k)
+	}
+
+	/// Appends a signed Rice code with the parameter k (at most 64) as used in FLAC.
+	/// The values 0, -1, 1, -2, 2 ... are written as the unsigned values 0, 1, 2, 3, 4 ...
+	pub fn write_signed_rice(&mut self, value: i64, k: u32) -> Result<()> {
+		self.write_rice(((value << 1) ^ (value >> 63)) as u64, k)
+	}
+
+	// Appends the 8 least significant bits of value
+	fn push_byte(&mut self, value: u64) {
+		for i in (0..8).rev() {
+			self.buffer.push_bit(value & (1 << i) != 0);
+		}
+	}
+
+	/// Returns the bits written so far
+	pub fn as_buffer(&self) -> &BitBuffer {
+		&self.buffer
+	}
+
+	/// Consumes the writer and returns the written bits
+	pub fn into_buffer(self) -> BitBuffer {
+		self.buffer
+	}
+
+	/// Starts accounting the written bits for the section label
+	pub fn begin_section(&mut self, label: &str) {
+		self.open_sections.push((s!(label), self.buffer.bit_len()));
+	}
+
+	/// Ends the section opened last and adds its bits to the total of its label
+	pub fn end_section(&mut self) -> Result<()> {
+		let (label, start) = match self.open_sections.pop() {
+			Some(section) => section,
+			None => return Err(s!(NO_OPEN_SECTION_MSG)),
+		};
+
+		let n = self.buffer.bit_len() - start;
+		match self.totals.iter_mut().find(|(l, _)| *l == label) {
+			Some(total) => total.1 += n,
+			None => self.totals.push((label, n)),
+		}
+		Ok(())
+	}
+
+	/// Returns the number of bits written in all closed sections with the given label
+	pub fn section_bits(&self, label: &str) -> usize {
+		self.totals.iter().find(|(l, _)| l == label).map_or(0, |(_, n)| *n)
+	}
+
+	/// Returns the totals of all closed sections in the order, in which the labels were first closed
+	pub fn sections(&self) -> &[(String, usize)] {
+		&self.totals
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::BitReader;
+
+	#[test]
+	fn accounting_of_sections() {
+		let mut w = BitWriter::new();
+		w.write_bit(true); // Not accounted
+
+		w.begin_section("frame");
+		for i in 0..3 {
+			w.begin_section("header");
+			w.write_bits(i, 4).unwrap();
+			w.end_section().unwrap();
+			w.begin_section("payload");
+			w.write_bits(0x48, 8).unwrap();
+			w.write_bit(false);
+			w.end_section().unwrap();
+		}
+		w.end_section().unwrap();
+
+		assert_eq!(w.section_bits("header"), 12);
+		assert_eq!(w.section_bits("payload"), 27);
+		assert_eq!(w.section_bits("frame"), 39);
+		assert_eq!(w.section_bits("trailer"), 0);
+		assert_eq!(w.sections(), &[(s!("header"), 12), (s!("payload"), 27), (s!("frame"), 39)]);
+		assert_eq!(w.bit_len(), 40);
+
+		assert_eq!(w.end_section().unwrap_err(), NO_OPEN_SECTION_MSG);
+
+		let b = w.into_buffer();
+		assert_eq!(b.read_bits(0, 14).unwrap(), 0b10_0000_1001_0000);
+	}
+
+	#[test]
+	fn exp_golomb_round_trips() {
+		let mut w = BitWriter::new();
+		w.write_ue(0).unwrap();
+		w.write_ue(3).unwrap();
+		w.write_se(-2).unwrap();
+		assert_eq!(w.as_buffer().read_bits(0, 11).unwrap(), 0b1_00100_00101);
+
+		let values = [0, 1, 2, 254, 255, 1 << 40, u64::MAX - 1];
+		for &v in values.iter() {
+			w.write_ue(v).unwrap();
+		}
+		for &v in [i64::MAX, -i64::MAX, 7, -7].iter() {
+			w.write_se(v).unwrap();
+		}
+		assert_eq!(w.write_ue(u64::MAX).unwrap_err(), EXP_GOLOMB_MSG);
+		assert_eq!(w.write_se(i64::MIN).unwrap_err(), EXP_GOLOMB_MSG);
+
+		let buffer = w.into_buffer();
+		let mut r = BitReader::from(&buffer);
+		assert_eq!(r.read_ue().unwrap(), 0);
+		assert_eq!(r.read_ue().unwrap(), 3);
+		assert_eq!(r.read_se().unwrap(), -2);
+		for &v in values.iter() {
+			assert_eq!(r.read_ue().unwrap(), v);
+		}
+		for &v in [i64::MAX, -i64::MAX, 7, -7].iter() {
+			assert_eq!(r.read_se().unwrap(), v);
+		}
+		assert_eq!(r.remaining_bits(), 0);
+	}
+
+	#[test]
+	fn varint_round_trips() {
+		let mut w = BitWriter::new();
+		w.write_bit(true);
+		w.write_varint(300);
+		assert_eq!(w.as_buffer().read_bits(1, 16).unwrap(), 0xAC02);
+
+		let values = [0, 1, 127, 128, 1 << 35, u64::MAX];
+		for &v in values.iter() {
+			w.write_varint(v);
+		}
+		let signed = [0, -1, 1, -64, 64, i64::MIN, i64::MAX];
+		for &v in signed.iter() {
+			w.write_zigzag_varint(v);
+		}
+
+		let buffer = w.into_buffer();
+		let mut r = BitReader::from(&buffer);
+		r.skip_bits(1).unwrap();
+		assert_eq!(r.read_varint().unwrap(), 300);
+		for &v in values.iter() {
+			assert_eq!(r.read_varint().unwrap(), v);
+		}
+		for &v in signed.iter() {
+			assert_eq!(r.read_zigzag_varint().unwrap(), v);
+		}
+		assert_eq!(r.remaining_bits(), 0);
+	}
+
+	#[test]
+	fn rice_round_trips() {
+		let mut w = BitWriter::new();
+		w.write_rice(14, 2).unwrap();
+		assert_eq!(w.as_buffer().read_bits(0, 6).unwrap(), 0b00_0110);
+		assert!(w.write_rice(1, 65).is_err());
+		assert_eq!(w.write_rice(u64::MAX, 0).unwrap_err(), RICE_QUOTIENT_MSG);
+		assert_eq!(w.write_rice((BitWriter::MAX_UNARY + 1) << 3, 3).unwrap_err(), RICE_QUOTIENT_MSG);
+		assert_eq!(w.bit_len(), 6);
+
+		let signed = [0, -1, 1, -7, 100, -100];
+		for &v in signed.iter() {
+			w.write_signed_rice(v, 3).unwrap();
+		}
+		w.write_rice(u64::MAX, 64).unwrap();
+		w.write_rice(5, 0).unwrap();
+
+		let buffer = w.into_buffer();
+		let mut r = BitReader::from(&buffer);
+		assert_eq!(r.read_rice(2).unwrap(), 14);
+		for &v in signed.iter() {
+			assert_eq!(r.read_signed_rice(3).unwrap(), v);
+		}
+		assert_eq!(r.read_rice(64).unwrap(), u64::MAX);
+		assert_eq!(r.read_rice(0).unwrap(), 5);
+		assert_eq!(r.remaining_bits(), 0);
+	}
+
+	#[test]
+	fn unary_and_truncated_binary_round_trips() {
+		let mut w = BitWriter::new();
+		w.write_unary(3).unwrap();
+		w.write_truncated_binary(4, 5).unwrap();
+		assert_eq!(w.as_buffer().read_bits(0, 7).unwrap(), 0b000_1111);
+		assert_eq!(w.write_truncated_binary(5, 5).unwrap_err(), OUT_OF_RANGE_MSG);
+		assert_eq!(w.write_truncated_binary(0, 0).unwrap_err(), EMPTY_ALPHABET_MSG);
+		assert_eq!(w.write_unary(u64::MAX).unwrap_err(), UNARY_MSG);
+
+		let alphabets = [1, 2, 3, 7, 10, 64, u64::MAX];
+		for &n in alphabets.iter() {
+			w.write_truncated_binary(0, n).unwrap();
+			w.write_truncated_binary(n / 2, n).unwrap();
+			w.write_truncated_binary(n - 1, n).unwrap();
+			w.write_unary(n % 5).unwrap();
+		}
+		w.write_unary(100).unwrap();
+
+		let buffer = w.into_buffer();
+		let mut r = BitReader::from(&buffer);
+		assert_eq!(r.read_unary().unwrap(), 3);
+		assert_eq!(r.read_truncated_binary(5).unwrap(), 4);
+		for &n in alphabets.iter() {
+			assert_eq!(r.read_truncated_binary(n).unwrap(), 0);
+			assert_eq!(r.read_truncated_binary(n).unwrap(), n / 2);
+			assert_eq!(r.read_truncated_binary(n).unwrap(), n - 1);
+			assert_eq!(r.read_unary().unwrap(), n % 5);
+		}
+		assert_eq!(r.read_unary().unwrap(), 100);
+		assert_eq!(r.remaining_bits(), 0);
+	}
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "derive")]
+
+extern crate bitlab;
+
+use bitlab::BitLayout;
+
+#[derive(BitLayout, Debug, PartialEq)]
+struct Packet {
+	#[bits(3)] kind: u8,
+	urgent: bool,
+	#[bits(12)] length: u16,
+	#[bits(9)] offset: i16,
+	#[bits(40)] timestamp: u64,
+	#[bits(7)] crc: u8,
+}
+
+#[test]
+fn derived_layouts() {
+	assert_eq!(Packet::BIT_LEN, 72);
+	let p = Packet { kind: 5, urgent: true, length: 0xABC, offset: -2, timestamp: 0x12_3456_789A, crc: 0x55 };
+	let bytes = p.to_bytes().unwrap();
+	assert_eq!(bytes, vec!{ 0xBA, 0xBC, 0xFF, 0x09, 0x1A, 0x2B, 0x3C, 0x4D, 0x55 });
+	assert_eq!(Packet::from_bytes(&bytes).unwrap(), p);
+
+	assert!(Packet::from_bytes(&bytes[..8]).is_err());
+	let too_big = Packet { kind: 8, ..p };
+	assert!(too_big.to_bytes().is_err());
+}